@@ -1,24 +1,43 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use cosmwasm_std::{
     self as cw_std, attr, to_json_binary, AllBalanceResponse, BankMsg,
     BankQuery, Binary, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response,
-    StdResult,
+    StdError, StdResult,
 };
 use cw_std::Coin;
+use nibiru_std::wasm::{filter_coins, insert_validated, remove_addr};
 
 use crate::oper_perms::Permissions;
 use crate::{
-    msgs::{PermsStatus, QueryMsg},
+    msgs::{
+        Config, OperatorDetail, OperatorUsage, PermsStatus, QueryMsg,
+        RecoveryStatus,
+    },
     oper_perms,
-    state::{Log, IS_HALTED, LOGS, OPERATORS},
+    state::{
+        Log, Metadata, OperatorSpend, OutflowWindow, PendingRecovery, ALIASES,
+        AUTO_HALT_AFTER_BLOCKS, DISABLED_OPERATORS, EVENT_PREFIX,
+        GLOBAL_OUTFLOW_CAP, IS_HALTED, KEY_HORIZON, LAST_ACTIVITY_HEIGHT, LOGS,
+        METADATA, MIN_SEND, OPERATORS, OPERATOR_LIMITS, OPERATOR_SPEND,
+        OUTFLOW_WINDOW, OUTFLOW_WINDOW_SECONDS, PENDING_RECOVERY, RECEIVED_BY,
+        RECOVERY, SEEN_KEYS, WITHDRAW_ADDRS,
+    },
 };
 
 use cw2::set_contract_version;
 
 use crate::{
     error::ContractError,
-    events::{event_bank_send, event_toggle_halt, event_withdraw},
+    events::{
+        event_auto_halt, event_bank_send, event_finalize_recovery,
+        event_initiate_recovery, event_prune_keys, event_set_alias,
+        event_set_auto_halt, event_set_global_cap, event_set_key_horizon,
+        event_set_metadata, event_set_min_send, event_set_operator_enabled,
+        event_set_operator_limit, event_set_operators, event_set_withdraw_addrs,
+        event_toggle_halt, event_veto_recovery, event_withdraw,
+        event_withdraw_cw20, with_event_prefix,
+    },
     msgs::{ExecuteMsg, InstantiateMsg},
     state::TO_ADDRS,
 };
@@ -26,7 +45,7 @@ use crate::{
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -39,6 +58,20 @@ pub fn instantiate(
     TO_ADDRS.save(deps.storage, &msg.to_addrs)?;
     OPERATORS.save(deps.storage, &msg.opers)?;
     IS_HALTED.save(deps.storage, &false)?;
+    LAST_ACTIVITY_HEIGHT.save(deps.storage, &env.block.height)?;
+    if let Some(recovery) = &msg.recovery {
+        RECOVERY.save(deps.storage, recovery)?;
+    }
+    METADATA.save(
+        deps.storage,
+        &Metadata {
+            label: msg.label,
+            description: msg.description,
+        },
+    )?;
+    if let Some(event_prefix) = &msg.event_prefix {
+        EVENT_PREFIX.save(deps.storage, event_prefix)?;
+    }
     Ok(Response::default())
 }
 
@@ -50,22 +83,65 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let contract_addr = env.contract.address.to_string();
-    match msg {
-        ExecuteMsg::BankSend { coins, to } => {
-            bank_send(deps, env, info, coins, to)
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?;
+    let res = match msg {
+        ExecuteMsg::BankSend {
+            coins,
+            to,
+            idempotency_key,
+        } => bank_send(deps, env, info, coins, to, idempotency_key),
+        ExecuteMsg::SetAlias { name, address } => {
+            set_alias(deps, env, info, name, address)
+        }
+        ExecuteMsg::SetGlobalCap { cap } => set_global_cap(deps, env, info, cap),
+        ExecuteMsg::SetOperatorEnabled { operator, enabled } => {
+            set_operator_enabled(deps, env, info, operator, enabled)
+        }
+        ExecuteMsg::SetOperatorLimit { operator, limit } => {
+            set_operator_limit(deps, env, info, operator, limit)
+        }
+        ExecuteMsg::SetWithdrawAddrs { addrs } => {
+            set_withdraw_addrs(deps, env, info, addrs)
+        }
+        ExecuteMsg::SetMinSend { denom, amount } => {
+            set_min_send(deps, env, info, denom, amount)
+        }
+        ExecuteMsg::SetKeyHorizon { seconds } => {
+            set_key_horizon(deps, env, info, seconds)
+        }
+        ExecuteMsg::PruneKeys { limit } => prune_keys(deps, env, info, limit),
+        ExecuteMsg::SetMetadata { label, description } => {
+            set_metadata(deps, env, info, label, description)
+        }
+        ExecuteMsg::SetAutoHalt { after_blocks } => {
+            set_auto_halt(deps, env, info, after_blocks)
+        }
+        ExecuteMsg::ToggleHalt { reason } => {
+            toggle_halt(deps, env, info, reason)
         }
-        ExecuteMsg::ToggleHalt {} => toggle_halt(deps, env, info),
         ExecuteMsg::UpdateOwnership(action) => {
             execute_update_ownership(deps, env, info, action)
         }
         ExecuteMsg::EditOpers(action) => edit_opers(deps, env, info, action),
-        ExecuteMsg::WithdrawAll { to } => {
-            withdraw_all(deps, env, info, to, contract_addr)
+        ExecuteMsg::SetOperators { add, remove } => {
+            set_operators(deps, env, info, add, remove)
+        }
+        ExecuteMsg::WithdrawAll { to, exclude_denoms } => {
+            withdraw_all(deps, env, info, to, exclude_denoms, contract_addr)
         }
         ExecuteMsg::Withdraw { to, denoms } => {
             withdraw(deps, env, info, to, denoms, contract_addr)
         }
-    }
+        ExecuteMsg::WithdrawCw20 {
+            contract,
+            to,
+            amount,
+        } => withdraw_cw20(deps, env, info, contract, to, amount),
+        ExecuteMsg::InitiateRecovery {} => initiate_recovery(deps, env, info),
+        ExecuteMsg::VetoRecovery {} => veto_recovery(deps, env, info),
+        ExecuteMsg::FinalizeRecovery {} => finalize_recovery(deps, env, info),
+    }?;
+    Ok(with_event_prefix(res, event_prefix.as_deref()))
 }
 
 pub fn withdraw(
@@ -81,13 +157,12 @@ pub fn withdraw(
         Some(given_to_addr) => given_to_addr,
         None => info.sender.to_string(),
     };
-    let balances: Vec<cw_std::Coin> =
-        query_bank_balances(contract_addr, deps.as_ref())?;
-    let balances: Vec<cw_std::Coin> = balances
-        .iter()
-        .filter(|b_coin| denoms.contains(&b_coin.denom))
-        .cloned()
-        .collect();
+    assert_withdraw_addr(deps.storage, &to_addr)?;
+    let balances: Vec<cw_std::Coin> = filter_coins(
+        &query_bank_balances(contract_addr, deps.as_ref())?,
+        &denoms,
+    );
+    record_outflow(deps.storage, &env, &balances)?;
 
     let tx_msg = BankMsg::Send {
         to_address: to_addr.to_string(),
@@ -101,6 +176,8 @@ pub fn withdraw(
             block_height: env.block.height,
             sender_addr: info.sender.to_string(),
             event: event.clone(),
+            post_balance: None,
+            is_emergency: false,
         },
     )?;
     Ok(Response::new().add_message(tx_msg).add_event(event))
@@ -111,6 +188,7 @@ pub fn withdraw_all(
     env: Env,
     info: MessageInfo,
     to: Option<String>,
+    exclude_denoms: Option<Vec<String>>,
     contract_addr: String,
 ) -> Result<Response, ContractError> {
     nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
@@ -118,7 +196,13 @@ pub fn withdraw_all(
         Some(given_to_addr) => given_to_addr,
         None => info.sender.to_string(),
     };
-    let balances = query_bank_balances(contract_addr, deps.as_ref())?;
+    assert_withdraw_addr(deps.storage, &to_addr)?;
+    let balances: Vec<Coin> = query_simulate_withdraw_all(
+        deps.as_ref(),
+        contract_addr,
+        exclude_denoms,
+    )?;
+    record_outflow(deps.storage, &env, &balances)?;
     let tx_msg = BankMsg::Send {
         to_address: to_addr.to_string(),
         amount: balances.clone(),
@@ -131,6 +215,64 @@ pub fn withdraw_all(
             block_height: env.block.height,
             sender_addr: info.sender.to_string(),
             event: event.clone(),
+            post_balance: None,
+            is_emergency: true,
+        },
+    )?;
+    Ok(Response::new().add_message(tx_msg).add_event(event))
+}
+
+pub fn withdraw_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_contract: String,
+    to: Option<String>,
+    amount: Option<cw_std::Uint128>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    let cw20_contract = deps.api.addr_validate(&cw20_contract)?.into_string();
+    let to_addr: String = match to {
+        Some(given_to_addr) => given_to_addr,
+        None => info.sender.to_string(),
+    };
+    assert_withdraw_addr(deps.storage, &to_addr)?;
+    let amount = match amount {
+        Some(amount) => amount,
+        None => {
+            let resp: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                &cw20_contract,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            resp.balance
+        }
+    };
+    if amount.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "no {cw20_contract} tokens to withdraw"
+        ))
+        .into());
+    }
+
+    let tx_msg = cw_std::WasmMsg::Execute {
+        contract_addr: cw20_contract.clone(),
+        msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+            recipient: to_addr.clone(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+    let event = event_withdraw_cw20(&cw20_contract, &amount, &to_addr);
+    LOGS.push_front(
+        deps.storage,
+        &Log {
+            block_height: env.block.height,
+            sender_addr: info.sender.to_string(),
+            event: event.clone(),
+            post_balance: None,
+            is_emergency: false,
         },
     )?;
     Ok(Response::new().add_message(tx_msg).add_event(event))
@@ -146,38 +288,499 @@ pub fn edit_opers(
     let mut perms = Permissions::load(deps.storage)?;
     match action {
         oper_perms::Action::AddOper { address } => {
-            perms.operators.insert(address.clone());
+            let changed =
+                insert_validated(deps.api, &mut perms.operators, &address)?;
             OPERATORS.save(deps.storage, &perms.operators)?;
 
             let res = Response::new().add_attributes(vec![
                 attr("action", "add_operator"),
                 attr("address", address),
+                attr("changed", changed.to_string()),
             ]);
             Ok(res)
         }
 
         oper_perms::Action::RemoveOper { address } => {
-            perms.operators.remove(address.as_str());
+            let changed = remove_addr(&mut perms.operators, &address);
             OPERATORS.save(deps.storage, &perms.operators)?;
 
             let res = Response::new().add_attributes(vec![
                 attr("action", "remove_operator"),
                 attr("address", address),
+                attr("changed", changed.to_string()),
             ]);
             Ok(res)
         }
     }
 }
 
-pub fn toggle_halt(
+pub fn set_operators(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    let add: BTreeSet<String> = add.into_iter().collect();
+    let remove: BTreeSet<String> = remove.into_iter().collect();
+    if let Some(address) = add.intersection(&remove).next() {
+        return Err(ContractError::ConflictingOperatorEdit {
+            address: address.clone(),
+        });
+    }
+    let mut operators = OPERATORS.load(deps.storage)?;
+    for address in &remove {
+        remove_addr(&mut operators, address);
+    }
+    for address in &add {
+        insert_validated(deps.api, &mut operators, address)?;
+    }
+    OPERATORS.save(deps.storage, &operators)?;
+
+    Ok(Response::new().add_event(event_set_operators(
+        &serde_json::to_string(&add)?,
+        &serde_json::to_string(&remove)?,
+        &serde_json::to_string(&operators)?,
+    )))
+}
+
+pub fn set_alias(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+    address: String,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    if !TO_ADDRS.load(deps.storage)?.contains(&address) {
+        return Err(ContractError::ToAddrNotAllowed { to_addr: address });
+    }
+    ALIASES.save(deps.storage, &name, &address)?;
+    Ok(Response::new().add_event(event_set_alias(&name, &address)))
+}
+
+pub fn set_global_cap(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    cap: Option<Coin>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    GLOBAL_OUTFLOW_CAP.save(deps.storage, &cap)?;
+    // Amounts sent under a previous cap do not count against a new one.
+    OUTFLOW_WINDOW.remove(deps.storage);
+    Ok(Response::new().add_event(event_set_global_cap(cap.as_ref())))
+}
+
+pub fn set_operator_enabled(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    if !OPERATORS.load(deps.storage)?.contains(&operator) {
+        return Err(ContractError::NoOperatorPerms { addr: operator });
+    }
+    let mut disabled = DISABLED_OPERATORS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if enabled {
+        disabled.remove(&operator);
+    } else {
+        disabled.insert(operator.clone());
+    }
+    DISABLED_OPERATORS.save(deps.storage, &disabled)?;
+    Ok(
+        Response::new()
+            .add_event(event_set_operator_enabled(&operator, enabled)),
+    )
+}
+
+/// Fails with `ContractError::OperatorDisabled` if the owner disabled
+/// `operator`.
+fn assert_operator_enabled(
+    storage: &dyn cw_std::Storage,
+    operator: &str,
+) -> Result<(), ContractError> {
+    let disabled = DISABLED_OPERATORS.may_load(storage)?.unwrap_or_default();
+    if disabled.contains(operator) {
+        return Err(ContractError::OperatorDisabled {
+            addr: operator.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn set_operator_limit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+    limit: Option<Coin>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    match &limit {
+        Some(limit) => OPERATOR_LIMITS.save(deps.storage, &operator, limit)?,
+        None => OPERATOR_LIMITS.remove(deps.storage, &operator),
+    }
+    Ok(Response::new()
+        .add_event(event_set_operator_limit(&operator, limit.as_ref())))
+}
+
+pub fn set_withdraw_addrs(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    addrs: Option<BTreeSet<String>>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    for addr in addrs.iter().flatten() {
+        deps.api.addr_validate(addr)?;
+    }
+    WITHDRAW_ADDRS.save(deps.storage, &addrs)?;
+    Ok(Response::new()
+        .add_event(event_set_withdraw_addrs(&serde_json::to_string(&addrs)?)))
+}
+
+/// Fails with `ContractError::WithdrawAddrNotAllowed` if "WITHDRAW_ADDRS" is
+/// set and does not contain `to_addr`.
+fn assert_withdraw_addr(
+    storage: &dyn cw_std::Storage,
+    to_addr: &str,
+) -> Result<(), ContractError> {
+    match WITHDRAW_ADDRS.may_load(storage)?.flatten() {
+        Some(addrs) if !addrs.contains(to_addr) => {
+            Err(ContractError::WithdrawAddrNotAllowed {
+                to_addr: to_addr.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn set_min_send(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Option<cw_std::Uint128>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    match &amount {
+        Some(amount) => MIN_SEND.save(deps.storage, &denom, amount)?,
+        None => MIN_SEND.remove(deps.storage, &denom),
+    }
+    Ok(Response::new().add_event(event_set_min_send(&denom, amount.as_ref())))
+}
+
+/// Errors if any of `coins` is below the minimum send amount of its denom.
+fn assert_above_min_send(
+    storage: &dyn cw_std::Storage,
+    coins: &[Coin],
+) -> Result<(), ContractError> {
+    for coin in coins {
+        if let Some(min) = MIN_SEND.may_load(storage, &coin.denom)? {
+            if coin.amount < min {
+                return Err(ContractError::BelowMinSend {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount,
+                    min,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn set_key_horizon(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    KEY_HORIZON.save(deps.storage, &seconds)?;
+    Ok(Response::new().add_event(event_set_key_horizon(seconds)))
+}
+
+/// Default and maximum number of idempotency keys removed by one `PruneKeys`.
+const DEFAULT_PRUNE_LIMIT: u32 = 100;
+const MAX_PRUNE_LIMIT: u32 = 500;
+
+/// Returns true if an idempotency key first used at `seen_at` has outlived the
+/// configured "KEY_HORIZON".
+fn is_key_expired(
+    storage: &dyn cw_std::Storage,
+    env: &Env,
+    seen_at: u64,
+) -> StdResult<bool> {
+    Ok(match KEY_HORIZON.may_load(storage)?.flatten() {
+        Some(horizon) => {
+            env.block.time.seconds() >= seen_at.saturating_add(horizon)
+        }
+        None => false,
+    })
+}
+
+/// Records `key` in "SEEN_KEYS", failing if it was used before and has not
+/// yet expired.
+fn record_idempotency_key(
+    storage: &mut dyn cw_std::Storage,
+    env: &Env,
+    key: &str,
+) -> Result<(), ContractError> {
+    if let Some(seen_at) = SEEN_KEYS.may_load(storage, key)? {
+        if !is_key_expired(storage, env, seen_at)? {
+            return Err(ContractError::DuplicateRequest {
+                key: key.to_string(),
+            });
+        }
+    }
+    SEEN_KEYS.save(storage, key, &env.block.time.seconds())?;
+    Ok(())
+}
+
+/// Removes up to `limit` expired idempotency keys. This is permissionless, so
+/// `info` is unused: an expired key would be accepted again by `BankSend`
+/// anyway, and without a horizon set by the owner nothing is pruned.
+pub fn prune_keys(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_PRUNE_LIMIT).min(MAX_PRUNE_LIMIT);
+    let mut expired: Vec<String> = vec![];
+    for entry in
+        SEEN_KEYS.range(deps.storage, None, None, cw_std::Order::Ascending)
+    {
+        let (key, seen_at) = entry?;
+        if is_key_expired(deps.storage, &env, seen_at)? {
+            expired.push(key);
+            if expired.len() >= limit as usize {
+                break;
+            }
+        }
+    }
+    for key in &expired {
+        SEEN_KEYS.remove(deps.storage, key);
+    }
+    Ok(Response::new().add_event(event_prune_keys(expired.len())))
+}
+
+pub fn set_metadata(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
+    label: Option<String>,
+    description: Option<String>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    let metadata = Metadata { label, description };
+    METADATA.save(deps.storage, &metadata)?;
+    Ok(Response::new().add_event(event_set_metadata(
+        metadata.label.as_deref().unwrap_or_default(),
+        metadata.description.as_deref().unwrap_or_default(),
+    )))
+}
+
+/// Returns the label, description, and event prefix of the contract.
+/// Contracts instantiated before metadata was added have neither label nor
+/// description.
+pub fn query_config(deps: Deps) -> StdResult<Config> {
+    let metadata = METADATA.may_load(deps.storage)?.unwrap_or_default();
+    Ok(Config {
+        label: metadata.label,
+        description: metadata.description,
+        event_prefix: EVENT_PREFIX.may_load(deps.storage)?,
+    })
+}
+
+/// Adds the outflow of `coins` to the current window of the
+/// `GLOBAL_OUTFLOW_CAP`, starting a new window if the previous one has ended.
+/// Fails with `ContractError::GlobalCapExceeded` if the cap would be exceeded.
+/// A no-op when no cap is set.
+fn record_outflow(
+    storage: &mut dyn cw_std::Storage,
+    env: &Env,
+    coins: &[Coin],
+) -> Result<(), ContractError> {
+    let Some(cap) = GLOBAL_OUTFLOW_CAP.may_load(storage)?.flatten() else {
+        return Ok(());
+    };
+    let mut window = current_outflow_window(storage, env)?;
+    for coin in coins.iter().filter(|coin| coin.denom == cap.denom) {
+        window.total = window
+            .total
+            .checked_add(coin.amount)
+            .map_err(StdError::from)?;
+    }
+    if window.total > cap.amount {
+        return Err(ContractError::GlobalCapExceeded {
+            cap: cap.to_string(),
+            denom: cap.denom,
+            window_total: window.total,
+        });
+    }
+    OUTFLOW_WINDOW.save(storage, &window)?;
+    Ok(())
+}
+
+/// Loads the current window of the `GLOBAL_OUTFLOW_CAP`, or an empty window
+/// starting now if the previous one has ended.
+fn current_outflow_window(
+    storage: &dyn cw_std::Storage,
+    env: &Env,
+) -> StdResult<OutflowWindow> {
+    let now = env.block.time.seconds();
+    Ok(match OUTFLOW_WINDOW.may_load(storage)? {
+        Some(window) if now < window.start + OUTFLOW_WINDOW_SECONDS => window,
+        _ => OutflowWindow {
+            start: now,
+            total: Default::default(),
+        },
+    })
+}
+
+/// Loads the operator's spend in its current window, or an empty spend for a
+/// window starting now if the previous one has ended.
+fn current_operator_spend(
+    storage: &dyn cw_std::Storage,
+    env: &Env,
+    operator: &str,
+) -> StdResult<OperatorSpend> {
+    let now = env.block.time.seconds();
+    Ok(match OPERATOR_SPEND.may_load(storage, operator)? {
+        Some(spend) if now < spend.start + OUTFLOW_WINDOW_SECONDS => spend,
+        _ => OperatorSpend {
+            start: now,
+            spent: vec![],
+        },
+    })
+}
+
+/// Adds `coins` to the operator's spend in its current window. Fails with
+/// `ContractError::OperatorLimitExceeded` if that exceeds the operator's limit.
+fn record_operator_spend(
+    storage: &mut dyn cw_std::Storage,
+    env: &Env,
+    operator: &str,
+    coins: &[Coin],
+) -> Result<(), ContractError> {
+    let mut spend = current_operator_spend(storage, env, operator)?;
+    let mut spent = cw_std::Coins::default();
+    for coin in spend.spent.into_iter().chain(coins.iter().cloned()) {
+        spent.add(coin)?;
+    }
+    if let Some(limit) = OPERATOR_LIMITS.may_load(storage, operator)? {
+        let window_total = spent.amount_of(&limit.denom);
+        if window_total > limit.amount {
+            return Err(ContractError::OperatorLimitExceeded {
+                operator: operator.to_string(),
+                limit: limit.to_string(),
+                denom: limit.denom,
+                window_total,
+            });
+        }
+    }
+    spend.spent = spent.into_vec();
+    OPERATOR_SPEND.save(storage, operator, &spend)?;
+    Ok(())
+}
+
+/// Adds `coins` to the lifetime total received by `to`.
+fn record_received(
+    storage: &mut dyn cw_std::Storage,
+    to: &str,
+    coins: &[Coin],
+) -> Result<(), ContractError> {
+    let mut received = cw_std::Coins::default();
+    for coin in RECEIVED_BY
+        .may_load(storage, to)?
+        .unwrap_or_default()
+        .into_iter()
+        .chain(coins.iter().cloned())
+    {
+        received.add(coin)?;
+    }
+    RECEIVED_BY.save(storage, to, &received.into_vec())?;
+    Ok(())
+}
+
+pub fn set_auto_halt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    after_blocks: Option<u64>,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    AUTO_HALT_AFTER_BLOCKS.save(deps.storage, &after_blocks)?;
+    // The inactivity window starts when auto-halt is configured.
+    LAST_ACTIVITY_HEIGHT.save(deps.storage, &env.block.height)?;
+    Ok(Response::new().add_event(event_set_auto_halt(after_blocks)))
+}
+
+/// Marks an operator action at the current block, which restarts the auto-halt
+/// inactivity window. Called by every operator-gated handler.
+fn record_operator_activity(
+    storage: &mut dyn cw_std::Storage,
+    env: &Env,
+) -> StdResult<()> {
+    LAST_ACTIVITY_HEIGHT.save(storage, &env.block.height)
+}
+
+/// Returns the last activity height and the number of blocks since then if
+/// the contract has been inactive for longer than "AUTO_HALT_AFTER_BLOCKS".
+fn stale_activity(
+    storage: &dyn cw_std::Storage,
+    env: &Env,
+) -> StdResult<Option<(u64, u64)>> {
+    let Some(after_blocks) = AUTO_HALT_AFTER_BLOCKS.may_load(storage)?.flatten()
+    else {
+        return Ok(None);
+    };
+    // Contracts instantiated before activity was tracked start tracking on
+    // their next send.
+    let Some(last_activity) = LAST_ACTIVITY_HEIGHT.may_load(storage)? else {
+        return Ok(None);
+    };
+    let idle_blocks = env.block.height.saturating_sub(last_activity);
+    Ok((idle_blocks > after_blocks).then_some((last_activity, idle_blocks)))
+}
+
+pub fn toggle_halt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reason: Option<String>,
 ) -> Result<Response, ContractError> {
     nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
     let new_is_halted = !IS_HALTED.load(deps.storage)?;
     IS_HALTED.save(deps.storage, &new_is_halted)?;
-    Ok(Response::new().add_event(event_toggle_halt(&new_is_halted)))
+    if !new_is_halted {
+        // Lifting a halt restarts the auto-halt inactivity window.
+        LAST_ACTIVITY_HEIGHT.save(deps.storage, &env.block.height)?;
+    }
+    let event = event_toggle_halt(
+        &new_is_halted,
+        info.sender.as_str(),
+        reason.as_deref(),
+        env.block.height,
+    );
+    LOGS.push_front(
+        deps.storage,
+        &Log {
+            block_height: env.block.height,
+            sender_addr: info.sender.to_string(),
+            event: event.clone(),
+            post_balance: None,
+            is_emergency: new_is_halted,
+        },
+    )?;
+    Ok(Response::new().add_event(event))
 }
 
 pub fn bank_send(
@@ -186,29 +789,82 @@ pub fn bank_send(
     info: MessageInfo,
     coins: Vec<cw_std::Coin>,
     to: String,
+    idempotency_key: Option<String>,
 ) -> Result<Response, ContractError> {
     // assert sender is operator
     Permissions::assert_operator(deps.storage, info.sender.to_string())?;
+    assert_operator_enabled(deps.storage, info.sender.as_str())?;
     // assert: Operator execute calls should not be halted.
     let is_halted = IS_HALTED.load(deps.storage)?;
     assert_not_halted(is_halted)?;
 
+    // A stale contract halts itself instead of sending. This is not an error
+    // so that the halt is persisted.
+    if let Some((last_activity, idle_blocks)) =
+        stale_activity(deps.storage, &env)?
+    {
+        IS_HALTED.save(deps.storage, &true)?;
+        let event = event_auto_halt(&last_activity, &idle_blocks);
+        LOGS.push_front(
+            deps.storage,
+            &Log {
+                block_height: env.block.height,
+                sender_addr: info.sender.to_string(),
+                event: event.clone(),
+                post_balance: None,
+                is_emergency: true,
+            },
+        )?;
+        return Ok(Response::new()
+            .add_attribute("sent", "false")
+            .add_attribute("halted", "true")
+            .add_event(event));
+    }
+    record_operator_activity(deps.storage, &env)?;
+
+    // Resolve a registered alias to its address.
+    let to = ALIASES.may_load(deps.storage, &to)?.unwrap_or(to);
+    if to == env.contract.address.as_str() {
+        return Err(ContractError::SelfSend { addr: to });
+    }
+
     // assert: Recipient addr must be in the TO_ADDRS set.
     if !TO_ADDRS.load(deps.storage)?.contains(&to) {
         return Err(ContractError::ToAddrNotAllowed {
             to_addr: to.to_string(),
         });
     }
+    assert_above_min_send(deps.storage, &coins)?;
+    if let Some(key) = &idempotency_key {
+        record_idempotency_key(deps.storage, &env, key)?;
+    }
+    record_outflow(deps.storage, &env, &coins)?;
+    record_operator_spend(deps.storage, &env, info.sender.as_str(), &coins)?;
+    record_received(deps.storage, &to, &coins)?;
 
     // Events and tx history logging
+    let post_balance = query_post_send_balances(
+        deps.as_ref(),
+        env.contract.address.as_str(),
+        &coins,
+    )?;
     let coins_json = serde_json::to_string(&coins)?;
-    let event = event_bank_send(&coins_json, info.sender.as_str());
+    let mut event = event_bank_send(
+        &coins_json,
+        info.sender.as_str(),
+        &serde_json::to_string(&post_balance)?,
+    );
+    if let Some(key) = idempotency_key {
+        event = event.add_attribute("idempotency_key", key);
+    }
     LOGS.push_front(
         deps.storage,
         &Log {
             block_height: env.block.height,
             sender_addr: info.sender.to_string(),
             event: event.clone(),
+            post_balance: Some(post_balance),
+            is_emergency: false,
         },
     )?;
 
@@ -218,38 +874,148 @@ pub fn bank_send(
             to_address: to,
             amount: coins,
         })
+        .add_attribute("sent", "true")
         .add_event(event))
 }
 
-pub fn execute_update_ownership(
+/// Loads the recovery config and asserts that `sender` is the recovery address.
+fn assert_recovery_addr(
+    storage: &dyn cw_std::Storage,
+    sender: &str,
+) -> Result<String, ContractError> {
+    let recovery = RECOVERY
+        .may_load(storage)?
+        .ok_or(ContractError::RecoveryNotConfigured)?;
+    if recovery.addr != sender {
+        return Err(ContractError::NotRecoveryAddr {
+            addr: sender.to_string(),
+        });
+    }
+    Ok(recovery.addr)
+}
+
+pub fn initiate_recovery(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    action: nibiru_ownable::Action,
 ) -> Result<Response, ContractError> {
-    let ownership = nibiru_ownable::update_ownership(
-        deps,
-        &env.block,
-        info.sender.as_str(),
-        action,
+    let recovery_addr =
+        assert_recovery_addr(deps.storage, info.sender.as_str())?;
+    if PENDING_RECOVERY.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::RecoveryAlreadyPending);
+    }
+    let delay_blocks = RECOVERY.load(deps.storage)?.delay_blocks;
+    let finalize_height = cw_std::Uint64::new(env.block.height)
+        .checked_add(delay_blocks.into())
+        .map_err(StdError::from)?;
+    let pending = PendingRecovery {
+        initiated_height: env.block.height,
+        finalize_height: finalize_height.u64(),
+    };
+    PENDING_RECOVERY.save(deps.storage, &pending)?;
+
+    let event =
+        event_initiate_recovery(&recovery_addr, &pending.finalize_height);
+    LOGS.push_front(
+        deps.storage,
+        &Log {
+            block_height: env.block.height,
+            sender_addr: info.sender.to_string(),
+            event: event.clone(),
+            post_balance: None,
+            is_emergency: false,
+        },
     )?;
-    Ok(Response::new().add_attributes(ownership.into_attributes()))
+    Ok(Response::new().add_event(event))
 }
 
-pub fn assert_not_halted(is_halted: bool) -> Result<(), ContractError> {
-    match is_halted {
-        true => Err(ContractError::OperationsHalted),
-        false => Ok(()),
+pub fn veto_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+    if PENDING_RECOVERY.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingRecovery);
     }
-}
+    PENDING_RECOVERY.remove(deps.storage);
 
-pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
-pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+    let event = event_veto_recovery(info.sender.as_str());
+    LOGS.push_front(
+        deps.storage,
+        &Log {
+            block_height: env.block.height,
+            sender_addr: info.sender.to_string(),
+            event: event.clone(),
+            post_balance: None,
+            is_emergency: false,
+        },
+    )?;
+    Ok(Response::new().add_event(event))
+}
+
+pub fn finalize_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let recovery_addr =
+        assert_recovery_addr(deps.storage, info.sender.as_str())?;
+    let pending = PENDING_RECOVERY
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingRecovery)?;
+    if env.block.height < pending.finalize_height {
+        return Err(ContractError::RecoveryTimelocked {
+            finalize_height: pending.finalize_height,
+        });
+    }
+    PENDING_RECOVERY.remove(deps.storage);
+    // Overwrites the owner and clears any pending ownership transfer.
+    nibiru_ownable::initialize_owner(deps.storage, Some(&recovery_addr))?;
+
+    let event = event_finalize_recovery(&recovery_addr);
+    LOGS.push_front(
+        deps.storage,
+        &Log {
+            block_height: env.block.height,
+            sender_addr: info.sender.to_string(),
+            event: event.clone(),
+            post_balance: None,
+            is_emergency: false,
+        },
+    )?;
+    Ok(Response::new().add_event(event))
+}
+
+pub fn execute_update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: nibiru_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = nibiru_ownable::update_ownership(
+        deps,
+        &env.block,
+        info.sender.as_str(),
+        action,
+    )?;
+    Ok(Response::new().add_attributes(ownership.into_attributes()))
+}
+
+pub fn assert_not_halted(is_halted: bool) -> Result<(), ContractError> {
+    match is_halted {
+        true => Err(ContractError::OperationsHalted),
+        false => Ok(()),
+    }
+}
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
     msg: QueryMsg,
 ) -> Result<Binary, ContractError> {
     match msg {
@@ -260,13 +1026,77 @@ pub fn query(
         QueryMsg::Ownership {} => Ok(to_json_binary(
             &nibiru_ownable::get_ownership(deps.storage)?,
         )?),
+        QueryMsg::Recovery {} => {
+            Ok(to_json_binary(&query_recovery_status(deps)?)?)
+        }
+        QueryMsg::OperatorUsage { operator } => Ok(to_json_binary(
+            &query_operator_usage(deps, &env, &operator)?,
+        )?),
+        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::ReceivedBy { to } => {
+            Ok(to_json_binary(&query_received_by(deps, &to)?)?)
+        }
+        QueryMsg::SimulateWithdrawAll { exclude_denoms } => {
+            Ok(to_json_binary(&query_simulate_withdraw_all(
+                deps,
+                env.contract.address.to_string(),
+                exclude_denoms,
+            )?)?)
+        }
+        QueryMsg::OperatorsDetailed {} => {
+            Ok(to_json_binary(&query_operators_detailed(deps, &env)?)?)
+        }
+        QueryMsg::LogsBySender { sender, limit } => Ok(to_json_binary(
+            &query_logs_by_sender(deps, &sender, limit)?,
+        )?),
+        QueryMsg::EmergencyLogs { limit } => {
+            Ok(to_json_binary(&query_emergency_logs(deps, limit)?)?)
+        }
+        QueryMsg::IsAllowedRecipient { to } => Ok(to_json_binary(
+            &query_is_allowed_recipient(deps, &env, to)?,
+        )?),
     }
 }
 
+/// Returns the coins `WithdrawAll` sends: every balance of `contract_addr`
+/// except those in `exclude_denoms`.
+pub fn query_simulate_withdraw_all(
+    deps: Deps,
+    contract_addr: String,
+    exclude_denoms: Option<Vec<String>>,
+) -> StdResult<Vec<Coin>> {
+    let exclude_denoms: BTreeSet<String> =
+        exclude_denoms.unwrap_or_default().into_iter().collect();
+    Ok(query_bank_balances(contract_addr, deps)?
+        .into_iter()
+        .filter(|b_coin| !exclude_denoms.contains(&b_coin.denom))
+        .collect())
+}
+
 pub fn query_accepted_denoms(deps: Deps) -> StdResult<BTreeSet<String>> {
     TO_ADDRS.load(deps.storage)
 }
 
+/// Returns the balance of each denom in `coins` that `addr` will hold once
+/// `coins` are sent, ordered by denom.
+pub fn query_post_send_balances(
+    deps: Deps,
+    addr: &str,
+    coins: &[Coin],
+) -> StdResult<Vec<Coin>> {
+    let mut sent: BTreeMap<&str, cw_std::Uint128> = BTreeMap::new();
+    for coin in coins {
+        *sent.entry(coin.denom.as_str()).or_default() += coin.amount;
+    }
+    sent.into_iter()
+        .map(|(denom, amount)| {
+            let balance = deps.querier.query_balance(addr, denom)?;
+            // An insufficient balance fails the BankMsg::Send anyway.
+            Ok(Coin::new(balance.amount.saturating_sub(amount), denom))
+        })
+        .collect()
+}
+
 /// Query all bank balances or return an empty response.
 ///
 /// ```rust
@@ -306,6 +1136,115 @@ pub fn query_perms_status(deps: Deps) -> Result<PermsStatus, ContractError> {
     Ok(perms_status)
 }
 
+pub fn query_recovery_status(
+    deps: Deps,
+) -> Result<RecoveryStatus, ContractError> {
+    Ok(RecoveryStatus {
+        recovery: RECOVERY.may_load(deps.storage)?,
+        pending: PENDING_RECOVERY.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_operator_usage(
+    deps: Deps,
+    env: &Env,
+    operator: &str,
+) -> Result<OperatorUsage, ContractError> {
+    let spend = current_operator_spend(deps.storage, env, operator)?;
+    let (global_remaining, global_window_resets_at) =
+        match GLOBAL_OUTFLOW_CAP.may_load(deps.storage)?.flatten() {
+            Some(cap) => {
+                let window = current_outflow_window(deps.storage, env)?;
+                let remaining = Coin {
+                    amount: cap.amount.saturating_sub(window.total),
+                    denom: cap.denom,
+                };
+                (Some(remaining), Some(window.start + OUTFLOW_WINDOW_SECONDS))
+            }
+            None => (None, None),
+        };
+    Ok(OperatorUsage {
+        spent_this_window: spend.spent,
+        limit: OPERATOR_LIMITS.may_load(deps.storage, operator)?,
+        window_resets_at: spend.start + OUTFLOW_WINDOW_SECONDS,
+        global_remaining,
+        global_window_resets_at,
+    })
+}
+
+/// Returns the usage of every operator, ordered by address. An operator is
+/// enabled unless the owner disabled it. Halts are reported by `Perms`.
+pub fn query_operators_detailed(
+    deps: Deps,
+    env: &Env,
+) -> Result<Vec<OperatorDetail>, ContractError> {
+    let disabled = DISABLED_OPERATORS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    OPERATORS
+        .load(deps.storage)?
+        .into_iter()
+        .map(|address| {
+            let usage = query_operator_usage(deps, env, &address)?;
+            Ok(OperatorDetail {
+                enabled: !disabled.contains(&address),
+                address,
+                limit: usage.limit,
+                spent_this_window: usage.spent_this_window,
+            })
+        })
+        .collect()
+}
+
+/// Returns the lifetime total of coins sent to `to`. Aliases are not resolved,
+/// so `to` must be an address.
+pub fn query_received_by(deps: Deps, to: &str) -> StdResult<Vec<Coin>> {
+    Ok(RECEIVED_BY.may_load(deps.storage, to)?.unwrap_or_default())
+}
+
+/// Returns whether `BankSend` would accept `to`, an address or alias, as its
+/// recipient.
+pub fn query_is_allowed_recipient(
+    deps: Deps,
+    env: &Env,
+    to: String,
+) -> StdResult<bool> {
+    let to = ALIASES.may_load(deps.storage, &to)?.unwrap_or(to);
+    Ok(to != env.contract.address.as_str()
+        && TO_ADDRS.load(deps.storage)?.contains(&to))
+}
+
+/// Default and maximum number of logs returned by `LogsBySender` and
+/// `EmergencyLogs`.
+const DEFAULT_LOGS_LIMIT: u32 = 10;
+const MAX_LOGS_LIMIT: u32 = 100;
+
+/// Returns the most recent "LOGS" entries whose sender is `sender`, newest
+/// first.
+pub fn query_logs_by_sender(
+    deps: Deps,
+    sender: &str,
+    limit: Option<u32>,
+) -> StdResult<Vec<Log>> {
+    let limit = limit.unwrap_or(DEFAULT_LOGS_LIMIT).min(MAX_LOGS_LIMIT);
+    LOGS.iter(deps.storage)?
+        .filter(|log| log.as_ref().map_or(true, |log| log.sender_addr == sender))
+        .take(limit as usize)
+        .collect()
+}
+
+/// Returns the most recent "LOGS" entries of emergency actions, newest first.
+pub fn query_emergency_logs(
+    deps: Deps,
+    limit: Option<u32>,
+) -> StdResult<Vec<Log>> {
+    let limit = limit.unwrap_or(DEFAULT_LOGS_LIMIT).min(MAX_LOGS_LIMIT);
+    LOGS.iter(deps.storage)?
+        .filter(|log| log.as_ref().map_or(true, |log| log.is_emergency))
+        .take(limit as usize)
+        .collect()
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::collections::BTreeSet;
@@ -314,14 +1253,21 @@ pub mod tests {
     use cw_std::{
         from_json, testing, BankMsg, Coin, CosmosMsg, Response, SubMsg, Uint128,
     };
-    use nibiru_std::errors::TestResult;
+    use nibiru_std::{errors::TestResult, tutil::test_addr};
     use serde::Serialize;
 
     use crate::{
-        contract::{execute, query},
-        msgs::{ExecuteMsg, PermsStatus, QueryMsg},
+        contract::{execute, instantiate, query},
+        error::ContractError,
+        msgs::{
+            Config, ExecuteMsg, InstantiateMsg, OperatorDetail, OperatorUsage,
+            PermsStatus, QueryMsg, RecoveryStatus,
+        },
         oper_perms::{self, Permissions},
-        state::{IS_HALTED, OPERATORS},
+        state::{
+            Log, PendingRecovery, RecoveryConfig, IS_HALTED, LOGS, OPERATORS,
+            OUTFLOW_WINDOW_SECONDS, PENDING_RECOVERY, SEEN_KEYS,
+        },
         tutil::{
             self, mock_info_for_sender, setup_contract, setup_contract_defaults,
             TEST_OWNER,
@@ -391,7 +1337,78 @@ pub mod tests {
                 to_addrs: to_addrs.to_vec(),
                 opers: opers.to_vec(),
                 sender: not_owner,
-                exec_msg: ExecuteMsg::ToggleHalt {},
+                exec_msg: ExecuteMsg::WithdrawCw20 {
+                    contract: String::from("cw20_token"),
+                    to: None,
+                    amount: None,
+                },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::ToggleHalt { reason: None },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::SetAlias {
+                    name: String::from("bybit"),
+                    address: String::from("mm_bybit"),
+                },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::SetGlobalCap {
+                    cap: Some(Coin::new(100u128, "unibi")),
+                },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::SetMetadata {
+                    label: Some(String::from("label")),
+                    description: None,
+                },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::SetAutoHalt {
+                    after_blocks: Some(100),
+                },
+                err: want_err,
+                contract_funds_start: None,
+                resp_msgs: vec![],
+            },
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: not_owner,
+                exec_msg: ExecuteMsg::SetOperators {
+                    add: vec![],
+                    remove: vec![String::from("oper0")],
+                },
                 err: want_err,
                 contract_funds_start: None,
                 resp_msgs: vec![],
@@ -402,6 +1419,7 @@ pub mod tests {
                 sender: not_owner,
                 exec_msg: ExecuteMsg::WithdrawAll {
                     to: Some(String::from("mm_bybit")),
+                    exclude_denoms: None,
                 },
                 err: want_err,
                 contract_funds_start: None,
@@ -442,6 +1460,7 @@ pub mod tests {
                 sender: TEST_OWNER,
                 exec_msg: ExecuteMsg::WithdrawAll {
                     to: Some(String::from("mm_bybit")),
+                    exclude_denoms: None,
                 },
                 err: None,
                 contract_funds_start: None,
@@ -458,6 +1477,7 @@ pub mod tests {
                 sender: TEST_OWNER,
                 exec_msg: ExecuteMsg::WithdrawAll {
                     to: Some(String::from("to_addr")),
+                    exclude_denoms: None,
                 },
                 err: None,
                 contract_funds_start: Some(vec![Coin {
@@ -473,6 +1493,45 @@ pub mod tests {
                 }
                 .into()],
             },
+            // WithdrawAll / Exclude denoms
+            TestCaseExec {
+                to_addrs: to_addrs.to_vec(),
+                opers: opers.to_vec(),
+                sender: TEST_OWNER,
+                exec_msg: ExecuteMsg::WithdrawAll {
+                    to: Some(String::from("to_addr")),
+                    exclude_denoms: Some(vec![String::from("unibi")]),
+                },
+                err: None,
+                contract_funds_start: Some(vec![
+                    Coin {
+                        denom: "uatom".into(),
+                        amount: Uint128::from(7u128),
+                    },
+                    Coin {
+                        denom: "unibi".into(),
+                        amount: Uint128::from(420u128),
+                    },
+                    Coin {
+                        denom: "uusd".into(),
+                        amount: Uint128::from(69u128),
+                    },
+                ]),
+                resp_msgs: vec![BankMsg::Send {
+                    to_address: String::from("to_addr"),
+                    amount: vec![
+                        Coin {
+                            denom: "uatom".into(),
+                            amount: Uint128::from(7u128),
+                        },
+                        Coin {
+                            denom: "uusd".into(),
+                            amount: Uint128::from(69u128),
+                        },
+                    ],
+                }
+                .into()],
+            },
             // Withdraw / Nonzero amount
             TestCaseExec {
                 to_addrs: to_addrs.to_vec(),
@@ -586,7 +1645,7 @@ pub mod tests {
         );
 
         // ToggleHalt : error case
-        let exec_msg = ExecuteMsg::ToggleHalt {};
+        let exec_msg = ExecuteMsg::ToggleHalt { reason: None };
         let sender = "not_owner";
         let info = mock_info_for_sender(sender);
         let exec_resp =
@@ -623,7 +1682,7 @@ pub mod tests {
     #[test]
     fn exec_edit_opers_add() -> TestResult {
         let (mut deps, _env, _info) = tutil::setup_contract_defaults()?;
-        let new_member = "new_member";
+        let new_member = &test_addr(3);
         let perms = Permissions::load(&deps.storage)?;
         let not_has: bool = !perms.is_owner(new_member);
         assert!(not_has);
@@ -644,7 +1703,7 @@ pub mod tests {
             );
             assert_eq!(
                 resp.attributes.len(),
-                2,
+                3,
                 "resp.attributes: {:#?}",
                 resp.attributes
             );
@@ -667,6 +1726,90 @@ pub mod tests {
         let binary = query(deps.as_ref(), testing::mock_env(), query_req)?;
         let response: PermsStatus = cosmwasm_std::from_json(binary)?;
         assert!(response.perms.has(sender));
+
+        // Adding an operator twice changes nothing, and invalid addresses
+        // are rejected.
+        let add_oper = |address: &str| {
+            ExecuteMsg::EditOpers(oper_perms::Action::AddOper {
+                address: address.to_string(),
+            })
+        };
+        let res = execute(
+            deps.as_mut(),
+            testing::mock_env(),
+            mock_info_for_sender(sender),
+            add_oper(new_member),
+        )?;
+        assert_eq!(res.attributes[2], cw_std::attr("changed", "false"));
+        let res = execute(
+            deps.as_mut(),
+            testing::mock_env(),
+            mock_info_for_sender(sender),
+            add_oper("invalid"),
+        );
+        assert_eq!(res.unwrap_err().code(), "STD");
+        Ok(())
+    }
+
+    #[test]
+    fn exec_set_operators() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let new_opers = [test_addr(1), test_addr(2)];
+        let set_operators =
+            |add: Vec<String>, remove: Vec<&str>| ExecuteMsg::SetOperators {
+                add,
+                remove: remove.into_iter().map(String::from).collect(),
+            };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            set_operators(new_opers.to_vec(), vec!["oper1"]),
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+
+        // An invalid address or an address in both lists changes nothing.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            set_operators(
+                vec![new_opers[0].clone(), "invalid".to_string()],
+                vec!["oper1"],
+            ),
+        );
+        assert_eq!(res.unwrap_err().code(), "STD");
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            set_operators(vec![new_opers[0].clone()], vec![&new_opers[0]]),
+        );
+        assert_eq!(res.unwrap_err().code(), "CONFLICTING_OPERATOR_EDIT");
+        assert_eq!(
+            OPERATORS.load(deps.as_ref().storage)?,
+            BTreeSet::from(["oper0".to_string(), "oper1".to_string()])
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender(TEST_OWNER),
+            set_operators(new_opers.to_vec(), vec!["oper1"]),
+        )?;
+        let want_opers = BTreeSet::from([
+            "oper0".to_string(),
+            new_opers[0].clone(),
+            new_opers[1].clone(),
+        ]);
+        assert_eq!(OPERATORS.load(deps.as_ref().storage)?, want_opers);
+        let event = &res.events[0];
+        assert_eq!(event.ty, "broker_bank/set_operators");
+        assert_eq!(
+            event.attributes[2].value,
+            serde_json::to_string(&want_opers)?
+        );
         Ok(())
     }
 
@@ -704,7 +1847,7 @@ pub mod tests {
             );
             assert_eq!(
                 resp.attributes.len(),
-                2,
+                3,
                 "resp.attributes: {:#?}",
                 resp.attributes
             );
@@ -757,6 +1900,7 @@ pub mod tests {
         let exec_msg = ExecuteMsg::BankSend {
             coins: coins.clone(),
             to: String::from("mm_bybit"),
+            idempotency_key: None,
         };
         let sender = "valid_oper";
         let info = mock_info_for_sender(sender);
@@ -773,6 +1917,7 @@ pub mod tests {
         let exec_msg = ExecuteMsg::BankSend {
             coins: coins.clone(),
             to: String::from("mm_bybit"),
+            idempotency_key: None,
         };
         let sender = "invalid_sender";
         let info = mock_info_for_sender(sender);
@@ -783,6 +1928,7 @@ pub mod tests {
         let exec_msg = ExecuteMsg::BankSend {
             coins,
             to: String::from("not_allowed_addr"),
+            idempotency_key: None,
         };
         let sender = "valid_oper";
         let info = mock_info_for_sender(sender);
@@ -791,4 +1937,1375 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn exec_bank_send_logs_post_balance() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        deps.querier.bank.update_balance(
+            env.contract.address.as_str(),
+            vec![Coin::new(1000u128, "unibi"), Coin::new(50u128, "uusd")],
+        );
+
+        // Repeated denoms are summed. Denoms that aren't sent are left out.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            ExecuteMsg::BankSend {
+                coins: vec![
+                    Coin::new(300u128, "unibi"),
+                    Coin::new(120u128, "unibi"),
+                ],
+                to: "to_addr0".to_string(),
+                idempotency_key: None,
+            },
+        )?;
+        let want_post_balance = vec![Coin::new(580u128, "unibi")];
+        let attr = res.events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "post_balance")
+            .expect("missing post_balance attribute");
+        assert_eq!(
+            from_json::<Vec<Coin>>(attr.value.as_bytes())?,
+            want_post_balance
+        );
+
+        let log = LOGS
+            .front(deps.as_ref().storage)?
+            .expect("missing bank send log");
+        assert_eq!(log.post_balance, Some(want_post_balance));
+        assert_eq!(log.event, res.events[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn query_logs_by_sender() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        deps.querier.bank.update_balance(
+            env.contract.address.as_str(),
+            vec![Coin::new(1000u128, "unibi")],
+        );
+        let senders = ["oper0", "oper1", "oper0", "oper1", "oper0"];
+        for (idx, sender) in senders.into_iter().enumerate() {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info_for_sender(sender),
+                ExecuteMsg::BankSend {
+                    coins: vec![Coin::new(idx as u128 + 1, "unibi")],
+                    to: "to_addr0".to_string(),
+                    idempotency_key: None,
+                },
+            )?;
+        }
+
+        // Returns the amount sent by each matching log.
+        let logs_by_sender = |sender: &str, limit: Option<u32>| {
+            let msg = QueryMsg::LogsBySender {
+                sender: sender.to_string(),
+                limit,
+            };
+            let logs: Vec<Log> =
+                from_json(query(deps.as_ref(), env.clone(), msg)?)?;
+            logs.into_iter()
+                .map(|log| {
+                    assert_eq!(log.sender_addr, sender);
+                    let coins = &log.event.attributes[0];
+                    assert_eq!(coins.key, "coins");
+                    let coins: Vec<Coin> = from_json(coins.value.as_bytes())?;
+                    anyhow::Ok(coins[0].amount.u128())
+                })
+                .collect::<anyhow::Result<Vec<u128>>>()
+        };
+        // Only the target's entries are returned, newest first.
+        assert_eq!(logs_by_sender("oper0", None)?, vec![5, 3, 1]);
+        assert_eq!(logs_by_sender("oper1", None)?, vec![4, 2]);
+        assert_eq!(logs_by_sender("oper0", Some(2))?, vec![5, 3]);
+        assert!(logs_by_sender("to_addr0", None)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn exec_withdraw_addrs() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        deps.querier.bank.update_balance(
+            env.contract.address.as_str(),
+            vec![Coin::new(1000u128, "unibi")],
+        );
+        let treasury = test_addr(1);
+        let set_withdraw_addrs = ExecuteMsg::SetWithdrawAddrs {
+            addrs: Some(BTreeSet::from([treasury.clone()])),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            set_withdraw_addrs.clone(),
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            set_withdraw_addrs,
+        )?;
+
+        let withdraw = |to: &str| ExecuteMsg::Withdraw {
+            to: Some(to.to_string()),
+            denoms: BTreeSet::from([String::from("unibi")]),
+        };
+        // Operator recipients are not withdrawal targets.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            withdraw("to_addr0"),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::WithdrawAddrNotAllowed {
+                to_addr: String::from("to_addr0"),
+            }
+        );
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::WithdrawAll {
+                to: None,
+                exclude_denoms: None,
+            },
+        );
+        assert_eq!(res.unwrap_err().code(), "WITHDRAW_ADDR_NOT_ALLOWED");
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            withdraw(&treasury),
+        )?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: treasury,
+                amount: vec![Coin::new(1000u128, "unibi")],
+            })]
+        );
+
+        // Clearing the set allows any address again.
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetWithdrawAddrs { addrs: None },
+        )?;
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender(TEST_OWNER),
+            withdraw("to_addr0"),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn query_emergency_logs() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        deps.querier.bank.update_balance(
+            env.contract.address.as_str(),
+            vec![Coin::new(1000u128, "unibi")],
+        );
+        let msgs = [
+            (
+                "oper0",
+                ExecuteMsg::BankSend {
+                    coins: vec![Coin::new(10u128, "unibi")],
+                    to: "to_addr0".to_string(),
+                    idempotency_key: None,
+                },
+            ),
+            (
+                TEST_OWNER,
+                ExecuteMsg::WithdrawAll {
+                    to: None,
+                    exclude_denoms: None,
+                },
+            ),
+            (
+                TEST_OWNER,
+                ExecuteMsg::ToggleHalt {
+                    reason: Some(String::from("incident")),
+                },
+            ),
+            (TEST_OWNER, ExecuteMsg::ToggleHalt { reason: None }),
+        ];
+        for (sender, msg) in msgs {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info_for_sender(sender),
+                msg,
+            )?;
+        }
+        assert_eq!(LOGS.len(deps.as_ref().storage)?, 4);
+
+        // The routine send and the lifted halt are left out.
+        let logs: Vec<Log> = from_json(query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::EmergencyLogs { limit: None },
+        )?)?;
+        let event_types: Vec<&str> =
+            logs.iter().map(|log| log.event.ty.as_str()).collect();
+        assert_eq!(
+            event_types,
+            vec!["broker_bank/toggle_halt", "broker_bank/withdraw"]
+        );
+        assert!(logs.iter().all(|log| log.is_emergency));
+        Ok(())
+    }
+
+    #[test]
+    fn query_is_allowed_recipient() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAlias {
+                name: String::from("alias0"),
+                address: String::from("to_addr0"),
+            },
+        )?;
+        let is_allowed = |to: &str| -> anyhow::Result<bool> {
+            let msg = QueryMsg::IsAllowedRecipient { to: to.to_string() };
+            Ok(from_json(query(deps.as_ref(), env.clone(), msg)?)?)
+        };
+        assert!(is_allowed("to_addr1")?);
+        assert!(!is_allowed("not_allowed_addr")?);
+        assert!(is_allowed("alias0")?);
+        assert!(!is_allowed("unknown_alias")?);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_alias() -> TestResult {
+        let to_addrs: [String; 2] =
+            ["mm_kucoin", "mm_bybit"].map(|s| s.to_string());
+        let opers: [String; 1] = ["valid_oper"].map(|s| s.to_string());
+        let (mut deps, env, _info) =
+            setup_contract(to_addrs.to_vec(), opers.to_vec())?;
+        let coins = vec![Coin {
+            denom: "unibi".into(),
+            amount: Uint128::from(420u128),
+        }];
+
+        // Aliases can only point to addresses in TO_ADDRS
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAlias {
+                name: String::from("bybit"),
+                address: String::from("not_allowed_addr"),
+            },
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::ToAddrNotAllowed {
+                to_addr: String::from("not_allowed_addr")
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAlias {
+                name: String::from("bybit"),
+                address: String::from("mm_bybit"),
+            },
+        )?;
+
+        // Send via alias
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("valid_oper"),
+            ExecuteMsg::BankSend {
+                coins: coins.clone(),
+                to: String::from("bybit"),
+                idempotency_key: None,
+            },
+        )?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("mm_bybit"),
+                amount: coins.clone(),
+            }))]
+        );
+
+        // Send to an unknown alias
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("valid_oper"),
+            ExecuteMsg::BankSend {
+                coins,
+                to: String::from("kucoin"),
+                idempotency_key: None,
+            },
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::ToAddrNotAllowed {
+                to_addr: String::from("kucoin")
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_idempotency_key() -> TestResult {
+        let (mut deps, mut env, _info) = setup_contract_defaults()?;
+        let bank_send = |key: &str| ExecuteMsg::BankSend {
+            coins: vec![Coin::new(100u128, "unibi")],
+            to: String::from("to_addr0"),
+            idempotency_key: Some(key.to_string()),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            bank_send("transfer-1"),
+        )?;
+        assert_eq!(res.messages.len(), 1);
+
+        // The same key is rejected, even from another operator.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper1"),
+            bank_send("transfer-1"),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::DuplicateRequest {
+                key: "transfer-1".to_string()
+            }
+        );
+        assert_eq!(LOGS.len(deps.as_ref().storage)?, 1);
+
+        // Without a horizon, keys never expire or get pruned.
+        env.block.time = env.block.time.plus_seconds(1_000_000);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("anyone"),
+            ExecuteMsg::PruneKeys { limit: None },
+        )?;
+        assert!(SEEN_KEYS.has(deps.as_ref().storage, "transfer-1"));
+
+        // Only the owner can set the horizon.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            ExecuteMsg::SetKeyHorizon { seconds: Some(60) },
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetKeyHorizon { seconds: Some(60) },
+        )?;
+
+        // Expired keys are pruned, unexpired ones are kept.
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            bank_send("transfer-2"),
+        )?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("anyone"),
+            ExecuteMsg::PruneKeys { limit: None },
+        )?;
+        assert!(!SEEN_KEYS.has(deps.as_ref().storage, "transfer-1"));
+        assert!(SEEN_KEYS.has(deps.as_ref().storage, "transfer-2"));
+
+        // An expired key can be used again.
+        env.block.time = env.block.time.plus_seconds(60);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            bank_send("transfer-2"),
+        )?;
+        assert_eq!(LOGS.len(deps.as_ref().storage)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_auto_halt() -> TestResult {
+        let (mut deps, _env, _info) = setup_contract_defaults()?;
+        deps.querier = testing::MockQuerier::new(&[(
+            "cosmos2contract",
+            &[Coin::new(1_000u128, "unibi")],
+        )]);
+        let bank_send = ExecuteMsg::BankSend {
+            coins: vec![Coin::new(10u128, "unibi")],
+            to: String::from("to_addr0"),
+            idempotency_key: None,
+        };
+        let send_at = |deps: cw_std::DepsMut, height: u64| {
+            execute(
+                deps,
+                tutil::mock_env_height(height),
+                mock_info_for_sender("oper0"),
+                bank_send.clone(),
+            )
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            tutil::mock_env_height(100),
+            mock_info_for_sender("oper0"),
+            ExecuteMsg::SetAutoHalt {
+                after_blocks: Some(50),
+            },
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+        execute(
+            deps.as_mut(),
+            tutil::mock_env_height(100),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAutoHalt {
+                after_blocks: Some(50),
+            },
+        )?;
+
+        // Activity within the window keeps the contract running.
+        let res = send_at(deps.as_mut(), 150)?;
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.attributes, vec![cw_std::attr("sent", "true")]);
+        let res = send_at(deps.as_mut(), 200)?;
+        assert_eq!(res.messages.len(), 1);
+
+        // After a longer gap, the next send halts the contract instead.
+        let res = send_at(deps.as_mut(), 251)?;
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.attributes,
+            vec![
+                cw_std::attr("sent", "false"),
+                cw_std::attr("halted", "true")
+            ]
+        );
+        assert_eq!(res.events[0].ty, "broker_bank/auto_halt");
+        assert!(IS_HALTED.load(deps.as_ref().storage)?);
+        let res = send_at(deps.as_mut(), 252);
+        assert_eq!(res.unwrap_err(), ContractError::OperationsHalted);
+
+        // Lifting the halt restarts the window.
+        execute(
+            deps.as_mut(),
+            tutil::mock_env_height(300),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::ToggleHalt { reason: None },
+        )?;
+        let res = send_at(deps.as_mut(), 350)?;
+        assert_eq!(res.messages.len(), 1);
+
+        // Without auto-halt, any gap is fine.
+        execute(
+            deps.as_mut(),
+            tutil::mock_env_height(350),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAutoHalt { after_blocks: None },
+        )?;
+        let res = send_at(deps.as_mut(), 10_000)?;
+        assert_eq!(res.messages.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_to_self() -> TestResult {
+        let contract_addr = testing::mock_env().contract.address.to_string();
+        let (mut deps, env, _info) = setup_contract(
+            vec![contract_addr.clone(), String::from("to_addr0")],
+            vec![String::from("oper0")],
+        )?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetAlias {
+                name: String::from("treasury"),
+                address: contract_addr.clone(),
+            },
+        )?;
+
+        // Rejected even though the contract is in the "TO_ADDRS", whether
+        // addressed directly or through an alias.
+        for to in [contract_addr.clone(), String::from("treasury")] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info_for_sender("oper0"),
+                ExecuteMsg::BankSend {
+                    coins: vec![Coin::new(10u128, "unibi")],
+                    to,
+                    idempotency_key: None,
+                },
+            );
+            assert_eq!(
+                res.unwrap_err(),
+                ContractError::SelfSend {
+                    addr: contract_addr.clone()
+                }
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_min_send() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let set_min_send = |amount: Option<u128>| ExecuteMsg::SetMinSend {
+            denom: String::from("unibi"),
+            amount: amount.map(Uint128::from),
+        };
+        let bank_send = |coins: Vec<Coin>| ExecuteMsg::BankSend {
+            coins,
+            to: String::from("to_addr0"),
+            idempotency_key: None,
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            set_min_send(Some(100)),
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            set_min_send(Some(100)),
+        )?;
+
+        // Denoms without a minimum have no floor
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            bank_send(vec![
+                Coin::new(99u128, "unibi"),
+                Coin::new(1u128, "uusd"),
+            ]),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::BelowMinSend {
+                denom: String::from("unibi"),
+                amount: Uint128::new(99),
+                min: Uint128::new(100),
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            bank_send(vec![
+                Coin::new(100u128, "unibi"),
+                Coin::new(1u128, "uusd"),
+            ]),
+        )?;
+
+        // Clearing the minimum removes the floor
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            set_min_send(None),
+        )?;
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("oper0"),
+            bank_send(vec![Coin::new(1u128, "unibi")]),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn query_simulate_withdraw_all() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let contract_addr = env.contract.address.to_string();
+        let funds = [
+            Coin::new(1u128, "dust"),
+            Coin::new(420u128, "unibi"),
+            Coin::new(69u128, "uusd"),
+        ];
+        deps.querier =
+            testing::MockQuerier::new(&[(contract_addr.as_str(), &funds)]);
+        let exclude_denoms = Some(vec![String::from("uusd")]);
+
+        let simulated: Vec<Coin> = from_json(query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::SimulateWithdrawAll {
+                exclude_denoms: exclude_denoms.clone(),
+            },
+        )?)?;
+        assert_eq!(
+            simulated,
+            vec![Coin::new(1u128, "dust"), Coin::new(420u128, "unibi")]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::WithdrawAll {
+                to: None,
+                exclude_denoms,
+            },
+        )?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: TEST_OWNER.to_string(),
+                amount: simulated,
+            })]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exec_withdraw_cw20() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let contract_addr = env.contract.address.to_string();
+        let cw20_token = test_addr(9);
+        let queried_cw20 = cw20_token.clone();
+        deps.querier.update_wasm(move |query| match query {
+            cw_std::WasmQuery::Smart {
+                contract_addr: cw20,
+                msg,
+            } if *cw20 == queried_cw20 => {
+                let cw20::Cw20QueryMsg::Balance { address } =
+                    from_json(msg).unwrap()
+                else {
+                    panic!("unexpected cw20 query");
+                };
+                let balance = match address == contract_addr {
+                    true => Uint128::new(420),
+                    false => Uint128::zero(),
+                };
+                cw_std::SystemResult::Ok(cw_std::ContractResult::Ok(
+                    cw_std::to_json_binary(&cw20::BalanceResponse { balance })
+                        .unwrap(),
+                ))
+            }
+            _ => {
+                cw_std::SystemResult::Err(cw_std::SystemError::NoSuchContract {
+                    addr: String::from("unknown"),
+                })
+            }
+        });
+        let transfer_msg = |recipient: &str, amount: u128| -> CosmosMsg {
+            cw_std::WasmMsg::Execute {
+                contract_addr: cw20_token.clone(),
+                msg: cw_std::to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                    recipient: String::from(recipient),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        };
+
+        let withdraw_cw20 =
+            |to: Option<&str>, amount: Option<u128>| ExecuteMsg::WithdrawCw20 {
+                contract: cw20_token.clone(),
+                to: to.map(String::from),
+                amount: amount.map(Uint128::new),
+            };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            withdraw_cw20(None, None),
+        );
+        assert_eq!(res.unwrap_err().code(), "NOT_OWNER");
+
+        // The cw20 contract must be a valid address
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::WithdrawCw20 {
+                contract: String::from("cw20_token"),
+                to: None,
+                amount: Some(Uint128::new(20)),
+            },
+        );
+        assert_eq!(res.unwrap_err().code(), "STD");
+
+        // Defaults to the full CW20 balance, sent to the owner
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            withdraw_cw20(None, None),
+        )?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(transfer_msg(TEST_OWNER, 420))]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            withdraw_cw20(Some("mm_bybit"), Some(20)),
+        )?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(transfer_msg("mm_bybit", 20))]
+        );
+        assert_eq!(LOGS.len(deps.as_ref().storage)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn query_received_by() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let received_by = |deps: cw_std::Deps, to: &str| {
+            query(
+                deps,
+                testing::mock_env(),
+                QueryMsg::ReceivedBy { to: to.to_string() },
+            )
+            .and_then(|bin| Ok(from_json::<Vec<Coin>>(bin)?))
+        };
+        assert_eq!(received_by(deps.as_ref(), "to_addr0")?, vec![]);
+
+        for coins in [
+            vec![Coin::new(100u128, "unibi")],
+            vec![Coin::new(50u128, "unibi"), Coin::new(7u128, "uusd")],
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info_for_sender("oper0"),
+                ExecuteMsg::BankSend {
+                    coins,
+                    to: String::from("to_addr0"),
+                    idempotency_key: None,
+                },
+            )?;
+        }
+        assert_eq!(
+            received_by(deps.as_ref(), "to_addr0")?,
+            vec![Coin::new(150u128, "unibi"), Coin::new(7u128, "uusd")]
+        );
+        assert_eq!(received_by(deps.as_ref(), "to_addr1")?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_global_cap() -> TestResult {
+        let to_addrs: [String; 1] = ["mm_bybit"].map(|s| s.to_string());
+        let opers: [String; 1] = ["valid_oper"].map(|s| s.to_string());
+        let (mut deps, env, _info) =
+            setup_contract(to_addrs.to_vec(), opers.to_vec())?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetGlobalCap {
+                cap: Some(Coin::new(1000u128, "unibi")),
+            },
+        )?;
+
+        let bank_send = |amount: u128| ExecuteMsg::BankSend {
+            coins: vec![
+                Coin::new(amount, "unibi"),
+                // Other denoms do not count against the cap
+                Coin::new(5000u128, "uusd"),
+            ],
+            to: String::from("mm_bybit"),
+            idempotency_key: None,
+        };
+        let oper = mock_info_for_sender("valid_oper");
+        execute(deps.as_mut(), env.clone(), oper.clone(), bank_send(600))?;
+        execute(deps.as_mut(), env.clone(), oper.clone(), bank_send(400))?;
+
+        // The window total has reached the cap
+        let res =
+            execute(deps.as_mut(), env.clone(), oper.clone(), bank_send(1));
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::GlobalCapExceeded {
+                cap: String::from("1000unibi"),
+                denom: String::from("unibi"),
+                window_total: Uint128::new(1001),
+            }
+        );
+
+        // Owner withdrawals are capped too
+        let contract_addr = env.contract.address.to_string();
+        deps.querier = testing::MockQuerier::new(&[(
+            contract_addr.as_str(),
+            &[Coin::new(10u128, "unibi")],
+        )]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::WithdrawAll {
+                to: None,
+                exclude_denoms: None,
+            },
+        );
+        assert_eq!(res.unwrap_err().code(), "GLOBAL_CAP_EXCEEDED");
+
+        // The cap resets once the window has passed
+        let mut next_env = env.clone();
+        next_env.block.time =
+            env.block.time.plus_seconds(OUTFLOW_WINDOW_SECONDS);
+        execute(
+            deps.as_mut(),
+            next_env.clone(),
+            oper.clone(),
+            bank_send(1000),
+        )?;
+        let res = execute(deps.as_mut(), next_env, oper, bank_send(1));
+        assert_eq!(res.unwrap_err().code(), "GLOBAL_CAP_EXCEEDED");
+        Ok(())
+    }
+
+    #[test]
+    fn query_config_metadata() -> TestResult {
+        let mut deps = testing::mock_dependencies();
+        let env = testing::mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            InstantiateMsg {
+                owner: TEST_OWNER.to_string(),
+                to_addrs: BTreeSet::new(),
+                opers: BTreeSet::new(),
+                recovery: None,
+                label: Some(String::from("mm-desk-1")),
+                description: None,
+                event_prefix: None,
+            },
+        )?;
+        let query_config = |deps: cw_std::Deps| -> anyhow::Result<Config> {
+            let resp = query(deps, testing::mock_env(), QueryMsg::Config {})?;
+            Ok(from_json(resp)?)
+        };
+        assert_eq!(
+            query_config(deps.as_ref())?,
+            Config {
+                label: Some(String::from("mm-desk-1")),
+                description: None,
+                event_prefix: None,
+            }
+        );
+
+        let want = Config {
+            label: Some(String::from("mm-desk-2")),
+            description: Some(String::from("Market maker float")),
+            event_prefix: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetMetadata {
+                label: want.label.clone(),
+                description: want.description.clone(),
+            },
+        )?;
+        assert_eq!(res.events[0].ty, "broker_bank/set_metadata");
+        assert_eq!(query_config(deps.as_ref())?, want);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_bank_send_event_prefix() -> TestResult {
+        let mut deps = testing::mock_dependencies();
+        let env = testing::mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            InstantiateMsg {
+                owner: TEST_OWNER.to_string(),
+                to_addrs: BTreeSet::from([String::from("to_addr0")]),
+                opers: BTreeSet::from([String::from("oper0")]),
+                recovery: None,
+                label: None,
+                description: None,
+                event_prefix: Some(String::from("treasury_a")),
+            },
+        )?;
+        let config: Config =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+        assert_eq!(config.event_prefix, Some(String::from("treasury_a")));
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("oper0"),
+            ExecuteMsg::BankSend {
+                coins: vec![Coin::new(5u128, "unibi")],
+                to: String::from("to_addr0"),
+                idempotency_key: None,
+            },
+        )?;
+        let event_types: Vec<&str> =
+            res.events.iter().map(|event| event.ty.as_str()).collect();
+        assert_eq!(event_types, vec!["treasury_a/broker_bank/send"]);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_toggle_halt_event_records_reason() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::ToggleHalt {
+                reason: Some(String::from("key rotation")),
+            },
+        )?;
+        assert_eq!(
+            res.events,
+            vec![cw_std::Event::new("broker_bank/toggle_halt")
+                .add_attribute("new_is_halted", "true")
+                .add_attribute("sender", TEST_OWNER)
+                .add_attribute("reason", "key rotation")
+                .add_attribute("block_height", env.block.height.to_string())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn query_operator_usage() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        deps.querier.bank.update_balance(
+            env.contract.address.as_str(),
+            vec![Coin::new(5000u128, "unibi")],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetGlobalCap {
+                cap: Some(Coin::new(1000u128, "unibi")),
+            },
+        )?;
+        let usage = |deps: cw_std::Deps,
+                     env: cw_std::Env,
+                     operator: &str|
+         -> anyhow::Result<OperatorUsage> {
+            Ok(from_json(query(
+                deps,
+                env,
+                QueryMsg::OperatorUsage {
+                    operator: String::from(operator),
+                },
+            )?)?)
+        };
+        let send = |amount: u128| ExecuteMsg::BankSend {
+            coins: vec![Coin::new(amount, "unibi")],
+            to: String::from("to_addr0"),
+            idempotency_key: None,
+        };
+        let window_resets_at = env.block.time.seconds() + OUTFLOW_WINDOW_SECONDS;
+        assert_eq!(
+            usage(deps.as_ref(), env.clone(), "oper0")?,
+            OperatorUsage {
+                spent_this_window: vec![],
+                limit: None,
+                window_resets_at,
+                global_remaining: Some(Coin::new(1000u128, "unibi")),
+                global_window_resets_at: Some(window_resets_at),
+            }
+        );
+
+        // Both operators spend against the one global cap.
+        for (operator, amount) in [("oper0", 300u128), ("oper1", 500)] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info_for_sender(operator),
+                send(amount),
+            )?;
+        }
+        let got = usage(deps.as_ref(), env.clone(), "oper0")?;
+        assert_eq!(got.spent_this_window, vec![Coin::new(300u128, "unibi")]);
+        assert_eq!(got.window_resets_at, window_resets_at);
+        // Only 200unibi of the cap is left for oper0, not 1000 - 300.
+        assert_eq!(got.global_remaining, Some(Coin::new(200u128, "unibi")));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            send(300),
+        );
+        assert_eq!(res.unwrap_err().code(), "GLOBAL_CAP_EXCEEDED");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            send(200),
+        )?;
+
+        // An operator limit is enforced in the operator's own window.
+        let mut next_env = env.clone();
+        next_env.block.time =
+            env.block.time.plus_seconds(OUTFLOW_WINDOW_SECONDS);
+        execute(
+            deps.as_mut(),
+            next_env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetOperatorLimit {
+                operator: String::from("oper1"),
+                limit: Some(Coin::new(400u128, "unibi")),
+            },
+        )?;
+        let got = usage(deps.as_ref(), next_env.clone(), "oper1")?;
+        assert_eq!(got.spent_this_window, vec![]);
+        assert_eq!(got.limit, Some(Coin::new(400u128, "unibi")));
+        assert_eq!(
+            got.window_resets_at,
+            window_resets_at + OUTFLOW_WINDOW_SECONDS
+        );
+        assert_eq!(got.global_remaining, Some(Coin::new(1000u128, "unibi")));
+        let res = execute(
+            deps.as_mut(),
+            next_env.clone(),
+            mock_info_for_sender("oper1"),
+            send(500),
+        );
+        assert_eq!(res.unwrap_err().code(), "OPERATOR_LIMIT_EXCEEDED");
+        execute(
+            deps.as_mut(),
+            next_env,
+            mock_info_for_sender("oper1"),
+            send(400),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn query_operators_detailed() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let cap = Coin::new(1000u128, "unibi");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetGlobalCap {
+                cap: Some(cap.clone()),
+            },
+        )?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper0"),
+            ExecuteMsg::BankSend {
+                coins: vec![Coin::new(300u128, "unibi")],
+                to: String::from("to_addr0"),
+                idempotency_key: None,
+            },
+        )?;
+        let detailed =
+            |deps: cw_std::Deps| -> anyhow::Result<Vec<OperatorDetail>> {
+                Ok(from_json(query(
+                    deps,
+                    env.clone(),
+                    QueryMsg::OperatorsDetailed {},
+                )?)?)
+            };
+        // Configure a limit for oper0 and disable oper1
+        let limit = Coin::new(500u128, "unibi");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetOperatorLimit {
+                operator: String::from("oper0"),
+                limit: Some(limit.clone()),
+            },
+        )?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetOperatorEnabled {
+                operator: String::from("oper1"),
+                enabled: false,
+            },
+        )?;
+        let want = |oper0_enabled: bool, oper1_enabled: bool| {
+            vec![
+                OperatorDetail {
+                    address: String::from("oper0"),
+                    enabled: oper0_enabled,
+                    limit: Some(limit.clone()),
+                    spent_this_window: vec![Coin::new(300u128, "unibi")],
+                },
+                OperatorDetail {
+                    address: String::from("oper1"),
+                    enabled: oper1_enabled,
+                    limit: None,
+                    spent_this_window: vec![],
+                },
+            ]
+        };
+        let got = detailed(deps.as_ref())?;
+        assert_eq!(got, want(true, false));
+        assert_eq!(got.iter().filter(|oper| !oper.enabled).count(), 1);
+
+        // The disabled operator cannot send, the other one still can
+        let send = ExecuteMsg::BankSend {
+            coins: vec![Coin::new(100u128, "unibi")],
+            to: String::from("to_addr0"),
+            idempotency_key: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper1"),
+            send.clone(),
+        );
+        assert_eq!(res.unwrap_err().code(), "OPERATOR_DISABLED");
+
+        // Only operators can be disabled
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetOperatorEnabled {
+                operator: String::from("to_addr0"),
+                enabled: false,
+            },
+        );
+        assert_eq!(res.unwrap_err().code(), "NO_OPERATOR_PERMS");
+
+        // Halting does not change whether operators are enabled
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::ToggleHalt { reason: None },
+        )?;
+        assert_eq!(detailed(deps.as_ref())?, want(true, false));
+        let perms: PermsStatus =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Perms {})?)?;
+        assert!(perms.is_halted);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::ToggleHalt { reason: None },
+        )?;
+
+        // Re-enabling oper1 lets it send again
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::SetOperatorEnabled {
+                operator: String::from("oper1"),
+                enabled: true,
+            },
+        )?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("oper1"),
+            send,
+        )?;
+        assert!(detailed(deps.as_ref())?.iter().all(|oper| oper.enabled));
+        Ok(())
+    }
+
+    fn setup_with_recovery(
+        delay_blocks: u64,
+    ) -> anyhow::Result<(
+        cw_std::OwnedDeps<
+            testing::MockStorage,
+            testing::MockApi,
+            testing::MockQuerier,
+        >,
+        cw_std::Env,
+    )> {
+        let mut deps = testing::mock_dependencies();
+        let env = tutil::mock_env_height(100);
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            InstantiateMsg {
+                owner: TEST_OWNER.to_string(),
+                to_addrs: BTreeSet::new(),
+                opers: BTreeSet::new(),
+                recovery: Some(RecoveryConfig {
+                    addr: String::from("recovery"),
+                    delay_blocks,
+                }),
+                label: None,
+                description: None,
+                event_prefix: None,
+            },
+        )?;
+        Ok((deps, env))
+    }
+
+    #[test]
+    fn exec_recovery_veto() -> TestResult {
+        let (mut deps, env) = setup_with_recovery(50)?;
+
+        // Only the recovery address can initiate
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("random"),
+            ExecuteMsg::InitiateRecovery {},
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::NotRecoveryAddr {
+                addr: String::from("random")
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::InitiateRecovery {},
+        )?;
+        let status: RecoveryStatus = from_json(query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Recovery {},
+        )?)?;
+        assert_eq!(
+            status.pending,
+            Some(PendingRecovery {
+                initiated_height: 100,
+                finalize_height: 150,
+            })
+        );
+
+        // Only the owner can veto
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::VetoRecovery {},
+        );
+        assert!(res.is_err());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info_for_sender(TEST_OWNER),
+            ExecuteMsg::VetoRecovery {},
+        )?;
+
+        // A vetoed recovery cannot be finalized, even after the timelock
+        let res = execute(
+            deps.as_mut(),
+            tutil::mock_env_height(200),
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::FinalizeRecovery {},
+        );
+        assert_eq!(res.unwrap_err(), ContractError::NoPendingRecovery);
+        let ownership = nibiru_ownable::get_ownership(deps.as_ref().storage)?;
+        assert_eq!(ownership.owner, Some(TEST_OWNER.to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn exec_recovery_finalize() -> TestResult {
+        let (mut deps, env) = setup_with_recovery(50)?;
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::InitiateRecovery {},
+        )?;
+
+        // Finalizing before the timelock elapses fails
+        let res = execute(
+            deps.as_mut(),
+            tutil::mock_env_height(149),
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::FinalizeRecovery {},
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::RecoveryTimelocked {
+                finalize_height: 150
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            tutil::mock_env_height(150),
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::FinalizeRecovery {},
+        )?;
+        let ownership = nibiru_ownable::get_ownership(deps.as_ref().storage)?;
+        assert_eq!(ownership.owner, Some(String::from("recovery")));
+        assert_eq!(PENDING_RECOVERY.may_load(deps.as_ref().storage)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_recovery_not_configured() -> TestResult {
+        let (mut deps, env, _info) = setup_contract_defaults()?;
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::InitiateRecovery {},
+        );
+        assert_eq!(res.unwrap_err(), ContractError::RecoveryNotConfigured);
+        Ok(())
+    }
+
+    #[test]
+    fn exec_recovery_delay_overflow() -> TestResult {
+        let (mut deps, env) = setup_with_recovery(u64::MAX)?;
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info_for_sender("recovery"),
+            ExecuteMsg::InitiateRecovery {},
+        );
+        let err = res.unwrap_err();
+        assert_eq!(err.code(), "STD");
+        assert!(err.to_string().contains("Overflow"), "err: {err}");
+        assert!(PENDING_RECOVERY.may_load(deps.as_ref().storage)?.is_none());
+        Ok(())
+    }
 }
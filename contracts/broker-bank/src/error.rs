@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
+use nibiru_std::errors::NibiruError;
 use std::collections::BTreeSet;
 
 use thiserror::Error;
@@ -36,6 +37,113 @@ pub enum ContractError {
         denom: String,
         denom_set: BTreeSet<String>,
     },
+
+    #[error("no recovery address is configured")]
+    RecoveryNotConfigured,
+
+    #[error("insufficient permissions: address is not the recovery address ({addr:?})")]
+    NotRecoveryAddr { addr: String },
+
+    #[error("a recovery is already pending")]
+    RecoveryAlreadyPending,
+
+    #[error("there is no pending recovery")]
+    NoPendingRecovery,
+
+    #[error("recovery is timelocked until block height {finalize_height}")]
+    RecoveryTimelocked { finalize_height: u64 },
+
+    #[error("global outflow cap of {cap} exceeded: {window_total}{denom} would be sent in the current window")]
+    GlobalCapExceeded {
+        cap: String,
+        denom: String,
+        window_total: Uint128,
+    },
+
+    #[error("send of {amount}{denom} is below the minimum of {min}{denom}")]
+    BelowMinSend {
+        denom: String,
+        amount: Uint128,
+        min: Uint128,
+    },
+
+    #[error("duplicate request: idempotency key {key:?} was already used")]
+    DuplicateRequest { key: String },
+
+    #[error("cannot send to the contract's own address ({addr:?})")]
+    SelfSend { addr: String },
+
+    #[error("operator {address:?} cannot be both added and removed")]
+    ConflictingOperatorEdit { address: String },
+
+    #[error("operator {addr:?} is disabled")]
+    OperatorDisabled { addr: String },
+
+    #[error("operator {operator:?} exceeded its limit of {limit}: {window_total}{denom} would be sent in its current window")]
+    OperatorLimitExceeded {
+        operator: String,
+        limit: String,
+        denom: String,
+        window_total: Uint128,
+    },
+
+    #[error("withdrawal address is not allowed (to_addr: {to_addr:?})")]
+    WithdrawAddrNotAllowed { to_addr: String },
+}
+
+impl ContractError {
+    /// Returns a stable, machine-readable code for the error category. Unlike
+    /// the Display string, these codes are part of the contract's public API
+    /// and are safe for off-chain clients to branch on.
+    ///
+    /// The codes are not emitted as event attributes, since a failed tx drops
+    /// its events, and the Display strings are kept as they are for clients
+    /// that already match on them.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::Std(_) => "STD",
+            ContractError::SerdeJson(_) => "SERDE_JSON",
+            ContractError::Ownership(err) => match err {
+                nibiru_ownable::OwnershipError::NotOwner => "NOT_OWNER",
+                nibiru_ownable::OwnershipError::NoOwner => "NO_OWNER",
+                nibiru_ownable::OwnershipError::NotPendingOwner => {
+                    "NOT_PENDING_OWNER"
+                }
+                nibiru_ownable::OwnershipError::TransferNotFound => {
+                    "TRANSFER_NOT_FOUND"
+                }
+                nibiru_ownable::OwnershipError::TransferExpired => {
+                    "TRANSFER_EXPIRED"
+                }
+                nibiru_ownable::OwnershipError::Std(_) => "STD",
+            },
+            ContractError::NotImplemented => "NOT_IMPLEMENTED",
+            ContractError::OperationsHalted => "HALTED",
+            ContractError::ToAddrNotAllowed { .. } => "TO_ADDR_NOT_ALLOWED",
+            ContractError::UnknownRequest => "UNKNOWN_REQUEST",
+            ContractError::NoOperatorPerms { .. } => "NO_OPERATOR_PERMS",
+            ContractError::AddExistentDenom { .. } => "ADD_EXISTENT_DENOM",
+            ContractError::RecoveryNotConfigured => "RECOVERY_NOT_CONFIGURED",
+            ContractError::NotRecoveryAddr { .. } => "NOT_RECOVERY_ADDR",
+            ContractError::RecoveryAlreadyPending => "RECOVERY_ALREADY_PENDING",
+            ContractError::NoPendingRecovery => "NO_PENDING_RECOVERY",
+            ContractError::RecoveryTimelocked { .. } => "RECOVERY_TIMELOCKED",
+            ContractError::GlobalCapExceeded { .. } => "GLOBAL_CAP_EXCEEDED",
+            ContractError::BelowMinSend { .. } => "BELOW_MIN_SEND",
+            ContractError::DuplicateRequest { .. } => "DUPLICATE_REQUEST",
+            ContractError::SelfSend { .. } => "SELF_SEND",
+            ContractError::ConflictingOperatorEdit { .. } => {
+                "CONFLICTING_OPERATOR_EDIT"
+            }
+            ContractError::OperatorDisabled { .. } => "OPERATOR_DISABLED",
+            ContractError::OperatorLimitExceeded { .. } => {
+                "OPERATOR_LIMIT_EXCEEDED"
+            }
+            ContractError::WithdrawAddrNotAllowed { .. } => {
+                "WITHDRAW_ADDR_NOT_ALLOWED"
+            }
+        }
+    }
 }
 
 impl From<serde_json::Error> for ContractError {
@@ -43,3 +151,135 @@ impl From<serde_json::Error> for ContractError {
         ContractError::SerdeJson(err.to_string())
     }
 }
+
+impl From<NibiruError> for ContractError {
+    fn from(err: NibiruError) -> Self {
+        match err {
+            NibiruError::CwStd(err) => ContractError::Std(err),
+            err => ContractError::Std(StdError::generic_err(err.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::StdError;
+    use nibiru_ownable::OwnershipError;
+
+    use super::ContractError;
+
+    #[test]
+    fn error_codes() {
+        let test_cases: Vec<(ContractError, &str)> = vec![
+            (StdError::generic_err("err").into(), "STD"),
+            (ContractError::SerdeJson("err".to_string()), "SERDE_JSON"),
+            (OwnershipError::NotOwner.into(), "NOT_OWNER"),
+            (OwnershipError::NoOwner.into(), "NO_OWNER"),
+            (OwnershipError::NotPendingOwner.into(), "NOT_PENDING_OWNER"),
+            (
+                OwnershipError::TransferNotFound.into(),
+                "TRANSFER_NOT_FOUND",
+            ),
+            (OwnershipError::TransferExpired.into(), "TRANSFER_EXPIRED"),
+            (ContractError::NotImplemented, "NOT_IMPLEMENTED"),
+            (ContractError::OperationsHalted, "HALTED"),
+            (
+                ContractError::ToAddrNotAllowed {
+                    to_addr: "to".to_string(),
+                },
+                "TO_ADDR_NOT_ALLOWED",
+            ),
+            (ContractError::UnknownRequest, "UNKNOWN_REQUEST"),
+            (
+                ContractError::NoOperatorPerms {
+                    addr: "addr".to_string(),
+                },
+                "NO_OPERATOR_PERMS",
+            ),
+            (
+                ContractError::AddExistentDenom {
+                    denom: "denom".to_string(),
+                    denom_set: Default::default(),
+                },
+                "ADD_EXISTENT_DENOM",
+            ),
+            (
+                ContractError::RecoveryNotConfigured,
+                "RECOVERY_NOT_CONFIGURED",
+            ),
+            (
+                ContractError::NotRecoveryAddr {
+                    addr: "addr".to_string(),
+                },
+                "NOT_RECOVERY_ADDR",
+            ),
+            (
+                ContractError::RecoveryAlreadyPending,
+                "RECOVERY_ALREADY_PENDING",
+            ),
+            (ContractError::NoPendingRecovery, "NO_PENDING_RECOVERY"),
+            (
+                ContractError::RecoveryTimelocked { finalize_height: 1 },
+                "RECOVERY_TIMELOCKED",
+            ),
+            (
+                ContractError::GlobalCapExceeded {
+                    cap: "100unibi".to_string(),
+                    denom: "unibi".to_string(),
+                    window_total: 101u128.into(),
+                },
+                "GLOBAL_CAP_EXCEEDED",
+            ),
+            (
+                ContractError::BelowMinSend {
+                    denom: "unibi".to_string(),
+                    amount: 1u128.into(),
+                    min: 100u128.into(),
+                },
+                "BELOW_MIN_SEND",
+            ),
+            (
+                ContractError::DuplicateRequest {
+                    key: "key".to_string(),
+                },
+                "DUPLICATE_REQUEST",
+            ),
+            (
+                ContractError::SelfSend {
+                    addr: "addr".to_string(),
+                },
+                "SELF_SEND",
+            ),
+            (
+                ContractError::ConflictingOperatorEdit {
+                    address: "addr".to_string(),
+                },
+                "CONFLICTING_OPERATOR_EDIT",
+            ),
+            (
+                ContractError::OperatorDisabled {
+                    addr: "oper".to_string(),
+                },
+                "OPERATOR_DISABLED",
+            ),
+            (
+                ContractError::OperatorLimitExceeded {
+                    operator: "oper".to_string(),
+                    limit: "100unibi".to_string(),
+                    denom: "unibi".to_string(),
+                    window_total: 101u128.into(),
+                },
+                "OPERATOR_LIMIT_EXCEEDED",
+            ),
+            (
+                ContractError::WithdrawAddrNotAllowed {
+                    to_addr: "to".to_string(),
+                },
+                "WITHDRAW_ADDR_NOT_ALLOWED",
+            ),
+        ];
+        for (err, want_code) in test_cases {
+            assert_eq!(err.code(), want_code, "err: {err}");
+        }
+    }
+}
@@ -1,16 +1,128 @@
 use std::collections::BTreeSet;
 
-use cosmwasm_std::Event;
+use cosmwasm_std::{Coin, Event, Response, Uint128};
 
-pub fn event_bank_send(coins_json: &str, caller: &str) -> Event {
+/// Prepends `prefix` to the type of every event of `res`. A no-op when
+/// `prefix` is `None`.
+pub fn with_event_prefix(mut res: Response, prefix: Option<&str>) -> Response {
+    if let Some(prefix) = prefix {
+        for event in res.events.iter_mut() {
+            event.ty = format!("{prefix}/{}", event.ty);
+        }
+    }
+    res
+}
+
+pub fn event_bank_send(
+    coins_json: &str,
+    caller: &str,
+    post_balance_json: &str,
+) -> Event {
     Event::new("broker_bank/send")
         .add_attribute("coins", coins_json)
         .add_attribute("caller", caller)
+        .add_attribute("post_balance", post_balance_json)
+}
+
+pub fn event_set_alias(name: &str, address: &str) -> Event {
+    Event::new("broker_bank/set_alias")
+        .add_attribute("name", name)
+        .add_attribute("address", address)
+}
+
+pub fn event_set_global_cap(cap: Option<&Coin>) -> Event {
+    Event::new("broker_bank/set_global_cap").add_attribute(
+        "cap",
+        cap.map(|coin| coin.to_string()).unwrap_or_default(),
+    )
+}
+
+pub fn event_set_operator_enabled(operator: &str, enabled: bool) -> Event {
+    Event::new("broker_bank/set_operator_enabled")
+        .add_attribute("operator", operator)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn event_set_operator_limit(operator: &str, limit: Option<&Coin>) -> Event {
+    Event::new("broker_bank/set_operator_limit")
+        .add_attribute("operator", operator)
+        .add_attribute(
+            "limit",
+            limit.map(|coin| coin.to_string()).unwrap_or_default(),
+        )
+}
+
+pub fn event_set_withdraw_addrs(addrs_json: &str) -> Event {
+    Event::new("broker_bank/set_withdraw_addrs")
+        .add_attribute("addrs", addrs_json)
+}
+
+pub fn event_set_min_send(denom: &str, amount: Option<&Uint128>) -> Event {
+    Event::new("broker_bank/set_min_send")
+        .add_attribute("denom", denom)
+        .add_attribute(
+            "amount",
+            amount.map(|amount| amount.to_string()).unwrap_or_default(),
+        )
+}
+
+pub fn event_set_key_horizon(seconds: Option<u64>) -> Event {
+    Event::new("broker_bank/set_key_horizon").add_attribute(
+        "seconds",
+        seconds
+            .map(|seconds| seconds.to_string())
+            .unwrap_or_default(),
+    )
+}
+
+pub fn event_prune_keys(pruned: usize) -> Event {
+    Event::new("broker_bank/prune_keys")
+        .add_attribute("pruned", pruned.to_string())
 }
 
-pub fn event_toggle_halt(is_halted: &bool) -> Event {
+pub fn event_set_metadata(label: &str, description: &str) -> Event {
+    Event::new("broker_bank/set_metadata")
+        .add_attribute("label", label)
+        .add_attribute("description", description)
+}
+
+pub fn event_toggle_halt(
+    is_halted: &bool,
+    sender: &str,
+    reason: Option<&str>,
+    block_height: u64,
+) -> Event {
     Event::new("broker_bank/toggle_halt")
         .add_attribute("new_is_halted", is_halted.to_string())
+        .add_attribute("sender", sender)
+        .add_attribute("reason", reason.unwrap_or_default())
+        .add_attribute("block_height", block_height.to_string())
+}
+
+pub fn event_set_auto_halt(after_blocks: Option<u64>) -> Event {
+    Event::new("broker_bank/set_auto_halt").add_attribute(
+        "after_blocks",
+        after_blocks
+            .map(|blocks| blocks.to_string())
+            .unwrap_or_default(),
+    )
+}
+
+pub fn event_auto_halt(last_activity_height: &u64, idle_blocks: &u64) -> Event {
+    Event::new("broker_bank/auto_halt")
+        .add_attribute("last_activity_height", last_activity_height.to_string())
+        .add_attribute("idle_blocks", idle_blocks.to_string())
+}
+
+pub fn event_set_operators(
+    added_json: &str,
+    removed_json: &str,
+    operators_json: &str,
+) -> Event {
+    Event::new("broker_bank/set_operators")
+        .add_attribute("added", added_json)
+        .add_attribute("removed", removed_json)
+        .add_attribute("new_operators", operators_json)
 }
 
 pub fn event_withdraw(coins_json: &str, to_addr: &str) -> Event {
@@ -19,6 +131,35 @@ pub fn event_withdraw(coins_json: &str, to_addr: &str) -> Event {
         .add_attribute("to_addr", to_addr)
 }
 
+pub fn event_withdraw_cw20(
+    cw20_contract: &str,
+    amount: &Uint128,
+    to_addr: &str,
+) -> Event {
+    Event::new("broker_bank/withdraw_cw20")
+        .add_attribute("cw20_contract", cw20_contract)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("to_addr", to_addr)
+}
+
+pub fn event_initiate_recovery(
+    recovery_addr: &str,
+    finalize_height: &u64,
+) -> Event {
+    Event::new("broker_bank/initiate_recovery")
+        .add_attribute("recovery_addr", recovery_addr)
+        .add_attribute("finalize_height", finalize_height.to_string())
+}
+
+pub fn event_veto_recovery(owner: &str) -> Event {
+    Event::new("broker_bank/veto_recovery").add_attribute("owner", owner)
+}
+
+pub fn event_finalize_recovery(new_owner: &str) -> Event {
+    Event::new("broker_bank/finalize_recovery")
+        .add_attribute("new_owner", new_owner)
+}
+
 pub fn denom_set_json(
     denom_set: BTreeSet<String>,
 ) -> serde_json::Result<String> {
@@ -3,18 +3,95 @@ use std::collections::BTreeSet;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std as cw;
 
-use crate::oper_perms;
+use crate::{
+    oper_perms,
+    state::{PendingRecovery, RecoveryConfig},
+};
 
 #[nibiru_ownable::ownable_execute]
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Send coins to an account the set of "TO_ADDRS", appending transaction
-    /// info to the "LOGS". This tx msg emits a "broker/bank/send" event
-    BankSend { coins: Vec<cw::Coin>, to: String },
+    /// info to the "LOGS". This tx msg emits a "broker/bank/send" event. The
+    /// recipient `to` can be an address or an alias registered with
+    /// `SetAlias`. A repeated `idempotency_key` is rejected so that relayers
+    /// can safely resubmit a send.
+    ///
+    /// If auto-halt is enabled and no operator has acted for longer than
+    /// `SetAutoHalt` allows, the tx succeeds but sends nothing: it halts the
+    /// contract and emits a "broker_bank/auto_halt" event instead. The
+    /// response then carries the attributes `sent=false` and `halted=true`,
+    /// while a send that goes through carries `sent=true`.
+    BankSend {
+        coins: Vec<cw::Coin>,
+        to: String,
+        idempotency_key: Option<String>,
+    },
+
+    /// SetAlias: Registers `name` as an alias for `address`, which must be in
+    /// the set of "TO_ADDRS". Only callable by the contract owner.
+    SetAlias { name: String, address: String },
+
+    /// SetGlobalCap: Sets or clears (with `None`) the contract-wide limit on
+    /// how much of `cap.denom` can be sent out by `BankSend`, `Withdraw`, and
+    /// `WithdrawAll` per day. Only callable by the contract owner.
+    SetGlobalCap { cap: Option<cw::Coin> },
+
+    /// SetOperatorEnabled: Disables or re-enables `BankSend` for a single
+    /// operator, which must be in the operator set. Unlike `ToggleHalt`, other
+    /// operators are not affected. Only callable by the contract owner.
+    SetOperatorEnabled { operator: String, enabled: bool },
+
+    /// SetOperatorLimit: Sets or clears (with `None`) the limit on how much of
+    /// `limit.denom` `operator` can send with `BankSend` per day, on top of the
+    /// global cap. Only callable by the contract owner.
+    SetOperatorLimit {
+        operator: String,
+        limit: Option<cw::Coin>,
+    },
+
+    /// SetWithdrawAddrs: Sets or clears (with `None`) the addresses that
+    /// `Withdraw`, `WithdrawAll`, and `WithdrawCw20` may send to. Without a
+    /// set, withdrawals can go to any address. Only callable by the contract
+    /// owner.
+    SetWithdrawAddrs { addrs: Option<BTreeSet<String>> },
+
+    /// SetMinSend: Sets or clears (with `None`) the minimum amount of `denom`
+    /// that `BankSend` may send. Only callable by the contract owner.
+    SetMinSend {
+        denom: String,
+        amount: Option<cw::Uint128>,
+    },
+
+    /// SetKeyHorizon: Sets or clears (with `None`) the number of seconds after
+    /// which a `BankSend` idempotency key expires. Only callable by the
+    /// contract owner.
+    SetKeyHorizon { seconds: Option<u64> },
+
+    /// PruneKeys: Removes up to `limit` expired idempotency keys from the
+    /// contract state. Callable by any account, since only keys past the
+    /// owner's `SetKeyHorizon` are removed.
+    PruneKeys { limit: Option<u32> },
+
+    /// SetMetadata: Replaces the label and description of the contract shown
+    /// by the `Config` query. Only callable by the contract owner.
+    SetMetadata {
+        label: Option<String>,
+        description: Option<String>,
+    },
+
+    /// SetAutoHalt: Sets or clears (with `None`) the number of blocks without
+    /// an operator action after which the contract halts itself. Once that many
+    /// blocks have passed, the next `BankSend` sends nothing and halts the
+    /// contract instead. Only callable by the contract owner.
+    SetAutoHalt { after_blocks: Option<u64> },
 
     /// ToggleHalt: Toggles on or off the ability of the operators to use the
     /// smart contract. Only callable by the contract owner.
-    ToggleHalt {},
+    ToggleHalt {
+        /// Optional note recorded in the emitted event for auditing.
+        reason: Option<String>,
+    },
 
     /// Withdraw coins from the broker smart contract balance. Only callable by
     /// the contract owner.
@@ -24,11 +101,49 @@ pub enum ExecuteMsg {
     },
 
     /// Withdraw all coins from the broker smart contract balance. Only callable
-    /// by the contract owner.
-    WithdrawAll { to: Option<String> },
+    /// by the contract owner. Denoms in `exclude_denoms` are left in the
+    /// contract.
+    WithdrawAll {
+        to: Option<String>,
+        exclude_denoms: Option<Vec<String>>,
+    },
+
+    /// Withdraw a CW20 token held by the broker smart contract. Only callable
+    /// by the contract owner. Withdraws the full CW20 balance if `amount` is
+    /// not given.
+    WithdrawCw20 {
+        contract: String,
+        to: Option<String>,
+        amount: Option<cw::Uint128>,
+    },
 
     /// TODO: owner
     EditOpers(oper_perms::Action),
+
+    /// SetOperators: Adds every address in `add` to and removes every address
+    /// in `remove` from the set of operators in one step, emitting a
+    /// "broker_bank/set_operators" event. Fails without changes if an added
+    /// address is invalid or an address is in both lists. Only callable by
+    /// the contract owner.
+    SetOperators {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+
+    /// InitiateRecovery: Starts a claim of contract ownership by the recovery
+    /// address set at instantiation. The claim can be finalized once the
+    /// configured number of blocks has passed, unless the owner vetoes it.
+    /// Only callable by the recovery address.
+    InitiateRecovery {},
+
+    /// VetoRecovery: Cancels a pending recovery. Only callable by the contract
+    /// owner.
+    VetoRecovery {},
+
+    /// FinalizeRecovery: Transfers ownership to the recovery address after the
+    /// timelock of a pending recovery has elapsed. Only callable by the
+    /// recovery address.
+    FinalizeRecovery {},
     // TODO: feat(broker-bank): Clear logs tx
 }
 
@@ -40,6 +155,60 @@ pub enum QueryMsg {
     /// operator set is "halted".
     #[returns(PermsStatus)]
     Perms {},
+
+    /// Recovery: Query the recovery config and any pending recovery.
+    #[returns(RecoveryStatus)]
+    Recovery {},
+
+    /// OperatorUsage: Query how much an operator has sent with `BankSend` in
+    /// its current window, its own limit, and what is left of the global cap
+    /// shared by all operators.
+    #[returns(OperatorUsage)]
+    OperatorUsage { operator: String },
+
+    /// Config: Query the label and description of the contract.
+    #[returns(Config)]
+    Config {},
+
+    /// ReceivedBy: Query the lifetime total of coins sent to `to` with
+    /// `BankSend`.
+    #[returns(Vec<cw::Coin>)]
+    ReceivedBy { to: String },
+
+    /// SimulateWithdrawAll: Query the coins that `WithdrawAll` with the same
+    /// `exclude_denoms` would send given the current contract balance.
+    #[returns(Vec<cw::Coin>)]
+    SimulateWithdrawAll { exclude_denoms: Option<Vec<String>> },
+
+    /// OperatorsDetailed: Query every operator with whether the owner enabled
+    /// it, its outflow limit, and what it sent this window.
+    #[returns(Vec<OperatorDetail>)]
+    OperatorsDetailed {},
+
+    /// LogsBySender: Query the most recent "LOGS" entries sent by `sender`,
+    /// newest first. Returns at most `limit` entries, 10 by default and 100
+    /// at most.
+    #[returns(Vec<crate::state::Log>)]
+    LogsBySender { sender: String, limit: Option<u32> },
+
+    /// EmergencyLogs: Query the most recent "LOGS" entries of emergency
+    /// actions, i.e. `WithdrawAll` sweeps and halts, newest first. Returns at
+    /// most `limit` entries, 10 by default and 100 at most.
+    #[returns(Vec<crate::state::Log>)]
+    EmergencyLogs { limit: Option<u32> },
+
+    /// IsAllowedRecipient: Query whether `BankSend` accepts `to` as its
+    /// recipient, resolving `to` if it is an alias. Balances and limits are
+    /// not checked.
+    #[returns(bool)]
+    IsAllowedRecipient { to: String },
+}
+
+#[cw_serde]
+pub struct Config {
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub event_prefix: Option<String>,
 }
 
 #[cw_serde]
@@ -48,10 +217,55 @@ pub struct PermsStatus {
     pub perms: oper_perms::Permissions,
 }
 
+#[cw_serde]
+pub struct RecoveryStatus {
+    pub recovery: Option<RecoveryConfig>,
+    pub pending: Option<PendingRecovery>,
+}
+
+#[cw_serde]
+pub struct OperatorUsage {
+    /// Coins sent by the operator in the current window.
+    pub spent_this_window: Vec<cw::Coin>,
+    /// The operator's own limit per window, set with `SetOperatorLimit`.
+    pub limit: Option<cw::Coin>,
+    /// Block time in seconds at which the current window ends. If the
+    /// operator has no open window, this is the end of a window starting now.
+    pub window_resets_at: u64,
+    /// Amount of the `GLOBAL_OUTFLOW_CAP` left in the current global window,
+    /// after the outflow of every operator and withdrawal. `None` without a
+    /// cap.
+    pub global_remaining: Option<cw::Coin>,
+    /// Block time in seconds at which the global window ends, which resets
+    /// `global_remaining`. `None` without a cap.
+    pub global_window_resets_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct OperatorDetail {
+    pub address: String,
+    /// False if the owner disabled the operator with `SetOperatorEnabled`.
+    /// A halt stops every operator regardless, see `PermsStatus::is_halted`.
+    pub enabled: bool,
+    /// The operator's own limit per window, set with `SetOperatorLimit`.
+    pub limit: Option<cw::Coin>,
+    /// Coins sent by the operator in the current window.
+    pub spent_this_window: Vec<cw::Coin>,
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// The owner is the only one that can use ExecuteMsg.
     pub owner: String,
     pub to_addrs: BTreeSet<String>,
     pub opers: BTreeSet<String>,
+    /// Optional account that can recover ownership through a timelocked,
+    /// owner-vetoable claim. See `ExecuteMsg::InitiateRecovery`.
+    pub recovery: Option<RecoveryConfig>,
+    /// Human-readable name of the deployment, e.g. for directory UIs.
+    pub label: Option<String>,
+    pub description: Option<String>,
+    /// Prepended to the type of every emitted event, e.g. "treasury_a"
+    /// emits "treasury_a/broker_bank/send". See `state::EVENT_PREFIX`.
+    pub event_prefix: Option<String>,
 }
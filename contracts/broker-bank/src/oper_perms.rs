@@ -1,7 +1,8 @@
 use std::collections::BTreeSet;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Storage;
+use cosmwasm_std::{Addr, Storage};
+use nibiru_std::wasm::assert_authorized;
 
 use crate::{error::ContractError, state::OPERATORS};
 
@@ -58,9 +59,10 @@ impl Permissions {
         addr: String,
     ) -> Result<Self, ContractError> {
         let perms = Self::load(storage)?;
-        match perms.is_operator(&addr) || perms.is_owner(&addr) {
-            true => Ok(perms),
-            false => Err(ContractError::NoOperatorPerms { addr }),
-        }
+        let mut allowed = perms.operators.clone();
+        allowed.extend(perms.owner.clone());
+        assert_authorized(&Addr::unchecked(&addr), &allowed, "operate")
+            .map_err(|_| ContractError::NoOperatorPerms { addr })?;
+        Ok(perms)
     }
 }
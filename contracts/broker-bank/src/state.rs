@@ -1,12 +1,16 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Event;
-use cw_storage_plus::{Deque, Item};
+use cosmwasm_std::{Coin, Event, Uint128};
+use cw_storage_plus::{Deque, Item, Map};
 use std::collections::BTreeSet;
 
 /// TO_ADDRS: Defines the set of addresses that can receive transfers from the
 /// contract.
 pub const TO_ADDRS: Item<BTreeSet<String>> = Item::new("to_addrs");
 
+/// ALIASES: Named destinations mapping an alias to an address in "TO_ADDRS".
+/// `ExecuteMsg::BankSend` accepts either an alias or a raw address.
+pub const ALIASES: Map<&str, String> = Map::new("aliases");
+
 /// OPERATORS: The set of accounts that can operate the broker smart contract.
 /// Operators cannot add or remove other operators or withdraw funds.
 pub const OPERATORS: Item<BTreeSet<String>> = Item::new("operators");
@@ -16,9 +20,126 @@ pub const OPERATORS: Item<BTreeSet<String>> = Item::new("operators");
 /// funds, withdraw, or change operator permissions.
 pub const LOGS: Deque<Log> = Deque::new("logs");
 
+/// METADATA: Human-readable label and description of the deployment, shown
+/// by directory UIs. Set at instantiation and updated by the owner.
+pub const METADATA: Item<Metadata> = Item::new("metadata");
+
+/// EVENT_PREFIX: Optional namespace set at instantiation that is prepended to
+/// the type of every event the contract emits, e.g. "treasury_a" turns
+/// "broker_bank/send" into "treasury_a/broker_bank/send". Lets indexers tell
+/// several deployments apart.
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
+
 /// IS_HALTED: An on and off switch the owner can toggle for the operators.
 pub const IS_HALTED: Item<bool> = Item::new("is_halted");
 
+/// AUTO_HALT_AFTER_BLOCKS: Optional number of blocks without operator
+/// activity after which the next `BankSend` halts the contract instead of
+/// sending. Set by the owner. Disabled if unset.
+pub const AUTO_HALT_AFTER_BLOCKS: Item<Option<u64>> =
+    Item::new("auto_halt_after_blocks");
+
+/// LAST_ACTIVITY_HEIGHT: Block height of the last successful operator action,
+/// or of the last time the owner enabled auto-halt or lifted a halt.
+pub const LAST_ACTIVITY_HEIGHT: Item<u64> = Item::new("last_activity_height");
+
+/// GLOBAL_OUTFLOW_CAP: Optional limit on the total amount of one denom that
+/// can leave the contract through `BankSend`, `Withdraw`, and `WithdrawAll`
+/// within an outflow window of `OUTFLOW_WINDOW_SECONDS`. Set by the owner.
+pub const GLOBAL_OUTFLOW_CAP: Item<Option<Coin>> =
+    Item::new("global_outflow_cap");
+
+/// OUTFLOW_WINDOW: Cumulative outflow of the capped denom in the current
+/// window.
+pub const OUTFLOW_WINDOW: Item<OutflowWindow> = Item::new("outflow_window");
+
+/// OPERATOR_SPEND: Cumulative amount each operator has sent with `BankSend` in
+/// its current window of `OUTFLOW_WINDOW_SECONDS`.
+pub const OPERATOR_SPEND: Map<&str, OperatorSpend> = Map::new("operator_spend");
+
+/// DISABLED_OPERATORS: Operators the owner disabled with `SetOperatorEnabled`.
+/// They stay operators but cannot use `BankSend` until enabled again.
+pub const DISABLED_OPERATORS: Item<BTreeSet<String>> =
+    Item::new("disabled_operators");
+
+/// OPERATOR_LIMITS: Per-operator limit, set by the owner, on how much of one
+/// denom an operator can send with `BankSend` within its window of
+/// `OUTFLOW_WINDOW_SECONDS`. Applies on top of the `GLOBAL_OUTFLOW_CAP`.
+pub const OPERATOR_LIMITS: Map<&str, Coin> = Map::new("operator_limits");
+
+/// WITHDRAW_ADDRS: Addresses that the owner's `Withdraw`, `WithdrawAll`, and
+/// `WithdrawCw20` may send to, set by the owner. Separate from the "TO_ADDRS"
+/// of operators. `None` allows any address.
+pub const WITHDRAW_ADDRS: Item<Option<BTreeSet<String>>> =
+    Item::new("withdraw_addrs");
+
+/// MIN_SEND: Smallest amount of a denom that `BankSend` may send, set by the
+/// owner. Denoms without an entry have no minimum.
+pub const MIN_SEND: Map<&str, Uint128> = Map::new("min_send");
+
+/// RECEIVED_BY: Lifetime total of coins each recipient has been sent with
+/// `BankSend`.
+pub const RECEIVED_BY: Map<&str, Vec<Coin>> = Map::new("received_by");
+
+/// Length of an outflow window for the `GLOBAL_OUTFLOW_CAP`: one day.
+pub const OUTFLOW_WINDOW_SECONDS: u64 = 24 * 60 * 60;
+
+/// SEEN_KEYS: Idempotency keys of previous `BankSend` calls, mapped to the
+/// block time in seconds at which each key was first used.
+pub const SEEN_KEYS: Map<&str, u64> = Map::new("seen_keys");
+
+/// KEY_HORIZON: Optional number of seconds after which an idempotency key in
+/// "SEEN_KEYS" expires and may be reused or pruned. Keys never expire if unset.
+pub const KEY_HORIZON: Item<Option<u64>> = Item::new("key_horizon");
+
+/// RECOVERY: Optional recovery account able to claim ownership of the contract
+/// after a timelock if the owner key is lost. Set at instantiation.
+pub const RECOVERY: Item<RecoveryConfig> = Item::new("recovery");
+
+/// PENDING_RECOVERY: An in-progress recovery claim that the owner can veto.
+pub const PENDING_RECOVERY: Item<PendingRecovery> =
+    Item::new("pending_recovery");
+
+#[cw_serde]
+pub struct RecoveryConfig {
+    /// Account allowed to initiate and finalize a recovery.
+    pub addr: String,
+    /// Number of blocks that must pass after `InitiateRecovery` before the
+    /// recovery can be finalized.
+    pub delay_blocks: u64,
+}
+
+#[cw_serde]
+pub struct PendingRecovery {
+    /// Block height at which the recovery was initiated.
+    pub initiated_height: u64,
+    /// First block height at which the recovery can be finalized.
+    pub finalize_height: u64,
+}
+
+#[cw_serde]
+pub struct OutflowWindow {
+    /// Block time in seconds at which the window started.
+    pub start: u64,
+    /// Amount of the capped denom sent out since `start`.
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct OperatorSpend {
+    /// Block time in seconds at which the operator's window started.
+    pub start: u64,
+    /// Coins sent by the operator since `start`.
+    pub spent: Vec<Coin>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Metadata {
+    pub label: Option<String>,
+    pub description: Option<String>,
+}
+
 /// Log: An entry in the "logs" state of the contract. Each `Log` records a
 /// successful execute transaction on the broker contract.
 #[cw_serde]
@@ -26,4 +147,11 @@ pub struct Log {
     pub block_height: u64,
     pub sender_addr: String,
     pub event: Event,
+    /// Contract balance of each sent denom after a `BankSend`. `None` for
+    /// every other transaction.
+    pub post_balance: Option<Vec<Coin>>,
+    /// Whether the transaction was an emergency action: a `WithdrawAll`
+    /// sweep or a halt, whether by the owner or automatic.
+    #[serde(default)]
+    pub is_emergency: bool,
 }
@@ -29,6 +29,10 @@ pub fn setup_contract(
         owner: info.sender.to_string(),
         to_addrs: to_addrs.into_iter().collect(),
         opers: opers.into_iter().collect(),
+        recovery: None,
+        label: None,
+        description: None,
+        event_prefix: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(0, res.messages.len());
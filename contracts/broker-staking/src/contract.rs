@@ -1,9 +1,15 @@
 use broker_bank::contract::{
-    assert_not_halted, edit_opers, execute_update_ownership, query_perms_status,
-    toggle_halt, withdraw, withdraw_all,
+    assert_not_halted, edit_opers, execute_update_ownership, query_config,
+    query_emergency_logs, query_is_allowed_recipient, query_logs_by_sender,
+    query_operator_usage, query_operators_detailed, query_perms_status,
+    query_received_by, query_recovery_status, query_simulate_withdraw_all,
+    set_metadata, toggle_halt, withdraw, withdraw_all,
 };
+use broker_bank::events::with_event_prefix;
 use broker_bank::oper_perms::Permissions;
-use broker_bank::state::{IS_HALTED, OPERATORS, TO_ADDRS};
+use broker_bank::state::{
+    Metadata, EVENT_PREFIX, IS_HALTED, METADATA, OPERATORS, TO_ADDRS,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
@@ -29,6 +35,16 @@ pub fn instantiate(
     TO_ADDRS.save(deps.storage, &msg.to_addrs)?;
     OPERATORS.save(deps.storage, &msg.opers)?;
     IS_HALTED.save(deps.storage, &false)?;
+    METADATA.save(
+        deps.storage,
+        &Metadata {
+            label: msg.label,
+            description: msg.description,
+        },
+    )?;
+    if let Some(event_prefix) = &msg.event_prefix {
+        EVENT_PREFIX.save(deps.storage, event_prefix)?;
+    }
 
     Ok(Response::new())
 }
@@ -41,7 +57,8 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let contract_addr = env.contract.address.to_string();
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?;
+    let res = match msg {
         ExecuteMsg::Withdraw { to, denoms } => {
             withdraw(deps, env, info, to, denoms, contract_addr)
         }
@@ -51,16 +68,22 @@ pub fn execute(
         ExecuteMsg::Unstake { unstake_msgs } => {
             unstake(deps, env, info, unstake_msgs)
         }
-        ExecuteMsg::ToggleHalt {} => toggle_halt(deps, env, info),
+        ExecuteMsg::ToggleHalt { reason } => {
+            toggle_halt(deps, env, info, reason)
+        }
         ExecuteMsg::UpdateOwnership(action) => {
             Ok(execute_update_ownership(deps, env, info, action)?)
         }
         ExecuteMsg::EditOpers(action) => edit_opers(deps, env, info, action),
         ExecuteMsg::WithdrawAll { to } => {
-            withdraw_all(deps, env, info, to, contract_addr)
+            withdraw_all(deps, env, info, to, None, contract_addr)
         }
         ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
-    }
+        ExecuteMsg::SetMetadata { label, description } => {
+            set_metadata(deps, env, info, label, description)
+        }
+    }?;
+    Ok(with_event_prefix(res, event_prefix.as_deref()))
 }
 
 pub fn claim_rewards(
@@ -189,7 +212,7 @@ fn build_unstakes_messages(
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
     msg: QueryMsg,
 ) -> Result<Binary, ContractError> {
     match msg {
@@ -200,5 +223,34 @@ pub fn query(
         QueryMsg::Ownership {} => Ok(to_json_binary(
             &nibiru_ownable::get_ownership(deps.storage)?,
         )?),
+        QueryMsg::Recovery {} => {
+            Ok(to_json_binary(&query_recovery_status(deps)?)?)
+        }
+        QueryMsg::OperatorUsage { operator } => Ok(to_json_binary(
+            &query_operator_usage(deps, &env, &operator)?,
+        )?),
+        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::ReceivedBy { to } => {
+            Ok(to_json_binary(&query_received_by(deps, &to)?)?)
+        }
+        QueryMsg::SimulateWithdrawAll { exclude_denoms } => {
+            Ok(to_json_binary(&query_simulate_withdraw_all(
+                deps,
+                env.contract.address.to_string(),
+                exclude_denoms,
+            )?)?)
+        }
+        QueryMsg::OperatorsDetailed {} => {
+            Ok(to_json_binary(&query_operators_detailed(deps, &env)?)?)
+        }
+        QueryMsg::LogsBySender { sender, limit } => Ok(to_json_binary(
+            &query_logs_by_sender(deps, &sender, limit)?,
+        )?),
+        QueryMsg::EmergencyLogs { limit } => {
+            Ok(to_json_binary(&query_emergency_logs(deps, limit)?)?)
+        }
+        QueryMsg::IsAllowedRecipient { to } => Ok(to_json_binary(
+            &query_is_allowed_recipient(deps, &env, to)?,
+        )?),
     }
 }
@@ -16,7 +16,10 @@ pub enum ExecuteMsg {
     ///
     /// When the smart contract is halted, the owner can still use the everything,
     /// while operators cannot.
-    ToggleHalt {},
+    ToggleHalt {
+        /// Optional note recorded in the emitted event for auditing.
+        reason: Option<String>,
+    },
 
     /// Withdraw coins from the broker smart contract balance. Only callable by
     /// the contract owner.
@@ -51,6 +54,13 @@ pub enum ExecuteMsg {
     },
 
     EditOpers(oper_perms::Action),
+
+    /// SetMetadata: Replaces the label and description of the contract shown
+    /// by the `Config` query. Only callable by the contract owner.
+    SetMetadata {
+        label: Option<String>,
+        description: Option<String>,
+    },
 }
 
 #[cw_serde]
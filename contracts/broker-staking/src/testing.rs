@@ -8,6 +8,7 @@ use cw_std::{
     Response, StakingMsg, Uint128,
 };
 use nibiru_std::errors::TestResult;
+use nibiru_std::tutil::test_addr;
 use serde::Serialize;
 
 use broker_bank::{
@@ -82,7 +83,7 @@ pub fn test_assert_owner() -> TestResult {
             to_addrs: to_addrs.to_vec(),
             opers: opers.to_vec(),
             sender: not_owner,
-            exec_msg: ExecuteMsg::ToggleHalt {},
+            exec_msg: ExecuteMsg::ToggleHalt { reason: None },
             err: want_err,
             contract_funds_start: None,
             resp_msgs: vec![],
@@ -273,7 +274,7 @@ pub fn exec_toggle_halt() -> TestResult {
     );
 
     // ToggleHalt : error case
-    let exec_msg = ExecuteMsg::ToggleHalt {};
+    let exec_msg = ExecuteMsg::ToggleHalt { reason: None };
     let sender = "not_owner";
     let info = mock_info_for_sender(sender);
     let exec_resp = execute(deps.as_mut(), env.clone(), info, exec_msg.clone());
@@ -309,7 +310,7 @@ pub fn exec_toggle_halt() -> TestResult {
 #[test]
 fn exec_edit_opers_add() -> TestResult {
     let (mut deps, _env, _info) = tutil::setup_contract_defaults()?;
-    let new_member = "new_member";
+    let new_member = &test_addr(3);
     let perms = Permissions::load(&deps.storage)?;
     let not_has: bool = !perms.is_owner(new_member);
     assert!(not_has);
@@ -325,7 +326,7 @@ fn exec_edit_opers_add() -> TestResult {
         assert_eq!(resp.messages.len(), 0, "resp.messages: {:?}", resp.messages);
         assert_eq!(
             resp.attributes.len(),
-            2,
+            3,
             "resp.attributes: {:#?}",
             resp.attributes
         );
@@ -379,7 +380,7 @@ fn exec_edit_opers_remove() -> TestResult {
         assert_eq!(resp.messages.len(), 0, "resp.messages: {:?}", resp.messages);
         assert_eq!(
             resp.attributes.len(),
-            2,
+            3,
             "resp.attributes: {:#?}",
             resp.attributes
         );
@@ -1,10 +1,11 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, entry_point, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response,
+    attr, entry_point, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Response, Storage, Uint256,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Item;
 use nibiru_std::{
     math::SdkDec,
     proto::{nibiru, NibiruStargateMsg},
@@ -12,8 +13,14 @@ use nibiru_std::{
 
 use crate::{
     error::ContractError,
-    msgs::{operator_perms, ExecuteMsg, InitMsg, QueryMsg},
-    state::{instantiate_perms, Permissions, OPERATORS},
+    msgs::{
+        operator_perms, ExecuteMsg, InfoResponse, InitMsg, QueryMsg,
+        ShiftBoundsResponse, SudoMsg,
+    },
+    state::{
+        instantiate_perms, Permissions, MAX_DEPTH_MULT, MAX_PEG_MULT,
+        MIN_DEPTH_MULT, MIN_PEG_MULT, OPERATORS,
+    },
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -53,12 +60,12 @@ pub fn execute(
             new_swap_invariant,
         } => {
             check.check_perms_operator()?;
-            let cosmos_msg: CosmosMsg = nibiru::perp::MsgShiftSwapInvariant {
-                sender: contract_addr,
+            let cosmos_msg = depth_shift_msg(
+                deps.storage,
+                contract_addr,
                 pair,
-                new_swap_invariant: new_swap_invariant.to_string(),
-            }
-            .into_stargate_msg();
+                new_swap_invariant,
+            )?;
             let res = Response::new()
                 .add_message(cosmos_msg)
                 .add_attributes(vec![attr("action", "shift_swap_invariant")]);
@@ -67,12 +74,8 @@ pub fn execute(
 
         ExecuteMsg::ShiftPegMultiplier { pair, new_peg_mult } => {
             check.check_perms_operator()?;
-            let cosmos_msg: CosmosMsg = nibiru::perp::MsgShiftPegMultiplier {
-                sender: contract_addr,
-                pair,
-                new_peg_mult: SdkDec::from_str(&new_peg_mult)?.pb_repr(),
-            }
-            .into_stargate_msg();
+            let cosmos_msg =
+                peg_shift_msg(deps.storage, contract_addr, pair, new_peg_mult)?;
             let res = Response::new()
                 .add_message(cosmos_msg)
                 .add_attributes(vec![attr("action", "shift_peg_multiplier")]);
@@ -108,10 +111,217 @@ pub fn execute(
             }
         }
 
+        ExecuteMsg::SetPegMultBounds { min, max } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            set_bounds(
+                deps.storage,
+                "peg multiplier",
+                min,
+                max,
+                MIN_PEG_MULT,
+                MAX_PEG_MULT,
+            )?;
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "set_peg_mult_bounds"),
+                attr("min", bound_attr(&min)),
+                attr("max", bound_attr(&max)),
+            ]))
+        }
+
+        ExecuteMsg::SetDepthMultBounds { min, max } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            set_bounds(
+                deps.storage,
+                "swap invariant",
+                min,
+                max,
+                MIN_DEPTH_MULT,
+                MAX_DEPTH_MULT,
+            )?;
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "set_depth_mult_bounds"),
+                attr("min", bound_attr(&min)),
+                attr("max", bound_attr(&max)),
+            ]))
+        }
+
         ExecuteMsg::UpdateOwnership(action) => {
             Ok(execute_update_ownership(deps, env, info, action)?)
         }
+
+        ExecuteMsg::Sweep { to, denoms } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            let to_addr = match to {
+                Some(to) => deps.api.addr_validate(&to)?.into_string(),
+                None => info.sender.to_string(),
+            };
+            let balances: Vec<Coin> = deps
+                .querier
+                .query_all_balances(&env.contract.address)?
+                .into_iter()
+                .filter(|coin| match &denoms {
+                    Some(denoms) => denoms.contains(&coin.denom),
+                    None => true,
+                })
+                .collect();
+
+            let mut res = Response::new().add_attributes(vec![
+                attr("action", "sweep"),
+                attr("to", to_addr.as_str()),
+            ]);
+            if !balances.is_empty() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: to_addr,
+                    amount: balances,
+                });
+            }
+            Ok(res)
+        }
+    }
+}
+
+/// The sudo entry point can only be called by the chain, e.g. through a
+/// governance proposal, so shifts sent with it skip the operator check. The
+/// owner-set bounds still apply.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(
+    deps: DepsMut,
+    env: Env,
+    msg: SudoMsg,
+) -> Result<Response, ContractError> {
+    let contract_addr = env.contract.address.to_string();
+    match msg {
+        SudoMsg::ForcePegShift { pair, peg_mult } => {
+            let cosmos_msg =
+                peg_shift_msg(deps.storage, contract_addr, pair, peg_mult)?;
+            Ok(Response::new()
+                .add_message(cosmos_msg)
+                .add_attributes(vec![attr("action", "force_peg_shift")]))
+        }
+        SudoMsg::ForceDepthShift {
+            pair,
+            swap_invariant,
+        } => {
+            let cosmos_msg = depth_shift_msg(
+                deps.storage,
+                contract_addr,
+                pair,
+                swap_invariant,
+            )?;
+            Ok(Response::new()
+                .add_message(cosmos_msg)
+                .add_attributes(vec![attr("action", "force_depth_shift")]))
+        }
+    }
+}
+
+/// Checks `new_peg_mult` against the peg multiplier bounds and builds the
+/// x/perp message that shifts the peg of `pair`.
+fn peg_shift_msg(
+    storage: &dyn Storage,
+    contract_addr: String,
+    pair: String,
+    new_peg_mult: String,
+) -> Result<CosmosMsg, ContractError> {
+    if MIN_PEG_MULT.exists(storage) || MAX_PEG_MULT.exists(storage) {
+        check_bounds(
+            storage,
+            "peg multiplier",
+            &Decimal::from_str(&new_peg_mult)?,
+            MIN_PEG_MULT,
+            MAX_PEG_MULT,
+        )?;
+    }
+    Ok(nibiru::perp::MsgShiftPegMultiplier {
+        sender: contract_addr,
+        pair,
+        new_peg_mult: SdkDec::from_str(&new_peg_mult)?.pb_repr(),
+    }
+    .into_stargate_msg())
+}
+
+/// Checks `new_swap_invariant` against the depth bounds and builds the x/perp
+/// message that shifts the swap invariant of `pair`.
+fn depth_shift_msg(
+    storage: &dyn Storage,
+    contract_addr: String,
+    pair: String,
+    new_swap_invariant: Uint256,
+) -> Result<CosmosMsg, ContractError> {
+    check_bounds(
+        storage,
+        "swap invariant",
+        &new_swap_invariant,
+        MIN_DEPTH_MULT,
+        MAX_DEPTH_MULT,
+    )?;
+    Ok(nibiru::perp::MsgShiftSwapInvariant {
+        sender: contract_addr,
+        pair,
+        new_swap_invariant: new_swap_invariant.to_string(),
+    }
+    .into_stargate_msg())
+}
+
+/// Errors if `value` is outside of the bounds stored in `min_item` and
+/// `max_item`. Unset bounds are not checked.
+fn check_bounds<T>(
+    storage: &dyn Storage,
+    name: &str,
+    value: &T,
+    min_item: Item<T>,
+    max_item: Item<T>,
+) -> Result<(), ContractError>
+where
+    T: PartialOrd + ToString + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let min = min_item.may_load(storage)?;
+    let max = max_item.may_load(storage)?;
+    let below_min = min.as_ref().is_some_and(|min| value < min);
+    let above_max = max.as_ref().is_some_and(|max| value > max);
+    if below_min || above_max {
+        return Err(ContractError::OutOfBounds {
+            name: name.to_string(),
+            value: value.to_string(),
+            min: min.map(|min| min.to_string()),
+            max: max.map(|max| max.to_string()),
+        });
+    }
+    Ok(())
+}
+
+/// Saves the given bounds, removing the ones that are `None`.
+fn set_bounds<T>(
+    storage: &mut dyn Storage,
+    name: &str,
+    min: Option<T>,
+    max: Option<T>,
+    min_item: Item<T>,
+    max_item: Item<T>,
+) -> Result<(), ContractError>
+where
+    T: PartialOrd + ToString + serde::Serialize + serde::de::DeserializeOwned,
+{
+    if let (Some(min), Some(max)) = (&min, &max) {
+        if min > max {
+            return Err(ContractError::InvalidBounds {
+                name: name.to_string(),
+                min: min.to_string(),
+                max: max.to_string(),
+            });
+        }
     }
+    for (bound, item) in [(min, min_item), (max, max_item)] {
+        match bound {
+            Some(bound) => item.save(storage, &bound)?,
+            None => item.remove(storage),
+        }
+    }
+    Ok(())
+}
+
+fn bound_attr<T: ToString>(bound: &Option<T>) -> String {
+    bound.as_ref().map(T::to_string).unwrap_or_default()
 }
 
 fn execute_update_ownership(
@@ -180,6 +390,26 @@ pub fn query(
             let res = operator_perms::PermsResponse { perms };
             Ok(cosmwasm_std::to_json_binary(&res)?)
         }
+        QueryMsg::ShiftBounds {} => {
+            let res = ShiftBoundsResponse {
+                min_peg_mult: MIN_PEG_MULT.may_load(deps.storage)?,
+                max_peg_mult: MAX_PEG_MULT.may_load(deps.storage)?,
+                min_depth_mult: MIN_DEPTH_MULT.may_load(deps.storage)?,
+                max_depth_mult: MAX_DEPTH_MULT.may_load(deps.storage)?,
+            };
+            Ok(cosmwasm_std::to_json_binary(&res)?)
+        }
+        QueryMsg::Info {} => {
+            let perms = Permissions::load(deps.storage)?;
+            let res = InfoResponse {
+                admin: perms.owner.unwrap_or_default(),
+                member_count: perms.operators.len() as u64,
+            };
+            Ok(cosmwasm_std::to_json_binary(&res)?)
+        }
+        QueryMsg::Ownership {} => Ok(cosmwasm_std::to_json_binary(
+            &nibiru_ownable::get_ownership(deps.storage)?,
+        )?),
     }
 }
 
@@ -187,12 +417,12 @@ pub fn query(
 pub mod tests {
     use super::*;
     use crate::{
-        msgs::{ExecuteMsg, InitMsg},
+        msgs::{ExecuteMsg, InitMsg, SudoMsg},
         state::OPERATORS,
         testing::{self as t, TestResult},
     };
 
-    use cosmwasm_std::{coins, testing};
+    use cosmwasm_std::{coins, testing, SubMsg, Uint256};
     use std::collections::BTreeSet;
 
     use easy_addr::addr;
@@ -301,6 +531,33 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_query_info() -> TestResult {
+        let (mut deps, _env, _info) = t::setup_contract()?;
+        for member in [addr!("member0"), addr!("member1")] {
+            execute(
+                deps.as_mut(),
+                testing::mock_env(),
+                testing::mock_info(t::TEST_OWNER, &[]),
+                ExecuteMsg::EditOpers(operator_perms::Action::AddOper {
+                    address: member.to_string(),
+                }),
+            )?;
+        }
+
+        let binary =
+            query(deps.as_ref(), testing::mock_env(), QueryMsg::Info {})?;
+        let response: InfoResponse = cosmwasm_std::from_json(binary)?;
+        assert_eq!(
+            response,
+            InfoResponse {
+                admin: t::TEST_OWNER.to_string(),
+                member_count: 2,
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_exec_edit_opers_remove() -> TestResult {
         let (mut deps, _env, _info) = t::setup_contract()?;
@@ -361,9 +618,326 @@ pub mod tests {
         Ok(())
     }
 
-    /// TODO: test change owner
     #[test]
     fn test_exec_change_admin() -> TestResult {
+        let (mut deps, env, _info) = t::setup_contract()?;
+        let query_ownership =
+            |deps: Deps| -> anyhow::Result<nibiru_ownable::Ownership<String>> {
+                let binary = query(deps, env.clone(), QueryMsg::Ownership {})?;
+                Ok(cosmwasm_std::from_json(binary)?)
+            };
+        let ownership = query_ownership(deps.as_ref())?;
+        assert_eq!(ownership.owner, Some(t::TEST_OWNER.to_string()));
+        assert_eq!(ownership.pending_owner, None);
+
+        // Only the owner can propose a transfer
+        let new_owner = "new-owner";
+        let transfer_msg = ExecuteMsg::UpdateOwnership(
+            nibiru_ownable::Action::TransferOwnership {
+                new_owner: new_owner.to_string(),
+                expiry: None,
+            },
+        );
+        let result = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(new_owner, &[]),
+            transfer_msg.clone(),
+        );
+        assert!(result.is_err());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(t::TEST_OWNER, &[]),
+            transfer_msg,
+        )?;
+
+        // The transfer is pending until accepted
+        let ownership = query_ownership(deps.as_ref())?;
+        assert_eq!(ownership.owner, Some(t::TEST_OWNER.to_string()));
+        assert_eq!(ownership.pending_owner, Some(new_owner.to_string()));
+
+        let accept_msg =
+            ExecuteMsg::UpdateOwnership(nibiru_ownable::Action::AcceptOwnership);
+        let result = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info("not-pending-owner", &[]),
+            accept_msg.clone(),
+        );
+        assert!(result.is_err());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(new_owner, &[]),
+            accept_msg,
+        )?;
+
+        let ownership = query_ownership(deps.as_ref())?;
+        assert_eq!(ownership.owner, Some(new_owner.to_string()));
+        assert_eq!(ownership.pending_owner, None);
+
+        // Admin rights move with ownership
+        let new_oper = addr!("new_oper");
+        let add_oper_msg =
+            ExecuteMsg::EditOpers(operator_perms::Action::AddOper {
+                address: new_oper.to_string(),
+            });
+        let result = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(t::TEST_OWNER, &[]),
+            add_oper_msg.clone(),
+        );
+        assert!(result.is_err());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(new_owner, &[]),
+            add_oper_msg,
+        )?;
+        let perms = Permissions::load(&deps.storage)?;
+        assert!(!perms.is_owner(t::TEST_OWNER));
+        assert!(perms.is_owner(new_owner));
+        assert!(perms.is_operator(new_oper));
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_sweep() -> TestResult {
+        let (mut deps, env, _info) = t::setup_contract()?;
+        let contract_addr = env.contract.address.to_string();
+        let balances = [Coin::new(420u128, "unibi"), Coin::new(69u128, "uusd")];
+        deps.querier = testing::MockQuerier::new(&[(
+            contract_addr.as_str(),
+            balances.as_slice(),
+        )]);
+
+        // Non-owners cannot sweep
+        let execute_msg = ExecuteMsg::Sweep {
+            to: None,
+            denoms: None,
+        };
+        let result = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info("not-owner", &[]),
+            execute_msg.clone(),
+        );
+        assert!(result.is_err());
+
+        // Sweep everything to the owner by default
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(t::TEST_OWNER, &[]),
+            execute_msg,
+        )?;
+        assert_eq!(
+            resp.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: t::TEST_OWNER.to_string(),
+                amount: balances.to_vec(),
+            })]
+        );
+        assert_eq!(resp.attributes[0], attr("action", "sweep"));
+
+        // Sweep a single denom to a given recipient
+        let to = addr!("recipient");
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            testing::mock_info(t::TEST_OWNER, &[]),
+            ExecuteMsg::Sweep {
+                to: Some(to.to_string()),
+                denoms: Some(vec!["uusd".to_string()]),
+            },
+        )?;
+        assert_eq!(
+            resp.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin::new(69u128, "uusd")],
+            })]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sudo_force_shifts() -> TestResult {
+        let (mut deps, env, _info) = t::setup_contract()?;
+        let contract_addr = env.contract.address.to_string();
+
+        // No operator is involved in a sudo call
+        let resp = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::ForcePegShift {
+                pair: "ubtc:unusd".to_string(),
+                peg_mult: "1.5".to_string(),
+            },
+        )?;
+        let want_msg: CosmosMsg = nibiru::perp::MsgShiftPegMultiplier {
+            sender: contract_addr.clone(),
+            pair: "ubtc:unusd".to_string(),
+            new_peg_mult: SdkDec::from_str("1.5")?.pb_repr(),
+        }
+        .into_stargate_msg();
+        assert_eq!(resp.messages, vec![SubMsg::new(want_msg)]);
+        assert_eq!(resp.attributes, vec![attr("action", "force_peg_shift")]);
+
+        let resp = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::ForceDepthShift {
+                pair: "ubtc:unusd".to_string(),
+                swap_invariant: Uint256::from(42u128),
+            },
+        )?;
+        let want_msg: CosmosMsg = nibiru::perp::MsgShiftSwapInvariant {
+            sender: contract_addr,
+            pair: "ubtc:unusd".to_string(),
+            new_swap_invariant: "42".to_string(),
+        }
+        .into_stargate_msg();
+        assert_eq!(resp.messages, vec![SubMsg::new(want_msg)]);
+
+        // The owner-set bounds still apply
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info(t::TEST_OWNER, &[]),
+            ExecuteMsg::SetDepthMultBounds {
+                min: None,
+                max: Some(Uint256::from(10u128)),
+            },
+        )?;
+        let err = sudo(
+            deps.as_mut(),
+            env,
+            SudoMsg::ForceDepthShift {
+                pair: "ubtc:unusd".to_string(),
+                swap_invariant: Uint256::from(42u128),
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("out of bounds"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_exec_shift_bounds() -> TestResult {
+        let (mut deps, env, _info) = t::setup_contract()?;
+        let owner = testing::mock_info(t::TEST_OWNER, &[]);
+        let peg_shift = |new_peg_mult: &str| ExecuteMsg::ShiftPegMultiplier {
+            pair: "ubtc:unusd".to_string(),
+            new_peg_mult: new_peg_mult.to_string(),
+        };
+        let depth_shift =
+            |new_swap_invariant: u128| ExecuteMsg::ShiftSwapInvariant {
+                pair: "ubtc:unusd".to_string(),
+                new_swap_invariant: Uint256::from(new_swap_invariant),
+            };
+
+        // Without bounds, any shift goes through
+        execute(deps.as_mut(), env.clone(), owner.clone(), peg_shift("1000"))?;
+
+        // Only the owner can set bounds
+        let set_peg_bounds = ExecuteMsg::SetPegMultBounds {
+            min: Some(Decimal::percent(50)),
+            max: Some(Decimal::percent(200)),
+        };
+        let result = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info("not-owner", &[]),
+            set_peg_bounds.clone(),
+        );
+        assert!(result.is_err());
+        execute(deps.as_mut(), env.clone(), owner.clone(), set_peg_bounds)?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            ExecuteMsg::SetDepthMultBounds {
+                min: None,
+                max: Some(Uint256::from(1_000_000u128)),
+            },
+        )?;
+        let binary =
+            query(deps.as_ref(), env.clone(), QueryMsg::ShiftBounds {})?;
+        let bounds: ShiftBoundsResponse = cosmwasm_std::from_json(binary)?;
+        assert_eq!(
+            bounds,
+            ShiftBoundsResponse {
+                min_peg_mult: Some(Decimal::percent(50)),
+                max_peg_mult: Some(Decimal::percent(200)),
+                min_depth_mult: None,
+                max_depth_mult: Some(Uint256::from(1_000_000u128)),
+            }
+        );
+
+        // In-bounds shifts succeed
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            peg_shift("1.5"),
+        )?;
+        assert_eq!(resp.messages.len(), 1);
+        let resp =
+            execute(deps.as_mut(), env.clone(), owner.clone(), depth_shift(42))?;
+        assert_eq!(resp.messages.len(), 1);
+
+        // Out-of-bounds shifts fail
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            peg_shift("1000"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OutOfBounds {
+                name: "peg multiplier".to_string(),
+                value: "1000".to_string(),
+                min: Some("0.5".to_string()),
+                max: Some("2".to_string()),
+            }
+        );
+        assert!(err.to_string().contains("out of bounds"));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            depth_shift(1_000_001),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OutOfBounds { .. }));
+
+        // Inverted bounds are rejected, and cleared bounds aren't checked
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            ExecuteMsg::SetPegMultBounds {
+                min: Some(Decimal::percent(200)),
+                max: Some(Decimal::percent(50)),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBounds { .. }));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            ExecuteMsg::SetPegMultBounds {
+                min: None,
+                max: None,
+            },
+        )?;
+        execute(deps.as_mut(), env, owner, peg_shift("1000"))?;
         Ok(())
     }
 }
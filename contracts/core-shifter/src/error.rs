@@ -16,4 +16,19 @@ pub enum ContractError {
 
     #[error("{0}")]
     MathError(#[from] errors::MathError),
+
+    #[error("{name} {value} out of bounds (min: {min:?}, max: {max:?})")]
+    OutOfBounds {
+        name: String,
+        value: String,
+        min: Option<String>,
+        max: Option<String>,
+    },
+
+    #[error("invalid {name} bounds: min {min} is greater than max {max}")]
+    InvalidBounds {
+        name: String,
+        min: String,
+        max: String,
+    },
 }
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint256;
+use cosmwasm_std::{Decimal, Uint256};
 
 /// InitMsg specifies the args for the instantiate entry point of the contract.
 #[cw_serde]
@@ -20,6 +20,42 @@ pub enum ExecuteMsg {
         new_peg_mult: String,
     },
     EditOpers(operator_perms::Action),
+    /// SetPegMultBounds: Sets the bounds that `ShiftPegMultiplier` checks
+    /// `new_peg_mult` against. A `None` bound is cleared. Only callable by the
+    /// contract owner.
+    SetPegMultBounds {
+        min: Option<Decimal>,
+        max: Option<Decimal>,
+    },
+    /// SetDepthMultBounds: Sets the bounds that `ShiftSwapInvariant` checks
+    /// `new_swap_invariant` against. A `None` bound is cleared. Only callable
+    /// by the contract owner.
+    SetDepthMultBounds {
+        min: Option<Uint256>,
+        max: Option<Uint256>,
+    },
+    /// Sweep: Sends the contract's bank balance to `to`, or to the owner if
+    /// `to` is not given. If `denoms` is given, only those denoms are swept.
+    /// Only callable by the contract owner.
+    Sweep {
+        to: Option<String>,
+        denoms: Option<Vec<String>>,
+    },
+}
+
+/// SudoMsg specifies the args for the sudo entry point of the contract, which
+/// only the chain can call, e.g. through a governance proposal.
+#[cw_serde]
+pub enum SudoMsg {
+    /// ForcePegShift: Shifts the peg multiplier of `pair` to `peg_mult`
+    /// without requiring an operator.
+    ForcePegShift { pair: String, peg_mult: String },
+    /// ForceDepthShift: Shifts the swap invariant of `pair` to
+    /// `swap_invariant` without requiring an operator.
+    ForceDepthShift {
+        pair: String,
+        swap_invariant: Uint256,
+    },
 }
 
 pub mod operator_perms {
@@ -46,8 +82,9 @@ pub mod operator_perms {
 }
 
 /// QueryMsg specifies the args for the query entry point of the contract.
-#[derive(QueryResponses)]
+#[nibiru_ownable::ownable_query]
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
     /// HasPerms: Query whether the given address has operator permissions.
     /// The query response showcases the contract owner and set of operators.
@@ -56,4 +93,26 @@ pub enum QueryMsg {
     /// Perms: Query the contract owner and set of operators.
     #[returns(operator_perms::PermsResponse)]
     Perms {},
+    /// ShiftBounds: Query the bounds checked by the peg and depth shifts.
+    #[returns(ShiftBoundsResponse)]
+    ShiftBounds {},
+    /// Info: Query the contract owner and the number of operators, a compact
+    /// alternative to `Perms` for UIs that don't need the full operator set.
+    #[returns(InfoResponse)]
+    Info {},
+}
+
+#[cw_serde]
+pub struct InfoResponse {
+    /// Contract owner, or an empty string if ownership was renounced.
+    pub admin: String,
+    pub member_count: u64,
+}
+
+#[cw_serde]
+pub struct ShiftBoundsResponse {
+    pub min_peg_mult: Option<Decimal>,
+    pub max_peg_mult: Option<Decimal>,
+    pub min_depth_mult: Option<Uint256>,
+    pub max_depth_mult: Option<Uint256>,
 }
@@ -1,13 +1,23 @@
 use std::collections::BTreeSet;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Api, Storage};
+use cosmwasm_std::{Api, Decimal, Storage, Uint256};
 use cw_storage_plus::Item;
 
 use crate::error::ContractError;
 
 pub const OPERATORS: Item<BTreeSet<String>> = Item::new("operators");
 
+/// Bounds on the `new_peg_mult` of a `ShiftPegMultiplier`, set by the owner.
+/// An unset bound is not checked.
+pub const MIN_PEG_MULT: Item<Decimal> = Item::new("min_peg_mult");
+pub const MAX_PEG_MULT: Item<Decimal> = Item::new("max_peg_mult");
+
+/// Bounds on the `new_swap_invariant` of a `ShiftSwapInvariant` (depth
+/// shift), set by the owner. An unset bound is not checked.
+pub const MIN_DEPTH_MULT: Item<Uint256> = Item::new("min_depth_mult");
+pub const MAX_DEPTH_MULT: Item<Uint256> = Item::new("max_depth_mult");
+
 #[cw_serde]
 pub struct Permissions {
     pub owner: Option<String>,
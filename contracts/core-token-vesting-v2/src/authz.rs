@@ -0,0 +1,76 @@
+//! Minimal protobuf types for delegating on behalf of a user through the
+//! authz module. The user must first grant this contract an authorization for
+//! "/cosmos.staking.v1beta1.MsgDelegate", e.g. a `StakeAuthorization`.
+
+use cosmwasm_std::{Binary, Coin, CosmosMsg};
+use prost::Message;
+
+pub const MSG_EXEC_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgExec";
+pub const MSG_DELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegate";
+
+/// cosmos.base.v1beta1.Coin
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// cosmos.staking.v1beta1.MsgDelegate
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgDelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<ProtoCoin>,
+}
+
+/// google.protobuf.Any
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoAny {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// cosmos.authz.v1beta1.MsgExec
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgExec {
+    #[prost(string, tag = "1")]
+    pub grantee: String,
+    #[prost(message, repeated, tag = "2")]
+    pub msgs: Vec<ProtoAny>,
+}
+
+/// Builds a `MsgExec`, signed by `grantee`, that delegates `amount` from
+/// `delegator` to `validator`.
+pub fn delegate_on_behalf(
+    grantee: &str,
+    delegator: &str,
+    validator: &str,
+    amount: &Coin,
+) -> CosmosMsg {
+    let delegate = MsgDelegate {
+        delegator_address: delegator.to_string(),
+        validator_address: validator.to_string(),
+        amount: Some(ProtoCoin {
+            denom: amount.denom.clone(),
+            amount: amount.amount.to_string(),
+        }),
+    };
+    let exec = MsgExec {
+        grantee: grantee.to_string(),
+        msgs: vec![ProtoAny {
+            type_url: MSG_DELEGATE_TYPE_URL.to_string(),
+            value: delegate.encode_to_vec(),
+        }],
+    };
+    CosmosMsg::Stargate {
+        type_url: MSG_EXEC_TYPE_URL.to_string(),
+        value: Binary::from(exec.encode_to_vec()),
+    }
+}
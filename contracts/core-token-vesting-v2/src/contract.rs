@@ -2,43 +2,60 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_json_binary, Attribute, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut,
-    Env, MessageInfo, Response, StdError, StdResult, Storage, Timestamp,
+    Env, Event, MessageInfo, Response, StdError, StdResult, Storage, Timestamp,
     Uint128,
 };
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
 use std::cmp::min;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde_json::to_string;
 
-use crate::errors::ContractError;
+use crate::authz::delegate_on_behalf;
+use crate::errors::{ContractError, VestingError};
 use crate::msg::{
-    from_vesting_to_query_output, DeregisterUserResponse, ExecuteMsg,
-    InstantiateMsg, QueryMsg, RewardUserRequest, RewardUserResponse,
-    VestingAccountResponse, VestingData, VestingSchedule,
+    from_query_output_to_vesting, from_vesting_to_query_output,
+    AggregateResponse, ClaimPreviewResponse, ClaimResponse,
+    ClaimedAtHeightResponse, DeregisterUserResponse, ExecuteMsg,
+    ExpiringScheduleResponse, InstantiateMsg, ManagerStatus, ManagersResponse,
+    MigrateMsg, NextUnlockResponse, ParamsResponse, QueryMsg, RewardUserRequest,
+    RewardUserResponse, ScheduleProgress, SimulateDeregisterResponse,
+    SolvencyResponse, UnderfundedScheduleResponse, VestingAccountResponse,
+    VestingData, VestingRounding, VestingSchedule,
 };
 use crate::state::{
-    VestingAccount, Whitelist, DENOM, UNALLOCATED_AMOUNT, VESTING_ACCOUNTS,
-    WHITELIST,
+    funded_denoms, has_schedules, load_schedules, load_unallocated,
+    resolve_denom, save_new_schedule, save_unallocated, schedule_denom,
+    unclaimed_by_denom, Config, VestingAccount, Whitelist, CLAIMED_TOTAL,
+    CONFIG, DENOM, LAST_CLAIM_HEIGHT, LEGACY_VESTING_ACCOUNTS, RELAYERS,
+    UNALLOCATED_AMOUNT, UNALLOCATED_AMOUNTS, VESTING_ACCOUNTS, WHITELIST,
 };
 
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     // Funds validation
-    if info.funds.len() != 1 {
-        return Err(StdError::generic_err(
-            "must deposit exactly one type of token",
-        ));
+    let multi_denom = msg.multi_denom.unwrap_or(false);
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFundsSent);
+    }
+    if !multi_denom && info.funds.len() > 1 {
+        return Err(ContractError::MultipleDenoms);
     }
-    if info.funds[0].amount.is_zero() {
-        return Err(StdError::generic_err("must deposit some token"));
+    if info.funds.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::ZeroAmount);
     }
     // Managers validation
     if msg.managers.is_empty() {
-        return Err(StdError::generic_err("managers cannot be empty"));
+        return Err(StdError::generic_err("managers cannot be empty").into());
     }
 
     deps.api.addr_validate(&msg.admin)?;
@@ -46,11 +63,14 @@ pub fn instantiate(
         deps.api.addr_validate(manager)?;
     }
 
-    let unallocated_amount = info.funds[0].amount;
-    let denom = &info.funds[0].denom;
-
-    UNALLOCATED_AMOUNT.save(deps.storage, &unallocated_amount)?;
-    DENOM.save(deps.storage, denom)?;
+    if multi_denom {
+        for coin in info.funds.iter() {
+            UNALLOCATED_AMOUNTS.save(deps.storage, &coin.denom, &coin.amount)?;
+        }
+    } else {
+        UNALLOCATED_AMOUNT.save(deps.storage, &info.funds[0].amount)?;
+        DENOM.save(deps.storage, &info.funds[0].denom)?;
+    }
     WHITELIST.save(
         deps.storage,
         &Whitelist {
@@ -58,8 +78,91 @@ pub fn instantiate(
             admin: msg.admin,
         },
     )?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            decimals: msg.decimals,
+            deregister_requires_force_before_cliff: msg
+                .deregister_requires_force_before_cliff
+                .unwrap_or(false),
+            multi_denom,
+            claim_cooldown_blocks: msg.claim_cooldown_blocks,
+            rounding: msg.rounding.unwrap_or_default(),
+        },
+    )?;
+    set_contract_version(
+        deps.storage,
+        format!("crates.io:{CONTRACT_NAME}"),
+        CONTRACT_VERSION,
+    )?;
+
+    match msg.initial_rewards {
+        Some((rewards, vesting_schedule)) => {
+            if info.funds.len() > 1 {
+                return Err(StdError::generic_err(
+                    "initial_rewards requires a single deposited denom",
+                )
+                .into());
+            }
+            let denom = Some(info.funds[0].denom.clone());
+            register_rewards(deps, rewards, vesting_schedule, denom, true)
+        }
+        None => Ok(Response::new()),
+    }
+}
+
+/// Upgrades the state of an existing deployment. Schedules stored under the
+/// old single-schedule layout are moved to the `(address, index)` keyed
+/// `VESTING_ACCOUNTS`, dropping fully claimed ones, and contracts that predate
+/// `CONFIG` get the default config.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let contract_name = format!("crates.io:{CONTRACT_NAME}");
+    // Deployments from before the contract version was recorded have none.
+    let from_version = match get_contract_version(deps.storage) {
+        Ok(stored) if stored.contract != contract_name => {
+            return Err(StdError::generic_err(format!(
+                "Cannot migrate from contract {} to {}",
+                stored.contract, contract_name
+            ))
+            .into());
+        }
+        Ok(stored) => stored.version,
+        Err(_) => "unknown".to_string(),
+    };
+
+    let legacy_accounts: Vec<(String, VestingAccount)> = LEGACY_VESTING_ACCOUNTS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for (address, account) in &legacy_accounts {
+        LEGACY_VESTING_ACCOUNTS.remove(deps.storage, address);
+        if account.claimed_amount < account.vesting_amount {
+            save_new_schedule(deps.storage, account)?;
+        }
+    }
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                decimals: None,
+                deregister_requires_force_before_cliff: false,
+                multi_denom: false,
+                claim_cooldown_blocks: None,
+                rounding: VestingRounding::Floor,
+            },
+        )?;
+    }
 
-    Ok(Response::new())
+    set_contract_version(deps.storage, contract_name, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("migrated_accounts", legacy_accounts.len().to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -73,12 +176,48 @@ pub fn execute(
         ExecuteMsg::RewardUsers {
             rewards,
             vesting_schedule,
-        } => reward_users(deps, env, info, rewards, vesting_schedule),
-        ExecuteMsg::DeregisterVestingAccounts { addresses } => {
-            deregister_vesting_accounts(deps, env, info, addresses)
+            denom,
+        } => reward_users(deps, env, info, rewards, vesting_schedule, denom),
+        ExecuteMsg::DeregisterVestingAccounts { addresses, force } => {
+            deregister_vesting_accounts(deps, env, info, addresses, force)
+        }
+        ExecuteMsg::Claim { limit } => {
+            let recipient = info.sender.to_string();
+            claim(deps, env, &recipient, limit)
+        }
+        ExecuteMsg::ClaimAndDelegate { validator } => {
+            claim_and_delegate(deps, env, info, validator)
+        }
+        ExecuteMsg::ClaimFor { user_address } => {
+            claim_for(deps, env, info, user_address)
+        }
+        ExecuteMsg::AddRelayer { address } => {
+            edit_relayers(deps, info, address, true)
+        }
+        ExecuteMsg::RemoveRelayer { address } => {
+            edit_relayers(deps, info, address, false)
+        }
+        ExecuteMsg::Withdraw { amount, denom } => {
+            withdraw(deps, env, info, amount, denom)
+        }
+        ExecuteMsg::ImportAccounts { accounts } => {
+            import_accounts(deps, env, info, accounts)
+        }
+        ExecuteMsg::Clawback {
+            user_address,
+            reduce_by,
+            schedule_index,
+        } => clawback(deps, env, info, user_address, reduce_by, schedule_index),
+        ExecuteMsg::ReclaimSurplus { to } => {
+            reclaim_surplus(deps, env, info, to)
+        }
+        ExecuteMsg::ConfirmMilestone {
+            user_address,
+            schedule_index,
+        } => confirm_milestone(deps, info, user_address, schedule_index),
+        ExecuteMsg::TransferVesting { from, to } => {
+            transfer_vesting(deps, env, info, from, to)
         }
-        ExecuteMsg::Claim {} => claim(deps, env, info),
-        ExecuteMsg::Withdraw { amount } => withdraw(deps, env, info, amount),
     }
 }
 
@@ -87,13 +226,14 @@ pub fn execute(
 /// Ensures the requested amount is less than or equal to the unallocated amount
 pub fn withdraw(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
+    denom: Option<String>,
 ) -> Result<Response, ContractError> {
     let whitelist = WHITELIST.load(deps.storage)?;
-    let mut unallocated_amount = UNALLOCATED_AMOUNT.load(deps.storage)?;
-    let denom = DENOM.load(deps.storage)?;
+    let denom = resolve_denom(deps.storage, denom)?;
+    let mut unallocated_amount = load_unallocated(deps.storage, &denom)?;
 
     if !whitelist.is_admin(&info.sender) {
         return Err(StdError::generic_err("Unauthorized").into());
@@ -104,27 +244,183 @@ pub fn withdraw(
     if amount_max.is_zero() {
         return Err(StdError::generic_err("Nothing to withdraw").into());
     }
+    // The unallocated amount may drift from the balance, so also make sure
+    // the withdrawal never dips into tokens owed to vesting accounts.
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, &denom)?
+        .amount;
+    let reserved = unclaimed_by_denom(deps.storage)?
+        .remove(&denom)
+        .unwrap_or_default();
+    let free_balance = balance.saturating_sub(reserved);
+    if amount_max > free_balance {
+        return Err(StdError::generic_err(format!(
+            "Cannot withdraw {}{}, only {} is not reserved for vesting accounts",
+            amount_max, denom, free_balance
+        ))
+        .into());
+    }
 
+    let old_unallocated_amount = unallocated_amount;
     unallocated_amount -= amount_max;
-    UNALLOCATED_AMOUNT.save(deps.storage, &unallocated_amount)?;
+    save_unallocated(deps.storage, &denom, &unallocated_amount)?;
 
     Ok(Response::new()
         .add_messages(vec![build_send_msg(&denom, amount_max, recipient)])
+        .add_event(event_unallocated_changed(
+            deps.storage,
+            &denom,
+            old_unallocated_amount,
+            unallocated_amount,
+        )?)
         .add_attribute("action", "withdraw")
         .add_attribute("recipient", recipient)
         .add_attribute("amount", amount_max.to_string())
         .add_attribute("unallocated_amount", unallocated_amount.to_string()))
 }
 
+/// Allow the contract admin to register vesting accounts from precomputed
+/// vesting data, preserving the amount already claimed by each account.
+fn import_accounts(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    accounts: Vec<(String, VestingData)>,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+
+    // multi_denom contracts import in the denom of the first account
+    let requested_denom = match accounts.first() {
+        Some((_, data)) if CONFIG.load(deps.storage)?.multi_denom => {
+            match &data.vesting_denom {
+                cw20::Denom::Native(denom) => Some(denom.clone()),
+                cw20::Denom::Cw20(_) => None,
+            }
+        }
+        _ => None,
+    };
+    let denom = resolve_denom(deps.storage, requested_denom)?;
+    let old_unallocated_amount = load_unallocated(deps.storage, &denom)?;
+    let mut unallocated_amount = old_unallocated_amount;
+    let rounding = CONFIG.load(deps.storage)?.rounding;
+
+    let mut attrs: Vec<Attribute> = vec![];
+    for (address, data) in accounts {
+        deps.api.addr_validate(&address)?;
+        if data.vesting_denom != cw20::Denom::Native(denom.clone()) {
+            return Err(StdError::generic_err(format!(
+                "Cannot import vesting for {} in denom {:?}, expected {}",
+                address, data.vesting_denom, denom
+            ))
+            .into());
+        }
+
+        let (vesting_schedule, vesting_amount, cliff_amount) =
+            from_query_output_to_vesting(&data.vesting_schedule);
+        if vesting_amount != data.vesting_amount {
+            return Err(StdError::generic_err(format!(
+                "Vesting amount of {} ({}) does not match its schedule ({})",
+                address, data.vesting_amount, vesting_amount
+            ))
+            .into());
+        }
+        RewardUserRequest {
+            user_address: address.clone(),
+            vesting_amount,
+            cliff_amount,
+            payout_address: None,
+            label: data.label.clone(),
+        }
+        .validate()?;
+        vesting_schedule.validate()?;
+        vesting_schedule.validate_amounts(vesting_amount, cliff_amount)?;
+        let milestones_confirmed = data.vesting_schedule.milestones_confirmed();
+        if let VestingSchedule::Milestone { tranches } = &vesting_schedule {
+            if milestones_confirmed > tranches.len() as u64 {
+                return Err(StdError::generic_err(format!(
+                    "Vesting schedule of {} confirms {} milestones but has only {} tranches",
+                    address, milestones_confirmed, tranches.len()
+                ))
+                .into());
+            }
+        }
+
+        let claimed_amount = data.claimed_amount;
+        if claimed_amount.checked_add(data.claimable_amount)?
+            != data.vested_amount
+        {
+            return Err(StdError::generic_err(format!(
+                "Claimed ({}) and claimable ({}) amounts of {} do not add up to its vested amount ({})",
+                claimed_amount, data.claimable_amount, address, data.vested_amount
+            ))
+            .into());
+        }
+        let unclaimed_amount = vesting_amount.checked_sub(claimed_amount)?;
+        if unclaimed_amount > unallocated_amount {
+            return Err(StdError::generic_err(format!(
+                "Insufficient funds for all imports. Contract has {} available but trying to allocate {}",
+                unallocated_amount, unclaimed_amount
+            ))
+            .into());
+        }
+        unallocated_amount -= unclaimed_amount;
+
+        if has_schedules(deps.storage, &address) {
+            return Err(StdError::generic_err(format!(
+                "User {} already has a vesting account",
+                address
+            ))
+            .into());
+        }
+        save_new_schedule(
+            deps.storage,
+            &VestingAccount {
+                address: address.clone(),
+                vesting_amount,
+                cliff_amount,
+                vesting_schedule,
+                claimed_amount,
+                denom: Some(denom.clone()),
+                milestones_confirmed,
+                payout_address: None,
+                label: data.label.clone(),
+                rounding,
+            },
+        )?;
+
+        attrs.extend(vec![
+            Attribute::new("address", &address),
+            Attribute::new("vesting_amount", vesting_amount.to_string()),
+            Attribute::new("claimed_amount", claimed_amount.to_string()),
+        ]);
+    }
+
+    save_unallocated(deps.storage, &denom, &unallocated_amount)?;
+
+    Ok(Response::new()
+        .add_event(event_unallocated_changed(
+            deps.storage,
+            &denom,
+            old_unallocated_amount,
+            unallocated_amount,
+        )?)
+        .add_attribute("action", "import_accounts")
+        .add_attributes(attrs)
+        .add_attribute("unallocated_amount", unallocated_amount.to_string()))
+}
+
 fn reward_users(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     rewards: Vec<RewardUserRequest>,
     vesting_schedule: VestingSchedule,
+    denom: Option<String>,
 ) -> Result<Response, ContractError> {
-    let mut res = vec![];
-
     let whitelist = WHITELIST.load(deps.storage)?;
     if !(whitelist.is_member(&info.sender) || whitelist.is_admin(&info.sender)) {
         return Err(StdError::generic_err(format!(
@@ -133,8 +429,22 @@ fn reward_users(
         ))
         .into());
     }
+    register_rewards(deps, rewards, vesting_schedule, denom, false)
+}
 
-    let unallocated_amount = UNALLOCATED_AMOUNT.load(deps.storage)?;
+/// Registers `rewards` out of the unallocated amount of `denom`. Rewards that
+/// fail to register are skipped and flagged in the response data, unless
+/// `atomic` is set, in which case the first failure is returned.
+fn register_rewards(
+    deps: DepsMut,
+    rewards: Vec<RewardUserRequest>,
+    vesting_schedule: VestingSchedule,
+    denom: Option<String>,
+    atomic: bool,
+) -> Result<Response, ContractError> {
+    let mut res = vec![];
+    let denom = resolve_denom(deps.storage, denom)?;
+    let unallocated_amount = load_unallocated(deps.storage, &denom)?;
 
     let total_requested: Uint128 =
         rewards.iter().map(|req| req.vesting_amount).sum();
@@ -148,20 +458,26 @@ fn reward_users(
     vesting_schedule.validate()?;
 
     let mut attrs: Vec<Attribute> = vec![];
+    let mut total_registered = Uint128::zero();
     for req in rewards {
         // validate amounts and cliff details if there's one
         req.validate()?;
+        vesting_schedule
+            .validate_amounts(req.vesting_amount, req.cliff_amount)?;
+        if let Some(payout_address) = &req.payout_address {
+            deps.api.addr_validate(payout_address)?;
+        }
 
         let result = register_vesting_account(
             deps.storage,
-            &req.user_address,
-            req.vesting_amount,
-            req.cliff_amount,
+            &req,
             &vesting_schedule,
+            &denom,
         );
 
         match result {
             Ok(response) => {
+                total_registered += req.vesting_amount;
                 attrs.extend(response.attributes);
                 res.push(RewardUserResponse {
                     user_address: req.user_address,
@@ -169,6 +485,7 @@ fn reward_users(
                     error_msg: "".to_string(),
                 });
             }
+            Err(error) if atomic => return Err(error),
             Err(error) => {
                 res.push(RewardUserResponse {
                     user_address: req.user_address,
@@ -182,10 +499,18 @@ fn reward_users(
         }
     }
 
-    UNALLOCATED_AMOUNT
-        .save(deps.storage, &(unallocated_amount - total_requested))?;
+    // Only the rewards that were registered are allocated, so skipped entries
+    // in `res` leave their amount unallocated.
+    let new_unallocated_amount = unallocated_amount - total_registered;
+    save_unallocated(deps.storage, &denom, &new_unallocated_amount)?;
 
     Ok(Response::new()
+        .add_event(event_unallocated_changed(
+            deps.storage,
+            &denom,
+            unallocated_amount,
+            new_unallocated_amount,
+        )?)
         .add_attributes(attrs)
         .add_attribute("method", "reward_users")
         .set_data(to_json_binary(&res).unwrap()))
@@ -193,13 +518,19 @@ fn reward_users(
 
 fn register_vesting_account(
     storage: &mut dyn Storage,
-    address: &str,
-    vesting_amount: Uint128,
-    cliff_amount: Uint128,
+    req: &RewardUserRequest,
     vesting_schedule: &VestingSchedule,
+    denom: &str,
 ) -> Result<Response, ContractError> {
-    // vesting_account existence check
-    if VESTING_ACCOUNTS.has(storage, address) {
+    let address = req.user_address.as_str();
+    // an address may hold several schedules, but only one per schedule and
+    // denom
+    let mut duplicate = false;
+    for (_, account) in load_schedules(storage, address)? {
+        duplicate |= &account.vesting_schedule == vesting_schedule
+            && schedule_denom(storage, &account)? == denom;
+    }
+    if duplicate {
         return Err(StdError::generic_err(format!(
             "User {} already has a vesting account",
             address
@@ -207,23 +538,28 @@ fn register_vesting_account(
         .into());
     }
     vesting_schedule.validate()?;
+    let rounding = CONFIG.load(storage)?.rounding;
 
-    VESTING_ACCOUNTS.save(
+    save_new_schedule(
         storage,
-        address,
         &VestingAccount {
             address: address.to_string(),
-            vesting_amount,
-            cliff_amount,
+            vesting_amount: req.vesting_amount,
+            cliff_amount: req.cliff_amount,
             vesting_schedule: vesting_schedule.clone(),
             claimed_amount: Uint128::zero(),
+            denom: Some(denom.to_string()),
+            milestones_confirmed: 0,
+            payout_address: req.payout_address.clone(),
+            label: req.label.clone(),
+            rounding,
         },
     )?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "register_vesting_account"),
         ("address", address),
-        ("vesting_amount", &vesting_amount.to_string()),
+        ("vesting_amount", &req.vesting_amount.to_string()),
     ]))
 }
 
@@ -232,6 +568,7 @@ fn deregister_vesting_accounts(
     env: Env,
     info: MessageInfo,
     addresses: Vec<String>,
+    force: Option<bool>,
 ) -> Result<Response, ContractError> {
     let whitelist = WHITELIST.load(deps.storage)?;
     if !(whitelist.is_member(&info.sender) || whitelist.is_admin(&info.sender)) {
@@ -242,9 +579,16 @@ fn deregister_vesting_accounts(
         .into());
     }
 
+    let guard_cliff = CONFIG
+        .load(deps.storage)?
+        .deregister_requires_force_before_cliff
+        && !force.unwrap_or(false);
+
     let mut res = vec![];
     let mut attrs: Vec<Attribute> = vec![];
     let mut messages: Vec<CosmosMsg> = vec![];
+    let mut count: u64 = 0;
+    let mut total_refunded = Uint128::zero();
 
     for address in addresses {
         let result = deregister_vesting_account(
@@ -252,11 +596,14 @@ fn deregister_vesting_accounts(
             env.block.time,
             &address,
             &whitelist.admin,
+            guard_cliff,
             &mut messages,
         );
 
         match result {
-            Ok(response) => {
+            Ok((response, refunded)) => {
+                count += 1;
+                total_refunded += refunded;
                 attrs.extend(response.attributes);
                 res.push(DeregisterUserResponse {
                     user_address: address,
@@ -277,8 +624,26 @@ fn deregister_vesting_accounts(
         }
     }
 
+    // Recovered funds are sent to the admin, so the unallocated amount is
+    // unchanged.
+    let mut events = vec![];
+    for denom in funded_denoms(deps.storage)? {
+        let unallocated_amount = load_unallocated(deps.storage, &denom)?;
+        events.push(event_unallocated_changed(
+            deps.storage,
+            &denom,
+            unallocated_amount,
+            unallocated_amount,
+        )?);
+    }
+    events.push(event_deregister_batch(
+        count,
+        total_refunded,
+        env.block.height,
+    ));
     Ok(Response::new()
         .add_messages(messages)
+        .add_events(events)
         .add_attributes(attrs)
         .add_attribute("action", "deregister_vesting_accounts")
         .set_data(to_json_binary(&res).unwrap()))
@@ -289,47 +654,316 @@ fn deregister_vesting_account(
     timestamp: Timestamp,
     address: &str,
     admin_address: &str,
+    guard_cliff: bool,
     messages: &mut Vec<CosmosMsg>,
-) -> Result<Response, ContractError> {
+) -> Result<(Response, Uint128), ContractError> {
     // vesting_account existence check
-    let account = VESTING_ACCOUNTS.may_load(storage, address)?;
-    let denom = DENOM.load(storage)?;
+    let schedules = load_schedules(storage, address)?;
 
-    if account.is_none() {
+    if schedules.is_empty() {
         return Err(StdError::generic_err(format!(
             "User {} does not have a vesting account.",
             address,
         ))
         .into());
     }
-    let account = account.unwrap();
 
-    // remove vesting account
-    VESTING_ACCOUNTS.remove(storage, address);
+    if guard_cliff {
+        for (_, account) in &schedules {
+            let cliff_time = account.vesting_schedule.cliff_time();
+            if timestamp.seconds() < cliff_time {
+                return Err(VestingError::DeregisterBeforeCliff {
+                    address: address.to_string(),
+                    cliff_time,
+                }
+                .into());
+            }
+        }
+    }
 
-    let vested_amount = account.vested_amount(timestamp)?;
-    let left_vesting_amount =
-        account.vesting_amount.checked_sub(vested_amount)?;
+    // remove all vesting schedules of the account, summing their amounts
+    let mut vesting_amount = Uint128::zero();
+    let mut vested_amount = Uint128::zero();
+    let mut claimed_amount = Uint128::zero();
+    let mut recoverable: BTreeMap<String, Uint128> = BTreeMap::new();
+    for (index, schedule) in &schedules {
+        VESTING_ACCOUNTS.remove(storage, (address, *index));
+        vesting_amount += schedule.vesting_amount;
+        vested_amount += schedule.vested_amount(timestamp)?;
+        claimed_amount += schedule.claimed_amount;
+        *recoverable
+            .entry(schedule_denom(storage, schedule)?)
+            .or_default() += schedule.vesting_amount - schedule.claimed_amount;
+    }
+    let left_vesting_amount = vesting_amount.checked_sub(vested_amount)?;
 
-    let recoverable_amount = account.vesting_amount - account.claimed_amount;
+    let recoverable_amount = vesting_amount - claimed_amount;
     // transfer all that's unclaimed to the admin
+    for (denom, amount) in recoverable {
+        send_if_amount_is_not_zero(messages, amount, &denom, admin_address)?;
+    }
 
-    send_if_amount_is_not_zero(
-        messages,
-        recoverable_amount,
-        &denom,
-        admin_address,
-    )?;
-
-    Ok(Response::new().add_attributes(vec![
+    let res = Response::new().add_attributes(vec![
         ("action", "deregister_vesting_account"),
         ("address", address),
-        ("vesting_amount", &account.vesting_amount.to_string()),
+        ("vesting_amount", &vesting_amount.to_string()),
         ("vested_amount", &vested_amount.to_string()),
         ("left_vesting_amount", &left_vesting_amount.to_string()),
-        ("claimed_amount", &account.claimed_amount.to_string()),
+        ("claimed_amount", &claimed_amount.to_string()),
         ("recoverable_amount", &recoverable_amount.to_string()),
-    ]))
+    ]);
+    Ok((res, recoverable_amount))
+}
+
+/// Allow the contract admin to reduce the unvested part of a user's most
+/// recent vesting schedule, refunding the reduction to the admin.
+fn clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user_address: String,
+    reduce_by: Uint128,
+    schedule_index: Option<u64>,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+    if reduce_by.is_zero() {
+        return Err(StdError::generic_err("Nothing to claw back").into());
+    }
+
+    let (index, mut account) = match schedule_index {
+        Some(index) => VESTING_ACCOUNTS
+            .may_load(deps.storage, (&user_address, index))?
+            .map(|account| (index, account))
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "User {} does not have a vesting schedule {}.",
+                    user_address, index,
+                ))
+            })?,
+        None => load_schedules(deps.storage, &user_address)?
+            .pop()
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "User {} does not have a vesting account.",
+                    user_address,
+                ))
+            })?,
+    };
+    account.reduce_unvested(env.block.time, reduce_by)?;
+    // Like a fully claimed schedule, one with nothing left to claim is dropped.
+    if account.claimed_amount >= account.vesting_amount {
+        VESTING_ACCOUNTS.remove(deps.storage, (&user_address, index));
+    } else {
+        // Shortening a schedule must not break its invariants.
+        account.vesting_schedule.validate()?;
+        VESTING_ACCOUNTS.save(deps.storage, (&user_address, index), &account)?;
+    }
+
+    // The refund goes to the admin, so the unallocated amount is unchanged.
+    let denom = schedule_denom(deps.storage, &account)?;
+    let unallocated_amount = load_unallocated(deps.storage, &denom)?;
+    Ok(Response::new()
+        .add_message(build_send_msg(&denom, reduce_by, &whitelist.admin))
+        .add_event(event_unallocated_changed(
+            deps.storage,
+            &denom,
+            unallocated_amount,
+            unallocated_amount,
+        )?)
+        .add_attribute("action", "clawback")
+        .add_attribute("address", user_address)
+        .add_attribute("reduce_by", reduce_by.to_string())
+        .add_attribute("vesting_amount", account.vesting_amount.to_string()))
+}
+
+/// Allow the contract admin to move all vesting schedules of `from` to `to`.
+/// Schedules keep their claimed amounts and get new indices under `to`.
+fn transfer_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    to: String,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+    let to = deps.api.addr_validate(&to)?.to_string();
+
+    let schedules = load_schedules(deps.storage, &from)?;
+    if schedules.is_empty() {
+        return Err(StdError::generic_err(format!(
+            "User {} does not have a vesting account.",
+            from,
+        ))
+        .into());
+    }
+    if has_schedules(deps.storage, &to) {
+        return Err(StdError::generic_err(format!(
+            "User {} already has a vesting account.",
+            to,
+        ))
+        .into());
+    }
+
+    for (index, mut account) in schedules.iter().cloned() {
+        VESTING_ACCOUNTS.remove(deps.storage, (&from, index));
+        account.address = to.clone();
+        save_new_schedule(deps.storage, &account)?;
+    }
+    // The claim state follows the schedules to the new address, so that
+    // per-user claim queries agree with the schedules.
+    if let Some(height) = LAST_CLAIM_HEIGHT.may_load(deps.storage, &from)? {
+        LAST_CLAIM_HEIGHT.remove(deps.storage, &from);
+        LAST_CLAIM_HEIGHT.save(deps.storage, &to, &height)?;
+    }
+    if let Some(claimed) = CLAIMED_TOTAL.may_load(deps.storage, &from)? {
+        CLAIMED_TOTAL.remove(deps.storage, &from, env.block.height)?;
+        CLAIMED_TOTAL.update(
+            deps.storage,
+            &to,
+            env.block.height,
+            |to_claimed| -> StdResult<Uint128> {
+                Ok(to_claimed.unwrap_or_default().checked_add(claimed)?)
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_vesting")
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("schedule_count", schedules.len().to_string()))
+}
+
+/// Allow the contract admin to confirm the next milestone of a milestone
+/// schedule, making its tranche claimable.
+fn confirm_milestone(
+    deps: DepsMut,
+    info: MessageInfo,
+    user_address: String,
+    schedule_index: u64,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+
+    let key = (user_address.as_str(), schedule_index);
+    let mut account =
+        VESTING_ACCOUNTS
+            .may_load(deps.storage, key)?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "User {} does not have a vesting schedule {}.",
+                    user_address, schedule_index,
+                ))
+            })?;
+    let VestingSchedule::Milestone { tranches } = &account.vesting_schedule
+    else {
+        return Err(StdError::generic_err(format!(
+            "Vesting schedule {} of {} is not a milestone schedule",
+            schedule_index, user_address,
+        ))
+        .into());
+    };
+    let Some(unlocked_amount) =
+        tranches.get(account.milestones_confirmed as usize).copied()
+    else {
+        return Err(StdError::generic_err(format!(
+            "All milestones of vesting schedule {} of {} are confirmed",
+            schedule_index, user_address,
+        ))
+        .into());
+    };
+    account.milestones_confirmed += 1;
+    VESTING_ACCOUNTS.save(deps.storage, key, &account)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "confirm_milestone")
+        .add_attribute("address", user_address)
+        .add_attribute("schedule_index", schedule_index.to_string())
+        .add_attribute(
+            "milestones_confirmed",
+            account.milestones_confirmed.to_string(),
+        )
+        .add_attribute("unlocked_amount", unlocked_amount.to_string()))
+}
+
+/// Allow the contract admin to recover tokens held by the contract beyond what
+/// is reserved for vesting accounts and the unallocated amount. Reserved
+/// amounts are never sent.
+fn reclaim_surplus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+    let recipient = match to {
+        Some(to) => deps.api.addr_validate(&to)?.to_string(),
+        None => whitelist.admin,
+    };
+
+    let mut reserved = unclaimed_by_denom(deps.storage)?;
+    for denom in funded_denoms(deps.storage)? {
+        *reserved.entry(denom.clone()).or_default() +=
+            load_unallocated(deps.storage, &denom)?;
+    }
+
+    let surplus: Vec<Coin> = deps
+        .querier
+        .query_all_balances(env.contract.address)?
+        .into_iter()
+        .filter_map(|balance| {
+            let reserved =
+                reserved.get(&balance.denom).copied().unwrap_or_default();
+            let amount = balance.amount.saturating_sub(reserved);
+            (!amount.is_zero()).then(|| Coin::new(amount.u128(), balance.denom))
+        })
+        .collect();
+    if surplus.is_empty() {
+        return Err(StdError::generic_err("Nothing to reclaim").into());
+    }
+
+    let amount = surplus
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.clone(),
+            amount: surplus,
+        })
+        .add_attribute("action", "reclaim_surplus")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount))
+}
+
+/// Event emitted whenever an execute message may change the unallocated
+/// amount, so that it can be tracked without querying the contract. Events of
+/// `multi_denom` contracts also carry the denom.
+fn event_unallocated_changed(
+    storage: &dyn Storage,
+    denom: &str,
+    old: Uint128,
+    new: Uint128,
+) -> StdResult<Event> {
+    let event = Event::new("unallocated_changed")
+        .add_attribute("old_unallocated_amount", old.to_string())
+        .add_attribute("new_unallocated_amount", new.to_string());
+    if CONFIG.load(storage)?.multi_denom {
+        return Ok(event.add_attribute("denom", denom));
+    }
+    Ok(event)
 }
 
 ///
@@ -348,46 +982,148 @@ fn send_if_amount_is_not_zero(
     Ok(())
 }
 
-fn claim(
+/// Allow the contract admin to add or remove a relayer that can claim on
+/// behalf of users with `ClaimFor`.
+fn edit_relayers(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    add: bool,
+) -> Result<Response, ContractError> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.is_admin(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized").into());
+    }
+    deps.api.addr_validate(&address)?;
+
+    let mut relayers = RELAYERS.may_load(deps.storage)?.unwrap_or_default();
+    let action = if add {
+        relayers.insert(address.clone());
+        "add_relayer"
+    } else {
+        relayers.remove(&address);
+        "remove_relayer"
+    };
+    RELAYERS.save(deps.storage, &relayers)?;
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("address", address))
+}
+
+/// Claims the vested tokens of `user_address` on its behalf. Only callable by
+/// a registered relayer. The tokens are sent to the user, not the relayer.
+fn claim_for(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    user_address: String,
 ) -> Result<Response, ContractError> {
-    let recipient = info.sender.as_str();
-    let denom = DENOM.load(deps.storage)?;
+    let relayers = RELAYERS.may_load(deps.storage)?.unwrap_or_default();
+    if !relayers.contains(info.sender.as_str()) {
+        return Err(StdError::generic_err(format!(
+            "Sender {} is not a registered relayer.",
+            &info.sender
+        ))
+        .into());
+    }
+    Ok(claim(deps, env, &user_address, None)?
+        .add_attribute("relayer", info.sender.as_str()))
+}
 
+fn claim(
+    deps: DepsMut,
+    env: Env,
+    recipient: &str,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
     let mut attrs: Vec<Attribute> = vec![];
+    if limit == Some(0) {
+        return Err(StdError::generic_err("limit must be greater than 0").into());
+    }
+    let mut remaining = limit.unwrap_or(u32::MAX);
+    let mut has_more = false;
+
+    let cooldown = CONFIG.load(deps.storage)?.claim_cooldown_blocks;
+    if let Some(cooldown) = cooldown {
+        if let Some(last) =
+            LAST_CLAIM_HEIGHT.may_load(deps.storage, recipient)?
+        {
+            let available_at = last.saturating_add(cooldown);
+            if env.block.height < available_at {
+                return Err(ContractError::ClaimCooldown { available_at });
+            }
+        }
+        LAST_CLAIM_HEIGHT.save(deps.storage, recipient, &env.block.height)?;
+    }
 
     // vesting_account existence check
-    let account = VESTING_ACCOUNTS.may_load(deps.storage, recipient)?;
-    if account.is_none() {
+    let schedules = load_schedules(deps.storage, recipient)?;
+    if schedules.is_empty() {
         return Err(StdError::generic_err(format!(
             "vesting entry is not found for denom {}",
-            to_string(&denom).unwrap(),
+            to_string(&funded_denoms(deps.storage)?.join(",")).unwrap(),
         ))
         .into());
     }
 
-    let mut account = account.unwrap();
-    let vested_amount = account.vested_amount(env.block.time)?;
-    let claimed_amount = account.claimed_amount;
+    let mut vesting_amount = Uint128::zero();
+    let mut total_vested = Uint128::zero();
+    let mut claimable_amount = Uint128::zero();
+    // Claimed amounts keyed by payout address and denom.
+    let mut claims: BTreeMap<(String, String), Uint128> = BTreeMap::new();
+    let mut receipts: Vec<Event> = vec![];
+    for (index, mut account) in schedules {
+        let vested_amount = account.vested_amount(env.block.time)?;
+        let claimable = vested_amount.checked_sub(account.claimed_amount)?;
+        let denom = schedule_denom(deps.storage, &account)?;
+        vesting_amount += account.vesting_amount;
+        total_vested += vested_amount;
+        if claimable.is_zero() {
+            continue;
+        }
+        if remaining == 0 {
+            has_more = true;
+            continue;
+        }
+        remaining -= 1;
+        claimable_amount += claimable;
+        let payout_address =
+            account.payout_address.as_deref().unwrap_or(recipient);
+        *claims
+            .entry((payout_address.to_string(), denom.clone()))
+            .or_default() += claimable;
+        receipts.push(event_claim_receipt(
+            recipient,
+            claimable,
+            &denom,
+            vested_amount,
+            index,
+        ));
 
-    let claimable_amount = vested_amount.checked_sub(claimed_amount)?;
+        account.claimed_amount = vested_amount;
+        // fully claimed schedules are dropped so queries never see them
+        if account.claimed_amount == account.vesting_amount {
+            VESTING_ACCOUNTS.remove(deps.storage, (recipient, index));
+        } else {
+            VESTING_ACCOUNTS.save(deps.storage, (recipient, index), &account)?;
+        }
+    }
     if claimable_amount.is_zero() {
         return Err(StdError::generic_err("nothing left to claim").into());
     }
-
-    account.claimed_amount = vested_amount;
-    if account.claimed_amount == account.vesting_amount {
-        VESTING_ACCOUNTS.remove(deps.storage, recipient);
-    } else {
-        VESTING_ACCOUNTS.save(deps.storage, recipient, &account)?;
-    }
+    CLAIMED_TOTAL.update(
+        deps.storage,
+        recipient,
+        env.block.height,
+        |claimed| -> StdResult<Uint128> {
+            Ok(claimed.unwrap_or_default().checked_add(claimable_amount)?)
+        },
+    )?;
 
     attrs.extend(
         vec![
-            ("vesting_amount", &account.vesting_amount.to_string()),
-            ("vested_amount", &vested_amount.to_string()),
+            ("vesting_amount", &vesting_amount.to_string()),
+            ("vested_amount", &total_vested.to_string()),
             ("claim_amount", &claimable_amount.to_string()),
         ]
         .into_iter()
@@ -395,9 +1131,92 @@ fn claim(
     );
 
     Ok(Response::new()
-        .add_messages(vec![build_send_msg(&denom, claimable_amount, recipient)])
+        .add_messages(claims.iter().filter(|(_, amount)| !amount.is_zero()).map(
+            |((payout_address, denom), amount)| {
+                build_send_msg(denom, *amount, payout_address)
+            },
+        ))
+        .add_events(receipts)
         .add_attributes(vec![("action", "claim"), ("address", recipient)])
-        .add_attributes(attrs))
+        .add_attributes(attrs)
+        .set_data(to_json_binary(&ClaimResponse { has_more })?))
+}
+
+/// Claims the vested tokens of the sender and delegates the native coins sent
+/// to the sender to `validator`, through an authz `MsgExec` that relies on the
+/// sender's `MsgDelegate` grant to this contract.
+fn claim_and_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator: String,
+) -> Result<Response, ContractError> {
+    // Operator addresses use the "valoper" prefix, so they are checked
+    // against the staking module rather than with `addr_validate`.
+    if deps.querier.query_validator(&validator)?.is_none() {
+        return Err(ContractError::UnknownValidator { validator });
+    }
+    let contract_addr = env.contract.address.to_string();
+    let user = info.sender.to_string();
+    let res = claim(deps, env, &user, None)?;
+
+    let delegations: Vec<CosmosMsg> = res
+        .messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount })
+                if *to_address == user =>
+            {
+                Some(amount.clone())
+            }
+            _ => None,
+        })
+        .flatten()
+        .map(|amount| {
+            delegate_on_behalf(&contract_addr, &user, &validator, &amount)
+        })
+        .collect();
+    if delegations.is_empty() {
+        return Err(StdError::generic_err(
+            "no native coins were claimed to delegate",
+        )
+        .into());
+    }
+    Ok(res
+        .add_messages(delegations)
+        .add_attribute("validator", validator))
+}
+
+/// Event emitted for every schedule paid out by a claim. Its type and
+/// attribute keys are kept stable so that integrators can index claims.
+/// "cumulative_claimed" is the total claimed from the schedule so far.
+fn event_claim_receipt(
+    user: &str,
+    amount: Uint128,
+    denom: &str,
+    cumulative_claimed: Uint128,
+    schedule_index: u64,
+) -> Event {
+    Event::new("vesting/claim_receipt")
+        .add_attribute("user", user)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom)
+        .add_attribute("cumulative_claimed", cumulative_claimed.to_string())
+        .add_attribute("schedule_index", schedule_index.to_string())
+}
+
+/// Event emitted once per `DeregisterVestingAccounts`. "count" is the number
+/// of accounts deregistered and "total_refunded" the sum of their
+/// recoverable amounts sent to the admin, over all denoms.
+fn event_deregister_batch(
+    count: u64,
+    total_refunded: Uint128,
+    block_height: u64,
+) -> Event {
+    Event::new("vesting/deregister_batch")
+        .add_attribute("count", count.to_string())
+        .add_attribute("total_refunded", total_refunded.to_string())
+        .add_attribute("block_height", block_height.to_string())
 }
 
 fn build_send_msg(denom: &str, amount: Uint128, to: &str) -> CosmosMsg {
@@ -422,7 +1241,373 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::VestingAccounts { address } => {
             to_json_binary(&vesting_accounts(deps, &env, address)?)
         }
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Params {} => to_json_binary(&query_params(deps)?),
+        QueryMsg::SolvencyCheck {} => {
+            to_json_binary(&solvency_check(deps, &env)?)
+        }
+        QueryMsg::Managers {} => to_json_binary(&query_managers(deps)?),
+        QueryMsg::TotalClaimableNow {} => {
+            to_json_binary(&total_claimable_now(deps, &env)?)
+        }
+        QueryMsg::UnderfundedSchedules {} => {
+            to_json_binary(&underfunded_schedules(deps, &env)?)
+        }
+        QueryMsg::Progress { address } => {
+            to_json_binary(&progress(deps, &env, &address)?)
+        }
+        QueryMsg::ClaimPreview { address } => {
+            to_json_binary(&claim_preview(deps, &env, &address)?)
+        }
+        QueryMsg::AggregateFor { addresses } => {
+            to_json_binary(&aggregate_for(deps, &env, addresses)?)
+        }
+        QueryMsg::ClaimedAtHeight { address, height } => {
+            to_json_binary(&ClaimedAtHeightResponse {
+                claimed_amount: CLAIMED_TOTAL
+                    .may_load_at_height(deps.storage, &address, height)?
+                    .unwrap_or_default(),
+                address,
+                height,
+            })
+        }
+        QueryMsg::SimulateDeregister { addresses } => {
+            to_json_binary(&simulate_deregister(deps, &env, addresses)?)
+        }
+        QueryMsg::NextUnlock { address } => {
+            to_json_binary(&next_unlock(deps, &env, &address)?)
+        }
+        QueryMsg::ExpiringSoon {
+            within_seconds,
+            start_after,
+            limit,
+        } => to_json_binary(&expiring_soon(
+            deps,
+            &env,
+            within_seconds,
+            start_after,
+            limit,
+        )?),
+    }
+}
+
+fn query_managers(deps: Deps) -> StdResult<ManagersResponse> {
+    let whitelist = WHITELIST.load(deps.storage)?;
+    let status = |address: String| ManagerStatus {
+        valid: deps.api.addr_validate(&address).is_ok(),
+        address,
+    };
+    let mut managers: Vec<String> = whitelist.members.into_iter().collect();
+    managers.sort();
+    Ok(ManagersResponse {
+        admin: status(whitelist.admin),
+        managers: managers.into_iter().map(status).collect(),
+    })
+}
+
+fn query_params(deps: Deps) -> StdResult<ParamsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let whitelist = WHITELIST.load(deps.storage)?;
+    let mut managers: Vec<String> = whitelist.members.into_iter().collect();
+    managers.sort();
+    Ok(ParamsResponse {
+        admin: whitelist.admin,
+        managers,
+        relayers: RELAYERS
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        funding_denoms: funded_denoms(deps.storage)?,
+        multi_denom: config.multi_denom,
+        decimals: config.decimals,
+        deregister_requires_force_before_cliff: config
+            .deregister_requires_force_before_cliff,
+        claim_cooldown_blocks: config.claim_cooldown_blocks,
+        rounding: config.rounding,
+        default_page_limit: DEFAULT_LIMIT,
+        max_page_limit: MAX_LIMIT,
+    })
+}
+
+fn solvency_check(deps: Deps, env: &Env) -> StdResult<Vec<SolvencyResponse>> {
+    let mut obligations = unclaimed_by_denom(deps.storage)?;
+    for denom in funded_denoms(deps.storage)? {
+        obligations.entry(denom).or_default();
+    }
+    obligations
+        .into_iter()
+        .map(|(denom, obligations)| {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &denom)?
+                .amount;
+            let shortfall = obligations.saturating_sub(balance);
+            Ok(SolvencyResponse {
+                denom,
+                solvent: shortfall.is_zero(),
+                obligations,
+                balance,
+                shortfall,
+            })
+        })
+        .collect()
+}
+
+/// Splits the balance of every insolvent denom over its schedules in
+/// proportion to what each still owes.
+fn underfunded_schedules(
+    deps: Deps,
+    env: &Env,
+) -> StdResult<Vec<UnderfundedScheduleResponse>> {
+    let insolvent: BTreeMap<String, SolvencyResponse> =
+        solvency_check(deps, env)?
+            .into_iter()
+            .filter(|solvency| !solvency.solvent)
+            .map(|solvency| (solvency.denom.clone(), solvency))
+            .collect();
+    if insolvent.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut res = vec![];
+    for item in VESTING_ACCOUNTS.range(
+        deps.storage,
+        None,
+        None,
+        cosmwasm_std::Order::Ascending,
+    ) {
+        let ((user_address, schedule_index), account) = item?;
+        let denom = schedule_denom(deps.storage, &account)?;
+        let Some(solvency) = insolvent.get(&denom) else {
+            continue;
+        };
+        let obligation =
+            account.vesting_amount.checked_sub(account.claimed_amount)?;
+        if obligation.is_zero() {
+            continue;
+        }
+        let available =
+            obligation.multiply_ratio(solvency.balance, solvency.obligations);
+        res.push(UnderfundedScheduleResponse {
+            user_address,
+            schedule_index,
+            denom,
+            obligation,
+            available,
+            shortfall: obligation - available,
+        });
+    }
+    Ok(res)
+}
+
+fn total_claimable_now(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut claimable: BTreeMap<String, Uint128> = BTreeMap::new();
+    for item in VESTING_ACCOUNTS.range(
+        deps.storage,
+        None,
+        None,
+        cosmwasm_std::Order::Ascending,
+    ) {
+        let (_, account) = item?;
+        let vested_amount = account.vested_amount(env.block.time)?;
+        *claimable
+            .entry(schedule_denom(deps.storage, &account)?)
+            .or_default() +=
+            vested_amount.checked_sub(account.claimed_amount)?;
+    }
+    Ok(claimable
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| Coin { denom, amount })
+        .collect())
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+fn expiring_soon(
+    deps: Deps,
+    env: &Env,
+    within_seconds: u64,
+    start_after: Option<(String, u64)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ExpiringScheduleResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let deadline = env.block.time.seconds().saturating_add(within_seconds);
+    let start = start_after
+        .as_ref()
+        .map(|(address, index)| Bound::exclusive((address.as_str(), *index)));
+
+    let mut res = vec![];
+    for item in VESTING_ACCOUNTS.range(
+        deps.storage,
+        start,
+        None,
+        cosmwasm_std::Order::Ascending,
+    ) {
+        let ((user_address, schedule_index), account) = item?;
+        // Milestone schedules do not end at a known time.
+        let end_time = account.vesting_schedule.end_time();
+        if end_time > deadline
+            || matches!(
+                account.vesting_schedule,
+                VestingSchedule::Milestone { .. }
+            )
+        {
+            continue;
+        }
+        res.push(ExpiringScheduleResponse {
+            user_address,
+            schedule_index,
+            denom: schedule_denom(deps.storage, &account)?,
+            end_time,
+            vesting_amount: account.vesting_amount,
+            claimed_amount: account.claimed_amount,
+        });
+        if res.len() >= limit {
+            break;
+        }
+    }
+    Ok(res)
+}
+
+/// Finds the earliest next unlock over the schedules of `address`.
+fn next_unlock(
+    deps: Deps,
+    env: &Env,
+    address: &str,
+) -> StdResult<NextUnlockResponse> {
+    let mut next: Option<(u64, Uint128)> = None;
+    for (_, account) in load_schedules(deps.storage, address)? {
+        let Some((time, amount)) = account.next_unlock(env.block.time)? else {
+            continue;
+        };
+        next = match next {
+            Some((next_time, _)) if time > next_time => next,
+            Some((next_time, next_amount)) if time == next_time => {
+                Some((time, next_amount + amount))
+            }
+            _ => Some((time, amount)),
+        };
+    }
+    Ok(NextUnlockResponse {
+        seconds_until: next.map(|(time, _)| time - env.block.time.seconds()),
+        amount_at_unlock: next.map(|(_, amount)| amount).unwrap_or_default(),
+    })
+}
+
+/// Share of `amount` in `total`, in basis points rounded down and clamped at
+/// 10000.
+fn to_bps(amount: Uint128, total: Uint128) -> u16 {
+    if total.is_zero() {
+        return 0;
+    }
+    amount.min(total).multiply_ratio(10_000u128, total).u128() as u16
+}
+
+fn progress(
+    deps: Deps,
+    env: &Env,
+    address: &str,
+) -> StdResult<Vec<ScheduleProgress>> {
+    load_schedules(deps.storage, address)?
+        .into_iter()
+        .map(|(schedule_index, account)| {
+            Ok(ScheduleProgress {
+                schedule_index,
+                vested_bps: to_bps(
+                    account.vested_amount(env.block.time)?,
+                    account.vesting_amount,
+                ),
+                claimed_bps: to_bps(
+                    account.claimed_amount,
+                    account.vesting_amount,
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Mirrors the amounts of an unlimited `claim` by `address` without claiming.
+fn claim_preview(
+    deps: Deps,
+    env: &Env,
+    address: &str,
+) -> StdResult<ClaimPreviewResponse> {
+    let mut claim_now = Uint128::zero();
+    let mut remaining_after = Uint128::zero();
+    for (_, account) in load_schedules(deps.storage, address)? {
+        let vested_amount = account.vested_amount(env.block.time)?;
+        claim_now += vested_amount.checked_sub(account.claimed_amount)?;
+        remaining_after += account.vesting_amount.checked_sub(vested_amount)?;
+    }
+    Ok(ClaimPreviewResponse {
+        claim_now,
+        remaining_after,
+        fully_claimed_after: remaining_after.is_zero(),
+    })
+}
+
+/// Computes the refunds of `deregister_vesting_account` without removing any
+/// schedule.
+fn simulate_deregister(
+    deps: Deps,
+    env: &Env,
+    addresses: Vec<String>,
+) -> StdResult<Vec<SimulateDeregisterResponse>> {
+    let master = WHITELIST.load(deps.storage)?.admin;
+    let mut res = vec![];
+    for address in addresses {
+        let schedules = load_schedules(deps.storage, &address)?;
+        if schedules.is_empty() {
+            return Err(StdError::generic_err(format!(
+                "User {} does not have a vesting account.",
+                address,
+            )));
+        }
+        // (refund_to_master, vested_unclaimed) per denom
+        let mut refunds: BTreeMap<String, (Uint128, Uint128)> = BTreeMap::new();
+        for (_, account) in &schedules {
+            let vested_amount = account.vested_amount(env.block.time)?;
+            let refund = refunds
+                .entry(schedule_denom(deps.storage, account)?)
+                .or_default();
+            refund.0 += account.vesting_amount - account.claimed_amount;
+            refund.1 += vested_amount.checked_sub(account.claimed_amount)?;
+        }
+        for (denom, (refund_to_master, vested_unclaimed)) in refunds {
+            res.push(SimulateDeregisterResponse {
+                user_address: address.clone(),
+                master: master.clone(),
+                denom,
+                refund_to_master,
+                vested_unclaimed,
+            });
+        }
     }
+    Ok(res)
+}
+
+// sum the vesting data of the provided vec of addresses
+fn aggregate_for(
+    deps: Deps,
+    env: &Env,
+    addresses: Vec<String>,
+) -> StdResult<AggregateResponse> {
+    let addresses: BTreeSet<String> = addresses.into_iter().collect();
+    let mut res = AggregateResponse {
+        total_vesting: Uint128::zero(),
+        total_vested: Uint128::zero(),
+        total_claimable: Uint128::zero(),
+    };
+    for address in addresses {
+        for vesting in vesting_account(deps, env, address)?.vestings {
+            res.total_vesting += vesting.vesting_amount;
+            res.total_vested += vesting.vested_amount;
+            res.total_claimable += vesting.claimable_amount;
+        }
+    }
+    Ok(res)
 }
 
 // query multiple vesting accounts, with the provided vec of addresses
@@ -445,39 +1630,45 @@ fn vesting_account(
     env: &Env,
     address: String,
 ) -> StdResult<VestingAccountResponse> {
-    let account = VESTING_ACCOUNTS.may_load(deps.storage, address.as_str())?;
+    let schedules = load_schedules(deps.storage, address.as_str())?;
     let whitelist = WHITELIST.load(deps.storage)?;
-    let denom = DENOM.load(deps.storage)?;
-
-    match account {
-        None => Ok(VestingAccountResponse {
-            address,
-            vestings: vec![],
-        }),
-        Some(account) => {
-            let vested_amount = account.vested_amount(env.block.time)?;
+    let decimals = CONFIG.load(deps.storage)?.decimals;
 
-            let vesting_schedule_query = from_vesting_to_query_output(
-                &account.vesting_schedule,
-                account.vesting_amount,
-                account.cliff_amount,
-            );
+    let mut vestings = vec![];
+    for (_, account) in schedules {
+        let vested_amount = account.vested_amount(env.block.time)?;
+        let (cliff_claimable, linear_claimable) =
+            account.claimable_breakdown(env.block.time)?;
 
-            let vesting = VestingData {
-                master_address: Some(whitelist.admin.clone()),
-                vesting_denom: cw20::Denom::Native(denom),
-                vesting_amount: account.vesting_amount,
-                vesting_schedule: vesting_schedule_query,
+        let vesting_schedule_query = from_vesting_to_query_output(
+            &account.vesting_schedule,
+            account.vesting_amount,
+            account.cliff_amount,
+            account.milestones_confirmed,
+        );
 
-                vested_amount,
-                claimable_amount: vested_amount
-                    .checked_sub(account.claimed_amount)?,
-            };
+        vestings.push(VestingData {
+            master_address: Some(whitelist.admin.clone()),
+            vesting_denom: cw20::Denom::Native(schedule_denom(
+                deps.storage,
+                &account,
+            )?),
+            vesting_amount: account.vesting_amount,
+            vesting_schedule: vesting_schedule_query,
 
-            Ok(VestingAccountResponse {
-                address,
-                vestings: vec![vesting],
-            })
-        }
+            vested_amount,
+            claimed_amount: account.claimed_amount,
+            claimable_amount: vested_amount
+                .checked_sub(account.claimed_amount)?,
+            cliff_claimable,
+            linear_claimable,
+            label: account.label.clone(),
+        });
     }
+
+    Ok(VestingAccountResponse {
+        address,
+        vestings,
+        decimals,
+    })
 }
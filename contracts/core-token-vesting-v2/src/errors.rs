@@ -10,6 +10,21 @@ pub enum ContractError {
 
     #[error(transparent)]
     Overflow(#[from] cosmwasm_std::OverflowError),
+
+    #[error("must deposit exactly one type of token")]
+    NoFundsSent,
+
+    #[error("must deposit exactly one type of token")]
+    MultipleDenoms,
+
+    #[error("must deposit some token")]
+    ZeroAmount,
+
+    #[error("claim is on cooldown until block height {available_at}")]
+    ClaimCooldown { available_at: u64 },
+
+    #[error("{validator} is not a known validator")]
+    UnknownValidator { validator: String },
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -26,6 +41,19 @@ pub enum VestingError {
         end_time: u64,
     },
 
+    #[error("blackout from {blackout_start} to {blackout_end} should be a nonempty range within the vesting period")]
+    InvalidBlackout {
+        blackout_start: u64,
+        blackout_end: u64,
+    },
+
+    #[error("end_time {end_time} extended by the blackout from {blackout_start} to {blackout_end} does not fit in a timestamp")]
+    BlackoutOverflow {
+        end_time: u64,
+        blackout_start: u64,
+        blackout_end: u64,
+    },
+
     #[error("cliff_amount ({cliff_amount}) should be less than or equal to vesting_amount ({vesting_amount})")]
     ExcessiveAmount {
         cliff_amount: u128,
@@ -37,4 +65,55 @@ pub enum VestingError {
         vesting_amount: u128,
         deposit_amount: u128,
     },
+
+    #[error("cannot deregister {address} before the cliff time ({cliff_time}) without force")]
+    DeregisterBeforeCliff { address: String, cliff_time: u64 },
+
+    #[error("stepwise unlock_time ({unlock_time}) should be greater than the previous step")]
+    UnorderedSteps { unlock_time: u64 },
+
+    #[error("a stepwise schedule needs at least one step and every step must be nonzero")]
+    InvalidStepwiseSteps,
+
+    #[error("stepwise amounts overflow when summed")]
+    StepsAmountOverflow,
+
+    #[error("stepwise amounts sum to {steps_amount} but should be equal to vesting_amount ({vesting_amount})")]
+    MismatchedStepsAmount {
+        steps_amount: u128,
+        vesting_amount: u128,
+    },
+
+    #[error(
+        "cliff_amount ({cliff_amount}) should be zero for a stepwise schedule"
+    )]
+    StepwiseCliffAmount { cliff_amount: u128 },
+
+    #[error("a milestone schedule needs at least one tranche and every tranche must be nonzero")]
+    InvalidMilestoneTranches,
+
+    #[error("milestone tranches overflow when summed")]
+    TranchesAmountOverflow,
+
+    #[error("milestone tranches sum to {tranches_amount} but should be equal to vesting_amount ({vesting_amount})")]
+    MismatchedTranchesAmount {
+        tranches_amount: u128,
+        vesting_amount: u128,
+    },
+
+    #[error(
+        "cliff_amount ({cliff_amount}) should be zero for a milestone schedule"
+    )]
+    MilestoneCliffAmount { cliff_amount: u128 },
+
+    #[error("label is {len} bytes long but should be at most {max_len}")]
+    LabelTooLong { len: usize, max_len: usize },
+
+    #[error(
+        "cannot claw back {reduce_by}, only {unvested_amount} is not yet vested"
+    )]
+    ExcessiveClawback {
+        reduce_by: u128,
+        unvested_amount: u128,
+    },
 }
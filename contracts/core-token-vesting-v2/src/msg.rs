@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Uint128, Uint64};
+use cosmwasm_std::{StdResult, Uint128, Uint64};
 use cw20::Denom;
 
 use crate::errors::{ContractError, VestingError};
@@ -9,6 +9,28 @@ use crate::errors::{ContractError, VestingError};
 pub struct InstantiateMsg {
     pub admin: String,
     pub managers: Vec<String>,
+    /// Number of decimal places of the vested token. This is only a display
+    /// hint for clients and does not affect any of the stored amounts.
+    pub decimals: Option<u8>,
+    /// If true, deregistering an account before its cliff time requires
+    /// `force: true` on the message. Defaults to false.
+    pub deregister_requires_force_before_cliff: Option<bool>,
+    /// If true, the contract can be funded with several denoms whose
+    /// unallocated amounts are tracked independently, and `RewardUsers` and
+    /// `Withdraw` must specify a denom. Defaults to false, in which case
+    /// exactly one denom must be deposited.
+    pub multi_denom: Option<bool>,
+    /// Minimum number of blocks between two claims of the same user. Disabled
+    /// if unset.
+    pub claim_cooldown_blocks: Option<u64>,
+    /// How linear schedules round the amount vested between the cliff and the
+    /// end time. Defaults to `VestingRounding::Floor`.
+    pub rounding: Option<VestingRounding>,
+    /// Rewards registered with the given schedule during instantiation, out
+    /// of the deposited funds, as if sent with `RewardUsers` by the admin.
+    /// Unlike `RewardUsers`, instantiation fails if any of them fails to
+    /// register. `multi_denom` contracts must deposit a single denom to use this.
+    pub initial_rewards: Option<(Vec<RewardUserRequest>, VestingSchedule)>,
 }
 
 /// Enum respresenting message types for the execute entry point.
@@ -19,37 +41,137 @@ pub enum ExecuteMsg {
     /// A creator operation that registers a vesting account
     /// address: String: Bech 32 address of the owner of the vesting account.
     /// vesting_schedule: VestingSchedule: The vesting schedule of the account.
+    /// Sets a `Vec<RewardUserResponse>` as the response data, with one entry
+    /// per reward flagging whether it was registered or skipped. Skipped
+    /// rewards are not deducted from the unallocated amount.
+    /// denom: Option<String>: Denom to vest. Required for `multi_denom`
+    /// contracts.
     RewardUsers {
         rewards: Vec<RewardUserRequest>,
         vesting_schedule: VestingSchedule,
+        denom: Option<String>,
     },
 
     /// A creator operation that unregisters a vesting account
     /// and transfers the rest of tokens back to contract admin.
     /// Args:
     /// - addresses: Vec<String>: Bech 32 addresses of the owners of vesting accounts.
+    /// - force: Option<bool>: Allows deregistering before the cliff when the
+    ///   contract is configured with `deregister_requires_force_before_cliff`.
     DeregisterVestingAccounts {
         addresses: Vec<String>,
+        force: Option<bool>,
     },
 
     /// Claim is an operation that allows one to claim vested tokens.
-    Claim {},
+    /// - limit: Option<u32>: Claims from at most `limit` schedules with
+    ///   something claimable, so that addresses with many schedules can claim
+    ///   in several transactions. Claims from all schedules if unset.
+    ///
+    /// Sets a `ClaimResponse` as the response data.
+    Claim { limit: Option<u32> },
+
+    /// ClaimAndDelegate claims like `Claim` and delegates the native coins
+    /// sent to the sender to `validator`. A contract cannot delegate for a
+    /// user directly, so the delegation is an authz `MsgExec` from the user's
+    /// account: the sender must first grant this contract an authorization
+    /// for "/cosmos.staking.v1beta1.MsgDelegate". Without a grant, the whole
+    /// transaction fails.
+    ClaimAndDelegate { validator: String },
+
+    /// ClaimFor claims the vested tokens of `user_address` and sends them to
+    /// that user. Only callable by a relayer added with `AddRelayer`, which
+    /// lets users claim without paying gas.
+    ClaimFor { user_address: String },
 
-    // Withdraw allows the admin to withdraw the funds from the contract
+    /// An admin operation that allows `address` to use `ClaimFor`.
+    AddRelayer { address: String },
+
+    /// An admin operation that revokes `ClaimFor` from `address`.
+    RemoveRelayer { address: String },
+
+    // Withdraw allows the admin to withdraw the funds from the contract.
+    // The denom is required for `multi_denom` contracts.
     Withdraw {
         amount: Uint128,
+        denom: Option<String>,
+    },
+
+    /// An admin operation that registers vesting accounts from precomputed
+    /// `VestingData`, e.g. as exported from another vesting contract. The
+    /// unclaimed part of each account is deducted from the unallocated amount
+    /// and the claimed part (`vested_amount - claimable_amount`) is preserved.
+    /// The `cliff_claimable`/`linear_claimable` breakdown is ignored. Fails if
+    /// any of the accounts already exists. All accounts of one import must
+    /// share a denom.
+    ImportAccounts {
+        accounts: Vec<(String, VestingData)>,
+    },
+
+    /// An admin operation that reduces the vesting schedule `schedule_index`
+    /// of `user_address` by `reduce_by` and sends that amount back to the
+    /// admin. Without an index, the most recent schedule is reduced. Only the
+    /// part not yet vested can be clawed back. A schedule left with nothing to
+    /// claim is removed, and a clawback that would leave an invalid schedule,
+    /// e.g. a blackout schedule ending before its blackout does, fails.
+    Clawback {
+        user_address: String,
+        reduce_by: Uint128,
+        schedule_index: Option<u64>,
+    },
+
+    /// An admin operation that sends the contract balance in excess of the
+    /// unclaimed vesting amounts and the unallocated amount of each denom, such
+    /// as tokens donated to the contract by mistake, to `to` or to the admin.
+    ReclaimSurplus { to: Option<String> },
+
+    /// An admin operation that confirms the next milestone of schedule
+    /// `schedule_index` of `user_address`, which must be a `Milestone`
+    /// schedule. The tranche of that milestone becomes claimable.
+    ConfirmMilestone {
+        user_address: String,
+        schedule_index: u64,
     },
+
+    /// An admin operation that moves every vesting schedule of `from` to `to`,
+    /// keeping the amounts already claimed, e.g. when a user changes wallets.
+    /// The claimed total and claim cooldown of `from` move along with them.
+    /// Fails if `to` already has vesting schedules.
+    TransferVesting { from: String, to: String },
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct RewardUserRequest {
     pub user_address: String,
     pub vesting_amount: Uint128,
     pub cliff_amount: Uint128,
+    /// Fixed address that receives every claim of this schedule instead of
+    /// `user_address`, e.g. a custody account. Only `user_address` (or one of
+    /// the relayers on its behalf) can still trigger the claim.
+    pub payout_address: Option<String>,
+    /// Free-form reference, e.g. the ID of the grant in an off-chain system,
+    /// returned with the schedule by queries. At most `MAX_LABEL_LEN` bytes.
+    pub label: Option<String>,
 }
 
+/// Maximum length in bytes of a schedule label.
+pub const MAX_LABEL_LEN: usize = 128;
+
 impl RewardUserRequest {
     pub fn validate(&self) -> Result<(), ContractError> {
+        if let Some(label) = &self.label {
+            if label.len() > MAX_LABEL_LEN {
+                return Err(ContractError::Vesting(
+                    VestingError::LabelTooLong {
+                        len: label.len(),
+                        max_len: MAX_LABEL_LEN,
+                    },
+                ));
+            }
+        }
         if self.vesting_amount.is_zero() {
             return Err(ContractError::Vesting(VestingError::ZeroVestingAmount));
         }
@@ -72,6 +194,13 @@ pub struct RewardUserResponse {
     pub error_msg: String,
 }
 
+#[cw_serde]
+pub struct ClaimResponse {
+    /// Whether schedules with something claimable were left out because of
+    /// the `limit` of the claim.
+    pub has_more: bool,
+}
+
 #[cw_serde]
 pub struct DeregisterUserResponse {
     pub user_address: String,
@@ -90,12 +219,205 @@ pub enum QueryMsg {
     VestingAccounts {
         address: Vec<String>,
     },
+    /// Config: Returns the contract configuration set at instantiation.
+    Config {},
+    /// AggregateFor: Returns the vesting, vested and claimable amounts summed
+    /// over the given addresses at the current block time. Duplicate
+    /// addresses are counted once.
+    AggregateFor {
+        addresses: Vec<String>,
+    },
+    /// ClaimedAtHeight: Returns the cumulative amount `address` had claimed
+    /// at the beginning of block `height`, i.e. excluding claims made in that
+    /// block.
+    ClaimedAtHeight {
+        address: String,
+        height: u64,
+    },
+    /// SimulateDeregister: Returns what `DeregisterVestingAccounts` would
+    /// refund to the master at the current block time, with one entry per
+    /// address and vested denom. Fails if any address has no vesting account.
+    SimulateDeregister {
+        addresses: Vec<String>,
+    },
+    /// NextUnlock: Returns how long until the vested amount of `address`
+    /// next increases and by how much, over all of its schedules.
+    NextUnlock {
+        address: String,
+    },
+    /// ExpiringSoon: Returns the schedules that are fully vested by the
+    /// current block time plus `within_seconds`, ordered by address and
+    /// schedule index. This includes schedules that already ended but still
+    /// have tokens to claim. Pass the last `(user_address, schedule_index)` as
+    /// `start_after` to get the next page.
+    ExpiringSoon {
+        within_seconds: u64,
+        start_after: Option<(String, u64)>,
+        limit: Option<u32>,
+    },
+    /// Params: Returns the active configuration of the contract, including
+    /// the settings from instantiation and the current admin, managers and
+    /// relayers.
+    Params {},
+    /// SolvencyCheck: Returns, per denom, whether the contract balance covers
+    /// the vesting amounts that users have not yet claimed.
+    SolvencyCheck {},
+    /// Managers: Returns the admin and the managers, sorted, each with
+    /// whether the address still passes validation. This surfaces entries
+    /// stored before a change to the validation rules.
+    Managers {},
+    /// TotalClaimableNow: Returns, per denom, the amount all vesting accounts
+    /// could claim at the current block time. Iterates every schedule, so it
+    /// is meant for off-chain monitoring rather than contract-to-contract
+    /// queries.
+    TotalClaimableNow {},
+    /// ClaimPreview: Returns what a full claim by `address` would pay out at
+    /// the current block time and how much would remain vesting afterwards,
+    /// summed over all of its schedules.
+    ClaimPreview {
+        address: String,
+    },
+    /// UnderfundedSchedules: Returns the schedules of every denom the
+    /// contract balance does not cover, as reported by `SolvencyCheck`, with
+    /// the share of the balance each one would get if it were split pro rata
+    /// over the unclaimed amounts. Meant for managers deciding on a top-up;
+    /// queries carry no sender, so anyone can call it.
+    UnderfundedSchedules {},
+    /// Progress: Returns, for each schedule of `address`, the share of its
+    /// vesting amount that has vested and that was claimed, in basis points.
+    Progress {
+        address: String,
+    },
+}
+
+#[cw_serde]
+pub struct ManagersResponse {
+    pub admin: ManagerStatus,
+    pub managers: Vec<ManagerStatus>,
+}
+
+#[cw_serde]
+pub struct ManagerStatus {
+    pub address: String,
+    pub valid: bool,
+}
+
+#[cw_serde]
+pub struct SolvencyResponse {
+    pub denom: String,
+    /// Whether `balance` covers `obligations`.
+    pub solvent: bool,
+    /// Sum of `vesting_amount - claimed_amount` over the schedules of the
+    /// denom.
+    pub obligations: Uint128,
+    pub balance: Uint128,
+    /// Amount by which `balance` falls short of `obligations`.
+    pub shortfall: Uint128,
+}
+
+#[cw_serde]
+pub struct UnderfundedScheduleResponse {
+    pub user_address: String,
+    pub schedule_index: u64,
+    pub denom: String,
+    /// `vesting_amount - claimed_amount` of the schedule.
+    pub obligation: Uint128,
+    /// Pro-rata share of the contract balance of `denom`, rounded down.
+    pub available: Uint128,
+    /// Amount by which `available` falls short of `obligation`.
+    pub shortfall: Uint128,
+}
+
+#[cw_serde]
+pub struct ParamsResponse {
+    pub admin: String,
+    pub managers: Vec<String>,
+    pub relayers: Vec<String>,
+    /// Denoms the contract was funded with. Has a single entry unless
+    /// `multi_denom` is set.
+    pub funding_denoms: Vec<String>,
+    pub multi_denom: bool,
+    pub decimals: Option<u8>,
+    pub deregister_requires_force_before_cliff: bool,
+    pub claim_cooldown_blocks: Option<u64>,
+    pub rounding: VestingRounding,
+    /// Default and maximum page size of `ExpiringSoon`.
+    pub default_page_limit: u32,
+    pub max_page_limit: u32,
+}
+
+#[cw_serde]
+pub struct ExpiringScheduleResponse {
+    pub user_address: String,
+    pub schedule_index: u64,
+    pub denom: String,
+    /// Time in seconds at which the schedule is fully vested.
+    pub end_time: u64,
+    pub vesting_amount: Uint128,
+    pub claimed_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct NextUnlockResponse {
+    /// Seconds from the current block time until the next unlock, or `None`
+    /// if everything has vested.
+    pub seconds_until: Option<u64>,
+    /// Amount that unlocks then, summed over the schedules unlocking at the
+    /// same time.
+    pub amount_at_unlock: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimPreviewResponse {
+    /// Amount a claim at the current block time would pay out.
+    pub claim_now: Uint128,
+    /// Amount still unvested, and therefore unclaimed, after that claim.
+    pub remaining_after: Uint128,
+    /// Whether that claim would leave no schedule behind.
+    pub fully_claimed_after: bool,
+}
+
+#[cw_serde]
+pub struct ScheduleProgress {
+    pub schedule_index: u64,
+    /// Vested share of the vesting amount, in basis points, at most 10000.
+    pub vested_bps: u16,
+    /// Claimed share of the vesting amount, in basis points, at most 10000.
+    pub claimed_bps: u16,
 }
 
 #[cw_serde]
 pub struct VestingAccountResponse {
     pub address: String,
     pub vestings: Vec<VestingData>,
+    /// Decimal places of the vesting denom, if configured. Amounts are always
+    /// returned in the smallest unit.
+    pub decimals: Option<u8>,
+}
+
+#[cw_serde]
+pub struct ClaimedAtHeightResponse {
+    pub address: String,
+    pub height: u64,
+    pub claimed_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateDeregisterResponse {
+    pub user_address: String,
+    pub master: String,
+    pub denom: String,
+    /// Total amount sent to the master, i.e. everything not yet claimed.
+    pub refund_to_master: Uint128,
+    /// Part of `refund_to_master` that has vested but was not claimed.
+    pub vested_unclaimed: Uint128,
+}
+
+#[cw_serde]
+pub struct AggregateResponse {
+    pub total_vesting: Uint128,
+    pub total_vested: Uint128,
+    pub total_claimable: Uint128,
 }
 
 #[cw_serde]
@@ -106,7 +428,47 @@ pub struct VestingData {
     pub vesting_schedule: VestingScheduleQueryOutput,
 
     pub vested_amount: Uint128,
+    /// Total amount claimed from this schedule so far.
+    pub claimed_amount: Uint128,
     pub claimable_amount: Uint128,
+    /// Part of `claimable_amount` unlocked at the cliff. Claims are counted
+    /// against the cliff portion first.
+    pub cliff_claimable: Uint128,
+    /// Part of `claimable_amount` accrued linearly after the cliff, or unlocked
+    /// by the steps of a stepwise schedule.
+    pub linear_claimable: Uint128,
+    /// Label given to the schedule when it was registered.
+    pub label: Option<String>,
+}
+
+/// Rounding of the linearly interpolated vested amount. Everything is vested
+/// at `end_time` in either mode, so the final claim always releases the exact
+/// total.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum VestingRounding {
+    /// Rounds down to the base unit.
+    #[default]
+    Floor,
+    /// Rounds to the nearest base unit, with halves rounded up.
+    Nearest,
+}
+
+impl VestingRounding {
+    /// Returns `numerator / denominator` rounded with this mode.
+    pub fn divide(
+        &self,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> StdResult<Uint128> {
+        let numerator = match self {
+            VestingRounding::Floor => numerator,
+            VestingRounding::Nearest => {
+                numerator.checked_add(denominator / Uint128::new(2))?
+            }
+        };
+        Ok(numerator.checked_div(denominator)?)
+    }
 }
 
 #[cw_serde]
@@ -116,6 +478,26 @@ pub enum VestingSchedule {
         end_time: Uint64,   // vesting end time in second unit
         cliff_time: Uint64, // cliff time in second unit
     },
+    /// Tranche vesting: each `(unlock_time, amount)` step unlocks `amount` at
+    /// `unlock_time` (in seconds). Steps must be strictly time-ordered and sum
+    /// to the vesting amount. There is no separate cliff amount.
+    Stepwise { steps: Vec<(Uint64, Uint128)> },
+    /// Milestone vesting: each tranche unlocks once the admin confirms the
+    /// next milestone with `ConfirmMilestone`, independent of time. Tranches
+    /// are confirmed in order, must be nonzero and sum to the vesting amount.
+    /// There is no separate cliff amount.
+    Milestone { tranches: Vec<Uint128> },
+    /// Linear vesting from `start_time` to `end_time` (in seconds) that
+    /// accrues nothing between `blackout_start` and `blackout_end`, e.g. during
+    /// a trading restriction. The schedule ends later by the length of the
+    /// blackout. The blackout must lie within `[start_time, end_time]`. The
+    /// cliff amount of a reward unlocks at `start_time`.
+    LinearWithBlackout {
+        start_time: Uint64,
+        end_time: Uint64,
+        blackout_start: Uint64,
+        blackout_end: Uint64,
+    },
 }
 
 /// For legacy, we need the query to return the schedule with the vesting amount and cliff amount
@@ -128,12 +510,44 @@ pub enum VestingScheduleQueryOutput {
         vesting_amount: Uint128,
         cliff_amount: Uint128,
     },
+    Stepwise {
+        steps: Vec<(Uint64, Uint128)>,
+        vesting_amount: Uint128,
+    },
+    Milestone {
+        tranches: Vec<Uint128>,
+        /// Number of leading tranches confirmed by the admin so far.
+        milestones_confirmed: u64,
+        vesting_amount: Uint128,
+    },
+    LinearWithBlackout {
+        start_time: Uint64,
+        end_time: Uint64,
+        blackout_start: Uint64,
+        blackout_end: Uint64,
+        vesting_amount: Uint128,
+        cliff_amount: Uint128,
+    },
+}
+
+impl VestingScheduleQueryOutput {
+    /// Number of confirmed milestones, which is zero for time-based schedules.
+    pub fn milestones_confirmed(&self) -> u64 {
+        match self {
+            VestingScheduleQueryOutput::Milestone {
+                milestones_confirmed,
+                ..
+            } => *milestones_confirmed,
+            _ => 0,
+        }
+    }
 }
 
 pub fn from_vesting_to_query_output(
     vesting: &VestingSchedule,
     vesting_amount: Uint128,
     cliff_amount: Uint128,
+    milestones_confirmed: u64,
 ) -> VestingScheduleQueryOutput {
     match vesting {
         VestingSchedule::LinearVestingWithCliff {
@@ -147,6 +561,94 @@ pub fn from_vesting_to_query_output(
             vesting_amount,
             cliff_amount,
         },
+        VestingSchedule::Stepwise { steps } => {
+            VestingScheduleQueryOutput::Stepwise {
+                steps: steps.clone(),
+                vesting_amount,
+            }
+        }
+        VestingSchedule::Milestone { tranches } => {
+            VestingScheduleQueryOutput::Milestone {
+                tranches: tranches.clone(),
+                milestones_confirmed,
+                vesting_amount,
+            }
+        }
+        VestingSchedule::LinearWithBlackout {
+            start_time,
+            end_time,
+            blackout_start,
+            blackout_end,
+        } => VestingScheduleQueryOutput::LinearWithBlackout {
+            start_time: *start_time,
+            end_time: *end_time,
+            blackout_start: *blackout_start,
+            blackout_end: *blackout_end,
+            vesting_amount,
+            cliff_amount,
+        },
+    }
+}
+
+/// Inverse of `from_vesting_to_query_output`. Returns the schedule together
+/// with its vesting amount and cliff amount.
+pub fn from_query_output_to_vesting(
+    output: &VestingScheduleQueryOutput,
+) -> (VestingSchedule, Uint128, Uint128) {
+    match output {
+        VestingScheduleQueryOutput::LinearVestingWithCliff {
+            start_time,
+            end_time,
+            cliff_time,
+            vesting_amount,
+            cliff_amount,
+        } => (
+            VestingSchedule::LinearVestingWithCliff {
+                start_time: *start_time,
+                end_time: *end_time,
+                cliff_time: *cliff_time,
+            },
+            *vesting_amount,
+            *cliff_amount,
+        ),
+        VestingScheduleQueryOutput::Stepwise {
+            steps,
+            vesting_amount,
+        } => (
+            VestingSchedule::Stepwise {
+                steps: steps.clone(),
+            },
+            *vesting_amount,
+            Uint128::zero(),
+        ),
+        VestingScheduleQueryOutput::Milestone {
+            tranches,
+            vesting_amount,
+            ..
+        } => (
+            VestingSchedule::Milestone {
+                tranches: tranches.clone(),
+            },
+            *vesting_amount,
+            Uint128::zero(),
+        ),
+        VestingScheduleQueryOutput::LinearWithBlackout {
+            start_time,
+            end_time,
+            blackout_start,
+            blackout_end,
+            vesting_amount,
+            cliff_amount,
+        } => (
+            VestingSchedule::LinearWithBlackout {
+                start_time: *start_time,
+                end_time: *end_time,
+                blackout_start: *blackout_start,
+                blackout_end: *blackout_end,
+            },
+            *vesting_amount,
+            *cliff_amount,
+        ),
     }
 }
 
@@ -190,6 +692,160 @@ impl VestingSchedule {
                 }
                 Ok(())
             }
+            VestingSchedule::Stepwise { steps } => {
+                // An empty schedule would end at 0 and vest immediately.
+                if steps.is_empty()
+                    || steps.iter().any(|(_, amount)| amount.is_zero())
+                {
+                    return Err(VestingError::InvalidStepwiseSteps);
+                }
+                for pair in steps.windows(2) {
+                    if pair[1].0 <= pair[0].0 {
+                        return Err(VestingError::UnorderedSteps {
+                            unlock_time: pair[1].0.u64(),
+                        });
+                    }
+                }
+                Ok(())
+            }
+            VestingSchedule::Milestone { tranches } => {
+                if tranches.is_empty()
+                    || tranches.iter().any(|amount| amount.is_zero())
+                {
+                    return Err(VestingError::InvalidMilestoneTranches);
+                }
+                Ok(())
+            }
+            VestingSchedule::LinearWithBlackout {
+                start_time,
+                end_time,
+                blackout_start,
+                blackout_end,
+            } => {
+                if end_time <= start_time {
+                    return Err(VestingError::InvalidTimeRange {
+                        start_time: start_time.u64(),
+                        cliff_time: start_time.u64(),
+                        end_time: end_time.u64(),
+                    });
+                }
+                if blackout_start < start_time
+                    || blackout_end <= blackout_start
+                    || blackout_end > end_time
+                {
+                    return Err(VestingError::InvalidBlackout {
+                        blackout_start: blackout_start.u64(),
+                        blackout_end: blackout_end.u64(),
+                    });
+                }
+                let blackout_len = blackout_end.u64() - blackout_start.u64();
+                if end_time.u64().checked_add(blackout_len).is_none() {
+                    return Err(VestingError::BlackoutOverflow {
+                        end_time: end_time.u64(),
+                        blackout_start: blackout_start.u64(),
+                        blackout_end: blackout_end.u64(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// validate_amounts checks the amounts of a reward against the schedule.
+    /// Stepwise and milestone schedules must unlock exactly `vesting_amount`
+    /// and cannot have a cliff amount.
+    pub fn validate_amounts(
+        &self,
+        vesting_amount: Uint128,
+        cliff_amount: Uint128,
+    ) -> Result<(), VestingError> {
+        match self {
+            VestingSchedule::LinearVestingWithCliff { .. }
+            | VestingSchedule::LinearWithBlackout { .. } => Ok(()),
+            VestingSchedule::Stepwise { steps } => {
+                if !cliff_amount.is_zero() {
+                    return Err(VestingError::StepwiseCliffAmount {
+                        cliff_amount: cliff_amount.into(),
+                    });
+                }
+                let steps_amount = steps
+                    .iter()
+                    .try_fold(Uint128::zero(), |sum, (_, amount)| {
+                        sum.checked_add(*amount)
+                    })
+                    .map_err(|_| VestingError::StepsAmountOverflow)?;
+                if steps_amount != vesting_amount {
+                    return Err(VestingError::MismatchedStepsAmount {
+                        steps_amount: steps_amount.into(),
+                        vesting_amount: vesting_amount.into(),
+                    });
+                }
+                Ok(())
+            }
+            VestingSchedule::Milestone { tranches } => {
+                if !cliff_amount.is_zero() {
+                    return Err(VestingError::MilestoneCliffAmount {
+                        cliff_amount: cliff_amount.into(),
+                    });
+                }
+                let tranches_amount = tranches
+                    .iter()
+                    .try_fold(Uint128::zero(), |sum, amount| {
+                        sum.checked_add(*amount)
+                    })
+                    .map_err(|_| VestingError::TranchesAmountOverflow)?;
+                if tranches_amount != vesting_amount {
+                    return Err(VestingError::MismatchedTranchesAmount {
+                        tranches_amount: tranches_amount.into(),
+                        vesting_amount: vesting_amount.into(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// end_time returns the time at which everything is vested. For stepwise
+    /// schedules this is the last unlock time, and blackout schedules end
+    /// later by the length of their blackout. Milestone schedules do not vest
+    /// with time, so they never end and this is `u64::MAX`.
+    pub fn end_time(&self) -> u64 {
+        match self {
+            VestingSchedule::LinearVestingWithCliff { end_time, .. } => {
+                end_time.u64()
+            }
+            VestingSchedule::Stepwise { steps } => {
+                steps.last().map(|(time, _)| time.u64()).unwrap_or_default()
+            }
+            VestingSchedule::Milestone { .. } => u64::MAX,
+            VestingSchedule::LinearWithBlackout {
+                end_time,
+                blackout_start,
+                blackout_end,
+                ..
+            } => end_time.u64().saturating_add(
+                blackout_end.u64().saturating_sub(blackout_start.u64()),
+            ),
+        }
+    }
+
+    /// cliff_time returns the time before which nothing is vested. For
+    /// stepwise schedules this is the first unlock time and for blackout
+    /// schedules the start time. Milestone schedules have no time-based cliff,
+    /// so this is zero.
+    pub fn cliff_time(&self) -> u64 {
+        match self {
+            VestingSchedule::LinearVestingWithCliff { cliff_time, .. } => {
+                cliff_time.u64()
+            }
+            VestingSchedule::Stepwise { steps } => steps
+                .first()
+                .map(|(time, _)| time.u64())
+                .unwrap_or_default(),
+            VestingSchedule::Milestone { .. } => 0,
+            VestingSchedule::LinearWithBlackout { start_time, .. } => {
+                start_time.u64()
+            }
         }
     }
 }
@@ -1,16 +1,65 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use cosmwasm_schema::cw_serde;
 
-use crate::msg::VestingSchedule;
-use cosmwasm_std::{StdResult, Timestamp, Uint128};
-use cw_storage_plus::{Item, Map};
+use crate::errors::{ContractError, VestingError};
+use crate::msg::{VestingRounding, VestingSchedule};
+use cosmwasm_std::{
+    Order, StdError, StdResult, Storage, Timestamp, Uint128, Uint64,
+};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
 
-pub const VESTING_ACCOUNTS: Map<&str, VestingAccount> =
+/// Vesting schedules keyed by `(address, index)`. An address may hold several
+/// schedules; fully claimed or deregistered ones are removed.
+pub const VESTING_ACCOUNTS: Map<(&str, u64), VestingAccount> =
+    Map::new("vesting_schedules");
+/// Single schedule per address, as stored before schedules were keyed by
+/// index. Only read by `migrate`, which moves them to `VESTING_ACCOUNTS`.
+pub const LEGACY_VESTING_ACCOUNTS: Map<&str, VestingAccount> =
     Map::new("vesting_accounts");
+/// Number of schedules ever created per address, so indices are never reused.
+pub const SCHEDULE_COUNT: Map<&str, u64> = Map::new("schedule_count");
 pub const UNALLOCATED_AMOUNT: Item<Uint128> = Item::new("unallocated_amount");
 pub const DENOM: Item<String> = Item::new("denom");
+/// Unallocated amount per denom of a `multi_denom` contract. Single-denom
+/// contracts use `UNALLOCATED_AMOUNT` and `DENOM` instead.
+pub const UNALLOCATED_AMOUNTS: Map<&str, Uint128> =
+    Map::new("unallocated_amounts");
+/// Cumulative amount claimed through `Claim` per address, snapshotted at every
+/// block height it changes so past values can be queried.
+pub const CLAIMED_TOTAL: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "claimed_total",
+    "claimed_total__checkpoints",
+    "claimed_total__changelog",
+    Strategy::EveryBlock,
+);
+/// Block height of the last `Claim` or `ClaimFor` of each address, used to
+/// enforce the claim cooldown. Only recorded while
+/// `Config::claim_cooldown_blocks` is set.
+pub const LAST_CLAIM_HEIGHT: Map<&str, u64> = Map::new("last_claim_height");
 pub const WHITELIST: Item<Whitelist> = Item::new("whitelist");
+/// Addresses allowed to `ClaimFor` users, managed by the admin.
+pub const RELAYERS: Item<BTreeSet<String>> = Item::new("relayers");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct Config {
+    /// Decimal places of the vesting denom, used by clients to render amounts.
+    pub decimals: Option<u8>,
+    /// Whether deregistering before the cliff requires an explicit `force`.
+    pub deregister_requires_force_before_cliff: bool,
+    /// Whether the contract was funded with several denoms, each tracked in
+    /// `UNALLOCATED_AMOUNTS`.
+    #[serde(default)]
+    pub multi_denom: bool,
+    /// Minimum number of blocks between two claims of the same user, tracked
+    /// in `LAST_CLAIM_HEIGHT`. Disabled if `None`.
+    #[serde(default)]
+    pub claim_cooldown_blocks: Option<u64>,
+    /// Rounding given to the schedules registered by the contract.
+    #[serde(default)]
+    pub rounding: VestingRounding,
+}
 
 #[cw_serde]
 pub struct Whitelist {
@@ -37,38 +86,432 @@ pub struct VestingAccount {
     pub cliff_amount: Uint128,
     pub vesting_schedule: VestingSchedule,
     pub claimed_amount: Uint128,
+    /// Denom the schedule vests. `None` means the contract `DENOM`.
+    pub denom: Option<String>,
+    /// Number of leading tranches of a `Milestone` schedule confirmed by the
+    /// admin. Always zero for time-based schedules.
+    #[serde(default)]
+    pub milestones_confirmed: u64,
+    /// Address that receives the claims of this schedule. `None` means the
+    /// owner of the schedule, `address`.
+    #[serde(default)]
+    pub payout_address: Option<String>,
+    /// Label of the schedule, see `RewardUserRequest::label`.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Rounding of the linear interpolation, copied from the `Config` of the
+    /// contract when the schedule was registered.
+    #[serde(default)]
+    pub rounding: VestingRounding,
 }
 
 impl VestingAccount {
     pub fn vested_amount(&self, block_time: Timestamp) -> StdResult<Uint128> {
+        // Everything is vested from `end_time` on, so that rounding in the
+        // interpolation never strands dust. Milestone schedules have no end.
+        if block_time.seconds() >= self.vesting_schedule.end_time() {
+            return Ok(self.vesting_amount);
+        }
         match self.vesting_schedule {
             VestingSchedule::LinearVestingWithCliff {
                 start_time: _start_time,
                 end_time,
                 cliff_time,
             } => {
+                // The cliff is inclusive: all of `cliff_amount` vests at
+                // `cliff_time` and linear accrual starts from there.
                 if block_time.seconds() < cliff_time.u64() {
                     return Ok(Uint128::zero());
                 }
 
-                if block_time.seconds() == cliff_time.u64() {
-                    return Ok(self.cliff_amount);
-                }
-
-                if block_time.seconds() >= end_time.u64() {
-                    return Ok(self.vesting_amount);
-                }
-
                 let remaining_token =
                     self.vesting_amount.checked_sub(self.cliff_amount)?;
-                let vested_token = remaining_token
-                    .checked_mul(Uint128::from(
+                let vested_token = self.rounding.divide(
+                    remaining_token.checked_mul(Uint128::from(
                         block_time.seconds() - cliff_time.u64(),
-                    ))?
-                    .checked_div(Uint128::from(end_time - cliff_time))?;
+                    ))?,
+                    Uint128::from(end_time - cliff_time),
+                )?;
 
                 Ok(vested_token + self.cliff_amount)
             }
+            VestingSchedule::Stepwise { ref steps } => Ok(steps
+                .iter()
+                .filter(|(unlock_time, _)| {
+                    block_time.seconds() >= unlock_time.u64()
+                })
+                .map(|(_, amount)| *amount)
+                .sum()),
+            VestingSchedule::Milestone { ref tranches } => Ok(tranches
+                .iter()
+                .take(self.milestones_confirmed as usize)
+                .sum()),
+            VestingSchedule::LinearWithBlackout {
+                start_time,
+                end_time,
+                blackout_start,
+                blackout_end,
+            } => {
+                if block_time.seconds() < start_time.u64() {
+                    return Ok(Uint128::zero());
+                }
+                let duration = end_time.u64() - start_time.u64();
+                let elapsed = active_seconds(
+                    block_time.seconds(),
+                    start_time.u64(),
+                    blackout_start.u64(),
+                    blackout_end.u64(),
+                )
+                .min(duration);
+                let vested_token = self.rounding.divide(
+                    (self.vesting_amount - self.cliff_amount)
+                        .checked_mul(Uint128::from(elapsed))?,
+                    Uint128::from(duration),
+                )?;
+                Ok(vested_token + self.cliff_amount)
+            }
+        }
+    }
+
+    /// Splits the claimable amount at `block_time` into the part unlocked at
+    /// the cliff and the part accrued linearly, returned in that order.
+    pub fn claimable_breakdown(
+        &self,
+        block_time: Timestamp,
+    ) -> StdResult<(Uint128, Uint128)> {
+        let vested_amount = self.vested_amount(block_time)?;
+        let claimable_amount = vested_amount.checked_sub(self.claimed_amount)?;
+        let cliff_vested =
+            if block_time.seconds() < self.vesting_schedule.cliff_time() {
+                Uint128::zero()
+            } else {
+                self.cliff_amount.min(vested_amount)
+            };
+        let cliff_claimable = cliff_vested.saturating_sub(self.claimed_amount);
+        Ok((cliff_claimable, claimable_amount - cliff_claimable))
+    }
+
+    /// Returns the next block time in seconds after `block_time` at which the
+    /// vested amount increases, together with the amount that unlocks then.
+    /// Before the cliff of a linear schedule, that is the cliff and its cliff
+    /// amount. Past it, it is the next time at which at least one more base
+    /// unit has accrued. `None` once everything has vested, and always for
+    /// milestone schedules, which do not unlock with time.
+    pub fn next_unlock(
+        &self,
+        block_time: Timestamp,
+    ) -> StdResult<Option<(u64, Uint128)>> {
+        let now = block_time.seconds();
+        let vested_now = self.vested_amount(block_time)?;
+        let unlock_time = match &self.vesting_schedule {
+            VestingSchedule::LinearVestingWithCliff {
+                end_time,
+                cliff_time,
+                ..
+            } => {
+                let (cliff, end) = (cliff_time.u64(), end_time.u64());
+                if now < cliff && !self.cliff_amount.is_zero() {
+                    Some(cliff)
+                } else if now >= end
+                    || end == cliff
+                    || self.vesting_amount == self.cliff_amount
+                {
+                    None
+                } else {
+                    let elapsed = now.max(cliff) - cliff;
+                    let offset =
+                        self.next_linear_offset(end - cliff, elapsed)?;
+                    Some((cliff + offset).min(end))
+                }
+            }
+            VestingSchedule::LinearWithBlackout {
+                start_time,
+                end_time,
+                blackout_start,
+                blackout_end,
+            } => {
+                let (start, end) = (start_time.u64(), end_time.u64());
+                let (blackout_start, blackout_end) =
+                    (blackout_start.u64(), blackout_end.u64());
+                if now < start && !self.cliff_amount.is_zero() {
+                    Some(start)
+                } else if now >= self.vesting_schedule.end_time()
+                    || end == start
+                    || self.vesting_amount == self.cliff_amount
+                {
+                    None
+                } else {
+                    let elapsed = if now < start {
+                        0
+                    } else {
+                        active_seconds(now, start, blackout_start, blackout_end)
+                    };
+                    let offset =
+                        self.next_linear_offset(end - start, elapsed)?;
+                    // Map the accrual time back to a block time.
+                    let unlock_time = if start + offset > blackout_start {
+                        start + offset + (blackout_end - blackout_start)
+                    } else {
+                        start + offset
+                    };
+                    Some(unlock_time.min(self.vesting_schedule.end_time()))
+                }
+            }
+            VestingSchedule::Stepwise { steps } => steps
+                .iter()
+                .find(|(unlock_time, amount)| {
+                    unlock_time.u64() > now && !amount.is_zero()
+                })
+                .map(|(unlock_time, _)| unlock_time.u64()),
+            VestingSchedule::Milestone { .. } => None,
+        };
+        let Some(unlock_time) = unlock_time else {
+            return Ok(None);
+        };
+        let vested_then =
+            self.vested_amount(Timestamp::from_seconds(unlock_time))?;
+        Ok(Some((unlock_time, vested_then - vested_now)))
+    }
+
+    /// Seconds of accrual after which the linear part of the schedule, spread
+    /// over `duration` seconds, has accrued one more base unit than after
+    /// `elapsed` seconds.
+    fn next_linear_offset(&self, duration: u64, elapsed: u64) -> StdResult<u64> {
+        // Linear accrual is (linear * elapsed + half) / duration rounded down,
+        // where half is zero with floor rounding and duration / 2 with nearest
+        // rounding. The next base unit accrues once elapsed reaches
+        // ceil((next * duration - half) / linear).
+        let linear = self.vesting_amount - self.cliff_amount;
+        let duration = Uint128::from(duration);
+        let next = self
+            .rounding
+            .divide(linear.checked_mul(Uint128::from(elapsed))?, duration)?
+            .checked_add(Uint128::one())?;
+        let half = match self.rounding {
+            VestingRounding::Floor => Uint128::zero(),
+            VestingRounding::Nearest => duration / Uint128::new(2),
+        };
+        let offset = next
+            .checked_mul(duration)?
+            .checked_sub(half)?
+            .checked_add(linear - Uint128::one())?
+            .checked_div(linear)?;
+        Ok(offset.u128() as u64)
+    }
+
+    /// Reduces the vesting amount by `reduce_by`, taken only from the part not
+    /// yet vested at `block_time`. A linear schedule keeps its rate and ends
+    /// earlier, unless the reduction reaches into the cliff amount, in which
+    /// case the remainder unlocks at the cliff. Stepwise and milestone
+    /// schedules shrink their last unvested steps or unconfirmed tranches.
+    pub fn reduce_unvested(
+        &mut self,
+        block_time: Timestamp,
+        reduce_by: Uint128,
+    ) -> Result<(), ContractError> {
+        let unvested_amount =
+            self.vesting_amount - self.vested_amount(block_time)?;
+        if reduce_by > unvested_amount {
+            return Err(VestingError::ExcessiveClawback {
+                reduce_by: reduce_by.into(),
+                unvested_amount: unvested_amount.into(),
+            }
+            .into());
+        }
+        self.vesting_amount -= reduce_by;
+
+        match &mut self.vesting_schedule {
+            VestingSchedule::LinearVestingWithCliff {
+                end_time,
+                cliff_time,
+                ..
+            }
+            | VestingSchedule::LinearWithBlackout {
+                end_time,
+                start_time: cliff_time,
+                ..
+            } => {
+                let linear_amount =
+                    self.vesting_amount + reduce_by - self.cliff_amount;
+                if reduce_by > linear_amount {
+                    // Only possible before the cliff, when nothing is vested.
+                    self.cliff_amount = self.vesting_amount;
+                    *end_time = *cliff_time;
+                } else if !linear_amount.is_zero() {
+                    let duration = *end_time - *cliff_time;
+                    let shortened = (linear_amount - reduce_by)
+                        .multiply_ratio(duration.u64(), linear_amount);
+                    // Rounding down keeps the rate from decreasing, so the
+                    // vested amount never drops below what was claimed.
+                    *end_time =
+                        *cliff_time + Uint64::new(shortened.u128() as u64);
+                }
+            }
+            VestingSchedule::Stepwise { steps } => {
+                let mut remaining = reduce_by;
+                for (unlock_time, amount) in steps.iter_mut().rev() {
+                    if remaining.is_zero()
+                        || unlock_time.u64() <= block_time.seconds()
+                    {
+                        break;
+                    }
+                    let cut = remaining.min(*amount);
+                    *amount -= cut;
+                    remaining -= cut;
+                }
+                steps.retain(|(_, amount)| !amount.is_zero());
+            }
+            VestingSchedule::Milestone { tranches } => {
+                let mut remaining = reduce_by;
+                for amount in tranches
+                    .iter_mut()
+                    .skip(self.milestones_confirmed as usize)
+                    .rev()
+                {
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    let cut = remaining.min(*amount);
+                    *amount -= cut;
+                    remaining -= cut;
+                }
+                tranches.retain(|amount| !amount.is_zero());
+            }
         }
+        Ok(())
+    }
+}
+
+/// Seconds of accrual from `start` to `now`, not counting the blackout from
+/// `blackout_start` to `blackout_end`. Expects `now >= start`.
+fn active_seconds(
+    now: u64,
+    start: u64,
+    blackout_start: u64,
+    blackout_end: u64,
+) -> u64 {
+    now.min(blackout_start) - start + now.saturating_sub(blackout_end)
+}
+
+/// Loads all live schedules of `address`, ordered by index.
+pub fn load_schedules(
+    storage: &dyn Storage,
+    address: &str,
+) -> StdResult<Vec<(u64, VestingAccount)>> {
+    VESTING_ACCOUNTS
+        .prefix(address)
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+pub fn has_schedules(storage: &dyn Storage, address: &str) -> bool {
+    VESTING_ACCOUNTS
+        .prefix(address)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+}
+
+/// Stores `account` under the next free index of its address.
+pub fn save_new_schedule(
+    storage: &mut dyn Storage,
+    account: &VestingAccount,
+) -> StdResult<u64> {
+    let address = account.address.as_str();
+    let index = SCHEDULE_COUNT
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    VESTING_ACCOUNTS.save(storage, (address, index), account)?;
+    SCHEDULE_COUNT.save(storage, address, &(index + 1))?;
+    Ok(index)
+}
+
+/// Denom vested by `account`.
+pub fn schedule_denom(
+    storage: &dyn Storage,
+    account: &VestingAccount,
+) -> StdResult<String> {
+    match &account.denom {
+        Some(denom) => Ok(denom.clone()),
+        None => DENOM.load(storage),
+    }
+}
+
+/// Amount still owed to vesting accounts per denom: the vesting amount of every
+/// live schedule minus what was already claimed from it.
+pub fn unclaimed_by_denom(
+    storage: &dyn Storage,
+) -> StdResult<BTreeMap<String, Uint128>> {
+    let mut unclaimed: BTreeMap<String, Uint128> = BTreeMap::new();
+    for item in VESTING_ACCOUNTS.range(storage, None, None, Order::Ascending) {
+        let (_, account) = item?;
+        *unclaimed
+            .entry(schedule_denom(storage, &account)?)
+            .or_default() +=
+            account.vesting_amount.checked_sub(account.claimed_amount)?;
+    }
+    Ok(unclaimed)
+}
+
+/// Resolves the denom requested by a reward, withdrawal or import. Single-denom
+/// contracts default to `DENOM`, while `multi_denom` contracts require one of
+/// the funded denoms.
+pub fn resolve_denom(
+    storage: &dyn Storage,
+    denom: Option<String>,
+) -> StdResult<String> {
+    if !CONFIG.load(storage)?.multi_denom {
+        let contract_denom = DENOM.load(storage)?;
+        return match denom {
+            Some(denom) if denom != contract_denom => {
+                Err(StdError::generic_err(format!(
+                    "Denom {} is not funded, expected {}",
+                    denom, contract_denom
+                )))
+            }
+            _ => Ok(contract_denom),
+        };
+    }
+    let denom = denom.ok_or_else(|| {
+        StdError::generic_err("denom is required for a multi_denom contract")
+    })?;
+    if !UNALLOCATED_AMOUNTS.has(storage, &denom) {
+        return Err(StdError::generic_err(format!(
+            "Denom {} is not funded",
+            denom
+        )));
+    }
+    Ok(denom)
+}
+
+/// Denoms the contract was funded with.
+pub fn funded_denoms(storage: &dyn Storage) -> StdResult<Vec<String>> {
+    if !CONFIG.load(storage)?.multi_denom {
+        return Ok(vec![DENOM.load(storage)?]);
+    }
+    UNALLOCATED_AMOUNTS
+        .keys(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// Unallocated amount of `denom`, which must be resolved with `resolve_denom`.
+pub fn load_unallocated(
+    storage: &dyn Storage,
+    denom: &str,
+) -> StdResult<Uint128> {
+    if CONFIG.load(storage)?.multi_denom {
+        return UNALLOCATED_AMOUNTS.load(storage, denom);
+    }
+    UNALLOCATED_AMOUNT.load(storage)
+}
+
+pub fn save_unallocated(
+    storage: &mut dyn Storage,
+    denom: &str,
+    amount: &Uint128,
+) -> StdResult<()> {
+    if CONFIG.load(storage)?.multi_denom {
+        return UNALLOCATED_AMOUNTS.save(storage, denom, amount);
     }
+    UNALLOCATED_AMOUNT.save(storage, amount)
 }
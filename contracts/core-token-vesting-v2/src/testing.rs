@@ -1,19 +1,33 @@
-use crate::contract::{execute, instantiate, query};
+use crate::authz;
+use crate::contract::{execute, instantiate, migrate, query, CONTRACT_VERSION};
 use crate::errors::{ContractError, VestingError};
 use crate::msg::{
-    DeregisterUserResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    RewardUserRequest, VestingAccountResponse, VestingData, VestingSchedule,
-    VestingScheduleQueryOutput,
+    AggregateResponse, ClaimPreviewResponse, ClaimResponse,
+    ClaimedAtHeightResponse, DeregisterUserResponse, ExecuteMsg,
+    ExpiringScheduleResponse, InstantiateMsg, ManagerStatus, ManagersResponse,
+    MigrateMsg, NextUnlockResponse, ParamsResponse, QueryMsg, RewardUserRequest,
+    RewardUserResponse, ScheduleProgress, SimulateDeregisterResponse,
+    SolvencyResponse, UnderfundedScheduleResponse, VestingAccountResponse,
+    VestingData, VestingRounding, VestingSchedule, VestingScheduleQueryOutput,
 };
+use crate::state::{
+    Config, VestingAccount, Whitelist, DENOM, LAST_CLAIM_HEIGHT,
+    LEGACY_VESTING_ACCOUNTS, UNALLOCATED_AMOUNT, WHITELIST,
+};
+use std::collections::HashSet;
 
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{coin, testing, Empty, MessageInfo};
+use cosmwasm_std::{coin, testing, Deps, DepsMut, Empty, MessageInfo, Storage};
 use cosmwasm_std::{
     from_json,
-    testing::{mock_dependencies, mock_env, mock_info},
-    Attribute, BankMsg, Coin, Env, OwnedDeps, Response, StdError, SubMsg,
-    Timestamp, Uint128, Uint64,
+    testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    },
+    Attribute, BankMsg, Coin, Env, Event, Order, OwnedDeps, Response,
+    StakingMsg, StdError, StdResult, SubMsg, Timestamp, Uint128, Uint64,
 };
+use cosmwasm_std::{CosmosMsg, Decimal, Validator};
+use prost::Message;
 
 pub type TestResult = Result<(), anyhow::Error>;
 
@@ -28,7 +42,8 @@ pub fn mock_env_with_time(block_time: u64) -> Env {
 pub fn setup_with_block_time(
     block_time: u64,
 ) -> anyhow::Result<(OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, Env)> {
-    let mut deps = testing::mock_dependencies();
+    let mut deps =
+        testing::mock_dependencies_with_balance(&[coin(5000, "token")]);
     let env = mock_env_with_time(block_time);
     instantiate(
         deps.as_mut(),
@@ -37,6 +52,12 @@ pub fn setup_with_block_time(
         InstantiateMsg {
             admin: "admin-sender".to_string(),
             managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
         },
     )?;
     Ok((deps, env))
@@ -49,6 +70,12 @@ fn proper_initialization() -> TestResult {
     let msg = InstantiateMsg {
         admin: "admin-sender".to_string(),
         managers: vec!["admin-sender".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
 
     let info = mock_info("addr0000", &[coin(1000, "nibi")]);
@@ -58,70 +85,159 @@ fn proper_initialization() -> TestResult {
 }
 
 #[test]
-fn invalid_coin_sent_instantiation() -> TestResult {
+fn instantiate_with_initial_rewards() -> TestResult {
     let mut deps = mock_dependencies();
-
-    let msg = InstantiateMsg {
+    let env = mock_env_with_time(100);
+    let schedule = VestingSchedule::LinearVestingWithCliff {
+        start_time: Uint64::new(100),
+        cliff_time: Uint64::new(200),
+        end_time: Uint64::new(300),
+    };
+    let msg = |rewards: Vec<RewardUserRequest>| InstantiateMsg {
         admin: "admin-sender".to_string(),
-        managers: vec!["admin-sender".to_string()],
+        managers: vec!["manager-sender".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: Some((rewards, schedule.clone())),
+    };
+    let reward = |vesting_amount: u128| RewardUserRequest {
+        user_address: "addr0001".to_string(),
+        vesting_amount: Uint128::new(vesting_amount),
+        cliff_amount: Uint128::new(1000),
+        payout_address: None,
+        label: None,
     };
 
-    // No coins sent
-    let res = instantiate(
+    // Initial rewards are validated like RewardUsers
+    let err = instantiate(
         deps.as_mut(),
-        mock_env(),
-        mock_info("addr0000", &[]),
-        msg.clone(),
-    );
-    match res {
-        Err(err) => {
-            assert_eq!(
-                err,
-                StdError::GenericErr {
-                    msg: "must deposit exactly one type of token".to_string(),
-                }
-            )
-        }
-        Ok(_) => panic!("Expected error but got success: {res:?}"),
-    }
+        env.clone(),
+        mock_info("deployer", &[coin(5000, "token")]),
+        msg(vec![reward(6000)]),
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Insufficient funds for all rewards"));
 
-    // 2 coins sent
+    // Unlike RewardUsers, a reward that fails to register fails the whole
+    // instantiation
+    let err = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("deployer", &[coin(5000, "token")]),
+        msg(vec![reward(1000), reward(1000)]),
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("User addr0001 already has a vesting account"));
+
+    let mut deps = mock_dependencies_with_balance(&[coin(5000, "token")]);
     let res = instantiate(
         deps.as_mut(),
-        mock_env(),
-        mock_info("addr0000", &[coin(1000, "nibi"), coin(1000, "usd")]),
-        msg.clone(),
+        env.clone(),
+        mock_info("deployer", &[coin(5000, "token")]),
+        msg(vec![reward(4000)]),
+    )?;
+    let data: Vec<RewardUserResponse> = from_json(res.data.unwrap())?;
+    assert!(data[0].success);
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(250),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings.len(), 1);
+    assert_eq!(res.vestings[0].vesting_amount, Uint128::new(4000));
+    assert_eq!(res.vestings[0].vested_amount, Uint128::new(2500));
+    assert_eq!(res.vestings[0].master_address, Some("admin-sender".into()));
+
+    // The rest of the deposit stays unallocated
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::Withdraw {
+            amount: Uint128::new(5000),
+            denom: None,
+        },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "admin-sender".to_string(),
+            amount: vec![coin(1000, "token")],
+        })]
     );
-    match res {
-        Err(err) => {
-            assert_eq!(
-                err,
-                StdError::GenericErr {
-                    msg: "must deposit exactly one type of token".to_string(),
-                }
-            )
-        }
-        Ok(_) => panic!("Expected error but got success: {res:?}"),
+    Ok(())
+}
+
+#[test]
+fn invalid_coin_sent_instantiation() -> TestResult {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        admin: "admin-sender".to_string(),
+        managers: vec!["admin-sender".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
+    };
+
+    let test_cases: Vec<(Vec<Coin>, ContractError, &str)> = vec![
+        (
+            vec![],
+            ContractError::NoFundsSent,
+            "must deposit exactly one type of token",
+        ),
+        (
+            vec![coin(1000, "nibi"), coin(1000, "usd")],
+            ContractError::MultipleDenoms,
+            "must deposit exactly one type of token",
+        ),
+        (
+            vec![coin(0, "nibi")],
+            ContractError::ZeroAmount,
+            "must deposit some token",
+        ),
+    ];
+    for (funds, want_err, want_msg) in test_cases {
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &funds),
+            msg.clone(),
+        );
+        let err = res.expect_err("instantiate should fail");
+        // the Display text is kept for clients matching on the message
+        assert_eq!(err.to_string(), want_msg);
+        assert_eq!(err, want_err, "funds: {funds:?}");
     }
 
-    // 0 amount coins sent
+    // multi_denom contracts still need funds
     let res = instantiate(
         deps.as_mut(),
         mock_env(),
-        mock_info("addr0000", &[coin(0, "nibi")]),
-        msg,
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            multi_denom: Some(true),
+            claim_cooldown_blocks: None,
+            initial_rewards: None,
+            ..msg
+        },
     );
-    match res {
-        Err(err) => {
-            assert_eq!(
-                err,
-                StdError::GenericErr {
-                    msg: "must deposit some token".to_string(),
-                }
-            )
-        }
-        Ok(_) => panic!("Expected error but got success: {res:?}"),
-    }
+    assert_eq!(res.unwrap_err(), ContractError::NoFundsSent);
 
     Ok(())
 }
@@ -133,6 +249,12 @@ fn invalid_manangers_initialization() -> TestResult {
     let msg = InstantiateMsg {
         admin: "admin-sender".to_string(),
         managers: vec![],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
 
     let info = mock_info("addr0000", &[coin(1000, "nibi")]);
@@ -142,9 +264,9 @@ fn invalid_manangers_initialization() -> TestResult {
         Err(err) => {
             assert_eq!(
                 err,
-                StdError::GenericErr {
+                ContractError::Std(StdError::GenericErr {
                     msg: "managers cannot be empty".to_string(),
-                }
+                })
             )
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
@@ -153,15 +275,21 @@ fn invalid_manangers_initialization() -> TestResult {
     let msg = InstantiateMsg {
         admin: "admin-sender".to_string(),
         managers: vec!["".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone());
     match res {
         Err(err) => {
             assert_eq!(
                 err,
-                StdError::GenericErr {
+                ContractError::Std(StdError::GenericErr {
                     msg: "Invalid input: human address too short for this mock implementation (must be >= 3).".to_string(),
-                }
+                })
             )
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
@@ -170,15 +298,21 @@ fn invalid_manangers_initialization() -> TestResult {
     let msg = InstantiateMsg {
         admin: "admin-sender".to_string(),
         managers: vec!["admin-sender".to_string(), "".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone());
     match res {
         Err(err) => {
             assert_eq!(
                 err,
-                StdError::GenericErr {
+                ContractError::Std(StdError::GenericErr {
                     msg: "Invalid input: human address too short for this mock implementation (must be >= 3).".to_string(),
-                }
+                })
             )
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
@@ -187,15 +321,21 @@ fn invalid_manangers_initialization() -> TestResult {
     let msg = InstantiateMsg {
         admin: "".to_string(),
         managers: vec!["admin-sender".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone());
     match res {
         Err(err) => {
             assert_eq!(
                 err,
-                StdError::GenericErr {
+                ContractError::Std(StdError::GenericErr {
                     msg: "Invalid input: human address too short for this mock implementation (must be >= 3).".to_string(),
-                }
+                })
             )
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
@@ -211,6 +351,12 @@ fn invalid_managers() -> TestResult {
     let msg = InstantiateMsg {
         admin: "admin-sender".to_string(),
         managers: vec!["admin-manager".to_string()],
+        decimals: None,
+        deregister_requires_force_before_cliff: None,
+        multi_denom: None,
+        claim_cooldown_blocks: None,
+        rounding: None,
+        initial_rewards: None,
     };
 
     // No coins sent
@@ -222,12 +368,7 @@ fn invalid_managers() -> TestResult {
     );
     match res {
         Err(err) => {
-            assert_eq!(
-                err,
-                StdError::GenericErr {
-                    msg: "must deposit exactly one type of token".to_string(),
-                }
-            )
+            assert_eq!(err, ContractError::NoFundsSent)
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
     }
@@ -241,12 +382,7 @@ fn invalid_managers() -> TestResult {
     );
     match res {
         Err(err) => {
-            assert_eq!(
-                err,
-                StdError::GenericErr {
-                    msg: "must deposit exactly one type of token".to_string(),
-                }
-            )
+            assert_eq!(err, ContractError::MultipleDenoms)
         }
         Ok(_) => panic!("Expected error but got success: {res:?}"),
     }
@@ -264,6 +400,12 @@ fn register_cliff_vesting_account_with_native_token() -> TestResult {
         InstantiateMsg {
             admin: "addr0000".to_string(),
             managers: vec!["admin-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
         },
     )?;
 
@@ -277,10 +419,13 @@ fn register_cliff_vesting_account_with_native_token() -> TestResult {
                       cliff_time: u64|
      -> ExecuteMsg {
         ExecuteMsg::RewardUsers {
+            denom: None,
             rewards: vec![RewardUserRequest {
                 user_address: "addr0001".to_string(),
                 vesting_amount: Uint128::new(vesting_amount),
                 cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
             }],
             vesting_schedule: VestingSchedule::LinearVestingWithCliff {
                 start_time: Uint64::new(start_time),
@@ -404,16 +549,21 @@ fn register_cliff_vesting_account_with_native_token() -> TestResult {
     let cliff_time = 105u64;
 
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![
             RewardUserRequest {
                 user_address: "addr0002".to_string(),
                 vesting_amount: Uint128::new(vesting_amount),
                 cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
             },
             RewardUserRequest {
                 user_address: "addr0002".to_string(),
                 vesting_amount: Uint128::new(vesting_amount),
                 cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
             },
         ],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
@@ -453,7 +603,7 @@ fn register_cliff_vesting_account_with_native_token() -> TestResult {
 
 #[test]
 fn test_withdraw() -> TestResult {
-    let mut deps = mock_dependencies();
+    let mut deps = mock_dependencies_with_balance(&[coin(2000, "uusd")]);
     let _res = instantiate(
         deps.as_mut(),
         mock_env(),
@@ -461,6 +611,12 @@ fn test_withdraw() -> TestResult {
         InstantiateMsg {
             admin: "addr0000".to_string(),
             managers: vec!["admin-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
         },
     )?;
 
@@ -474,10 +630,13 @@ fn test_withdraw() -> TestResult {
                       cliff_time: u64|
      -> ExecuteMsg {
         ExecuteMsg::RewardUsers {
+            denom: None,
             rewards: vec![RewardUserRequest {
                 user_address: "addr0001".to_string(),
                 vesting_amount: Uint128::new(vesting_amount),
                 cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
             }],
             vesting_schedule: VestingSchedule::LinearVestingWithCliff {
                 start_time: Uint64::new(start_time),
@@ -498,6 +657,7 @@ fn test_withdraw() -> TestResult {
 
     // unauthorized sender
     let msg = ExecuteMsg::Withdraw {
+        denom: None,
         amount: Uint128::new(1000),
     };
     require_error(
@@ -510,6 +670,7 @@ fn test_withdraw() -> TestResult {
 
     // withdraw more than unallocated
     let msg = ExecuteMsg::Withdraw {
+        denom: None,
         amount: Uint128::new(1001),
     };
     let res =
@@ -539,6 +700,7 @@ fn test_withdraw() -> TestResult {
 
     // withdraw but there's no more unallocated
     let msg = ExecuteMsg::Withdraw {
+        denom: None,
         amount: Uint128::new(1),
     };
     require_error(
@@ -578,6 +740,12 @@ fn register_vesting_account_with_native_token() -> TestResult {
         InstantiateMsg {
             admin: "addr0000".to_string(),
             managers: vec!["admin-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
         },
     )?;
 
@@ -586,10 +754,13 @@ fn register_vesting_account_with_native_token() -> TestResult {
 
     // zero amount vesting token
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![RewardUserRequest {
             user_address: "addr0001".to_string(),
             vesting_amount: Uint128::zero(),
             cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
         }],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
             start_time: Uint64::new(100),
@@ -608,10 +779,13 @@ fn register_vesting_account_with_native_token() -> TestResult {
 
     // too much vesting amount
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![RewardUserRequest {
             user_address: "addr0001".to_string(),
             vesting_amount: Uint128::new(1000001u128),
             cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
         }],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
             start_time: Uint64::new(100),
@@ -633,16 +807,21 @@ fn register_vesting_account_with_native_token() -> TestResult {
 
     // too much vesting amount in 2 rewards
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![
             RewardUserRequest {
                 user_address: "addr0001".to_string(),
                 vesting_amount: Uint128::new(1000u128),
                 cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
             },
             RewardUserRequest {
                 user_address: "addr0001".to_string(),
                 vesting_amount: Uint128::new(1u128),
                 cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
             },
         ],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
@@ -665,10 +844,13 @@ fn register_vesting_account_with_native_token() -> TestResult {
 
     // valid amount
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![RewardUserRequest {
             user_address: "addr0001".to_string(),
             vesting_amount: Uint128::new(100u128),
             cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
         }],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
             start_time: Uint64::new(100),
@@ -726,8 +908,13 @@ fn register_vesting_account_with_native_token() -> TestResult {
                     },
                 vesting_denom: cw20::Denom::Native("uusd".to_string()),
                 vested_amount: Uint128::zero(),
+                claimed_amount: Uint128::zero(),
                 claimable_amount: Uint128::zero(),
-            }]
+                cliff_claimable: Uint128::zero(),
+                linear_claimable: Uint128::zero(),
+                label: None,
+            }],
+            decimals: None,
         },
     );
     Ok(())
@@ -743,6 +930,12 @@ fn claim_native() -> TestResult {
         InstantiateMsg {
             admin: "addr0000".to_string(),
             managers: vec!["admin-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
         },
     )?;
 
@@ -752,10 +945,13 @@ fn claim_native() -> TestResult {
 
     // valid amount
     let msg = ExecuteMsg::RewardUsers {
+        denom: None,
         rewards: vec![RewardUserRequest {
             user_address: "addr0001".to_string(),
             vesting_amount: Uint128::new(1000000u128),
             cliff_amount: Uint128::new(500000u128),
+            payout_address: None,
+            label: None,
         }],
         vesting_schedule: VestingSchedule::LinearVestingWithCliff {
             start_time: Uint64::new(100),
@@ -793,7 +989,7 @@ fn claim_native() -> TestResult {
 
     // valid claim
     let info = mock_info("addr0001", &[]);
-    let msg = ExecuteMsg::Claim {};
+    let msg = ExecuteMsg::Claim { limit: None };
 
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
     assert_eq!(
@@ -842,8 +1038,13 @@ fn claim_native() -> TestResult {
                     },
                 vesting_denom: cw20::Denom::Native("uusd".to_string()),
                 vested_amount: Uint128::new(500000u128),
+                claimed_amount: Uint128::new(500000u128),
                 claimable_amount: Uint128::zero(),
-            }]
+                cliff_claimable: Uint128::zero(),
+                linear_claimable: Uint128::zero(),
+                label: None,
+            }],
+            decimals: None,
         },
     );
 
@@ -886,18 +1087,249 @@ fn claim_native() -> TestResult {
         VestingAccountResponse {
             address: "addr0001".to_string(),
             vestings: vec![],
+            decimals: None,
         }
     );
 
     Ok(())
 }
 
+#[test]
+fn claim_removes_fully_claimed_schedule() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward_msg =
+        |vesting_amount: u128, end_time: u64| ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(vesting_amount),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(end_time),
+            },
+        };
+    for msg in [reward_msg(1000, 110), reward_msg(2000, 200)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            msg,
+        )?;
+    }
+
+    // the first schedule is fully vested, the second one is at 10%
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(110),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![Coin {
+                denom: "token".to_string(),
+                amount: Uint128::new(1200),
+            }],
+        })]
+    );
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(110),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings.len(), 1);
+    assert_eq!(res.vestings[0].vesting_amount, Uint128::new(2000));
+    assert_eq!(res.vestings[0].vested_amount, Uint128::new(200));
+    assert_eq!(res.vestings[0].claimable_amount, Uint128::zero());
+    Ok(())
+}
+
+#[test]
+fn stepwise_vesting_unlocks_tranches() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let steps = vec![
+        (Uint64::new(200), Uint128::new(1000)),
+        (Uint64::new(300), Uint128::new(1000)),
+        (Uint64::new(400), Uint128::new(2000)),
+    ];
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(4000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::Stepwise {
+                steps: steps.clone(),
+            },
+        },
+    )?;
+
+    let vested = |deps: Deps, block_time: u64| -> anyhow::Result<u128> {
+        let res: VestingAccountResponse = from_json(query(
+            deps,
+            mock_env_with_time(block_time),
+            QueryMsg::VestingAccount {
+                address: "addr0001".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )?)?;
+        assert_eq!(
+            res.vestings[0].vesting_schedule,
+            VestingScheduleQueryOutput::Stepwise {
+                steps: steps.clone(),
+                vesting_amount: Uint128::new(4000),
+            }
+        );
+        Ok(res.vestings[0].vested_amount.u128())
+    };
+    assert_eq!(vested(deps.as_ref(), 199)?, 0);
+    assert_eq!(vested(deps.as_ref(), 200)?, 1000);
+    assert_eq!(vested(deps.as_ref(), 299)?, 1000);
+    assert_eq!(vested(deps.as_ref(), 300)?, 2000);
+    assert_eq!(vested(deps.as_ref(), 400)?, 4000);
+
+    // claiming across the tranches
+    let claim = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                 block_time: u64|
+     -> anyhow::Result<Vec<Attribute>> {
+        let res = execute(
+            deps.as_mut(),
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )?;
+        Ok(res.attributes)
+    };
+    assert_eq!(
+        claim(&mut deps, 250)?[4],
+        Attribute::new("claim_amount", "1000")
+    );
+    assert_eq!(
+        claim(&mut deps, 350)?[4],
+        Attribute::new("claim_amount", "1000")
+    );
+    assert_eq!(
+        claim(&mut deps, 450)?[4],
+        Attribute::new("claim_amount", "2000")
+    );
+    assert_eq!(schedule_len(deps.as_ref(), "addr0001")?, 0);
+    Ok(())
+}
+
+#[test]
+fn stepwise_vesting_validation() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let msg =
+        |steps: Vec<(u64, u128)>, cliff_amount: u128| ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(3000),
+                cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::Stepwise {
+                steps: steps
+                    .into_iter()
+                    .map(|(time, amount)| {
+                        (Uint64::new(time), Uint128::new(amount))
+                    })
+                    .collect(),
+            },
+        };
+
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        msg(vec![(200, 1000), (200, 2000)], 0),
+        VestingError::UnorderedSteps { unlock_time: 200 }.into(),
+    );
+    for steps in [vec![], vec![(200, 3000), (300, 0)]] {
+        require_error(
+            &mut deps,
+            &env,
+            mock_info("admin-sender", &[]),
+            msg(steps, 0),
+            VestingError::InvalidStepwiseSteps.into(),
+        );
+    }
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        msg(vec![(200, u128::MAX), (300, 1)], 0),
+        VestingError::StepsAmountOverflow.into(),
+    );
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        msg(vec![(200, 1000), (300, 1000)], 0),
+        VestingError::MismatchedStepsAmount {
+            steps_amount: 2000,
+            vesting_amount: 3000,
+        }
+        .into(),
+    );
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        msg(vec![(200, 1000), (300, 2000)], 500),
+        VestingError::StepwiseCliffAmount { cliff_amount: 500 }.into(),
+    );
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        msg(vec![(200, 1000), (300, 2000)], 0),
+    )?;
+    assert_eq!(schedule_len(deps.as_ref(), "addr0001")?, 1);
+    Ok(())
+}
+
+fn schedule_len(deps: Deps, address: &str) -> anyhow::Result<usize> {
+    let res: VestingAccountResponse = from_json(query(
+        deps,
+        mock_env(),
+        QueryMsg::VestingAccount {
+            address: address.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    Ok(res.vestings.len())
+}
+
 #[test]
 fn deregister_err_nonexistent_vesting_account() -> TestResult {
     let (mut deps, env) = setup_with_block_time(50)?;
 
     let msg = ExecuteMsg::DeregisterVestingAccounts {
         addresses: vec!["nonexistent".to_string()],
+        force: None,
     };
     let res = execute(
         deps.as_mut(),
@@ -923,6 +1355,7 @@ fn deregister_err_unauthorized_vesting_account() -> TestResult {
     // Try to deregister with unauthorized sender
     let msg = ExecuteMsg::DeregisterVestingAccounts {
         addresses: vec!["addr0001".to_string()],
+        force: None,
     };
     require_error(
         &mut deps,
@@ -947,10 +1380,13 @@ fn deregister_successful() -> TestResult {
         env.clone(), // Use the custom environment with the adjusted block time
         testing::mock_info("admin-sender", &[]),
         ExecuteMsg::RewardUsers {
+            denom: None,
             rewards: vec![RewardUserRequest {
                 user_address: "addr0001".to_string(),
                 vesting_amount: Uint128::new(5000u128),
                 cliff_amount: Uint128::new(1250u128),
+                payout_address: None,
+                label: None,
             }],
             vesting_schedule: VestingSchedule::LinearVestingWithCliff {
                 start_time: Uint64::new(100),
@@ -965,7 +1401,7 @@ fn deregister_successful() -> TestResult {
         deps.as_mut(),
         env.clone(),
         testing::mock_info("addr0001", &[]),
-        ExecuteMsg::Claim {},
+        ExecuteMsg::Claim { limit: None },
     )?;
 
     // Deregister with the manager address
@@ -975,6 +1411,7 @@ fn deregister_successful() -> TestResult {
         testing::mock_info("manager-sender", &[]),
         ExecuteMsg::DeregisterVestingAccounts {
             addresses: vec!["addr0001".to_string()],
+            force: None,
         },
     )?;
     let data =
@@ -1003,60 +1440,3347 @@ fn deregister_successful() -> TestResult {
 }
 
 #[test]
-fn query_vesting_accounts() -> TestResult {
-    // Set up the environment with a block time before the vesting start time
+fn simulate_deregister_matches_refund() -> TestResult {
     let (mut deps, env) = setup_with_block_time(105)?;
-
-    let register_msg = ExecuteMsg::RewardUsers {
-        rewards: vec![RewardUserRequest {
-            user_address: "addr0001".to_string(),
-            vesting_amount: Uint128::new(5000u128),
-            cliff_amount: Uint128::new(1250u128),
-        }],
-        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
-            start_time: Uint64::new(100),
-            end_time: Uint64::new(110),
-            cliff_time: Uint64::new(105),
-        },
-    };
-
     execute(
         deps.as_mut(),
-        env.clone(), // Use the custom environment with the adjusted block time
+        env.clone(),
         testing::mock_info("admin-sender", &[]),
-        register_msg,
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(5000u128),
+                cliff_amount: Uint128::new(1250u128),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+    // claim the cliff amount
+    execute(
+        deps.as_mut(),
+        env,
+        testing::mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
     )?;
 
-    let res = query(
+    let env = mock_env_with_time(107);
+    let simulated: Vec<SimulateDeregisterResponse> = from_json(query(
         deps.as_ref(),
-        env,
-        QueryMsg::VestingAccounts {
-            address: vec!["addr0001".to_string()],
+        env.clone(),
+        QueryMsg::SimulateDeregister {
+            addresses: vec!["addr0001".to_string()],
         },
-    )?;
+    )?)?;
+    assert_eq!(
+        simulated,
+        vec![SimulateDeregisterResponse {
+            user_address: "addr0001".to_string(),
+            master: "admin-sender".to_string(),
+            denom: "token".to_string(),
+            refund_to_master: Uint128::new(3750),
+            vested_unclaimed: Uint128::new(1500),
+        }]
+    );
 
-    let response_items: Vec<VestingAccountResponse> = from_json(res).unwrap();
+    // unknown addresses fail the simulation
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SimulateDeregister {
+            addresses: vec!["addr0001".to_string(), "addr0002".to_string()],
+        },
+    )
+    .unwrap_err();
     assert_eq!(
-        response_items[0],
-        VestingAccountResponse {
-            address: "addr0001".to_string(),
-            vestings: vec![VestingData {
-                master_address: Some("admin-sender".to_string()),
-                vesting_amount: Uint128::new(5000u128),
-                vesting_schedule:
-                    VestingScheduleQueryOutput::LinearVestingWithCliff {
-                        start_time: Uint64::new(100),
-                        end_time: Uint64::new(110),
-                        cliff_time: Uint64::new(105),
-                        vesting_amount: Uint128::new(5000u128),
-                        cliff_amount: Uint128::new(1250u128),
-                    },
-                vesting_denom: cw20::Denom::Native("token".to_string()),
-                vested_amount: Uint128::new(1250u128),
-                claimable_amount: Uint128::new(1250u128),
-            }]
-        }
+        err,
+        StdError::generic_err("User addr0002 does not have a vesting account.")
     );
 
+    let res = execute(
+        deps.as_mut(),
+        env,
+        testing::mock_info("manager-sender", &[]),
+        ExecuteMsg::DeregisterVestingAccounts {
+            addresses: vec!["addr0001".to_string()],
+            force: None,
+        },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: simulated[0].master.clone(),
+            amount: vec![coin(
+                simulated[0].refund_to_master.u128(),
+                &simulated[0].denom
+            )],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn deregister_emits_batch_summary() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |user_address: &str, vesting_amount: u128| RewardUserRequest {
+        user_address: user_address.to_string(),
+        vesting_amount: Uint128::new(vesting_amount),
+        cliff_amount: Uint128::zero(),
+        payout_address: None,
+        label: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![reward("addr0001", 1000), reward("addr0002", 600)],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(150),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+
+    // addr0001 claimed 500 of 1000, and the unknown address is skipped.
+    let env = mock_env_with_time(150);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager-sender", &[]),
+        ExecuteMsg::DeregisterVestingAccounts {
+            addresses: vec![
+                "addr0001".to_string(),
+                "addr0002".to_string(),
+                "addr0003".to_string(),
+            ],
+            force: None,
+        },
+    )?;
+    let batch = res
+        .events
+        .iter()
+        .find(|event| event.ty == "vesting/deregister_batch")
+        .expect("missing vesting/deregister_batch event");
+    assert_eq!(
+        batch.attributes,
+        vec![
+            Attribute::new("count", "2"),
+            Attribute::new("total_refunded", "1100"),
+            Attribute::new("block_height", env.block.height.to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn deregister_before_cliff_requires_force() -> TestResult {
+    let mut deps = mock_dependencies();
+    let env = mock_env_with_time(102);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[coin(5000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: Some(true),
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(5000u128),
+                cliff_amount: Uint128::new(1250u128),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+
+    // Without force, the pre-cliff deregister is rejected.
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager-sender", &[]),
+        ExecuteMsg::DeregisterVestingAccounts {
+            addresses: vec!["addr0001".to_string()],
+            force: None,
+        },
+    )?;
+    let data: Vec<DeregisterUserResponse> = from_json(res.data.unwrap())?;
+    assert!(!data[0].success);
+    assert!(
+        data[0].error_msg.contains(
+            "cannot deregister addr0001 before the cliff time (105) without force"
+        ),
+        "unexpected error message {:?}",
+        data[0].error_msg
+    );
+    assert!(res.messages.is_empty());
+
+    // With force, the full grant is returned to the admin.
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("manager-sender", &[]),
+        ExecuteMsg::DeregisterVestingAccounts {
+            addresses: vec!["addr0001".to_string()],
+            force: Some(true),
+        },
+    )?;
+    let data: Vec<DeregisterUserResponse> = from_json(res.data.unwrap())?;
+    assert!(data[0].success);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "admin-sender".to_string(),
+            amount: vec![coin(5000, "token")],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn query_vesting_accounts() -> TestResult {
+    // Set up the environment with a block time before the vesting start time
+    let (mut deps, env) = setup_with_block_time(105)?;
+
+    let register_msg = ExecuteMsg::RewardUsers {
+        denom: None,
+        rewards: vec![RewardUserRequest {
+            user_address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(5000u128),
+            cliff_amount: Uint128::new(1250u128),
+            payout_address: None,
+            label: None,
+        }],
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            end_time: Uint64::new(110),
+            cliff_time: Uint64::new(105),
+        },
+    };
+
+    execute(
+        deps.as_mut(),
+        env.clone(), // Use the custom environment with the adjusted block time
+        testing::mock_info("admin-sender", &[]),
+        register_msg,
+    )?;
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::VestingAccounts {
+            address: vec!["addr0001".to_string()],
+        },
+    )?;
+
+    let response_items: Vec<VestingAccountResponse> = from_json(res).unwrap();
+    assert_eq!(
+        response_items[0],
+        VestingAccountResponse {
+            address: "addr0001".to_string(),
+            vestings: vec![VestingData {
+                master_address: Some("admin-sender".to_string()),
+                vesting_amount: Uint128::new(5000u128),
+                vesting_schedule:
+                    VestingScheduleQueryOutput::LinearVestingWithCliff {
+                        start_time: Uint64::new(100),
+                        end_time: Uint64::new(110),
+                        cliff_time: Uint64::new(105),
+                        vesting_amount: Uint128::new(5000u128),
+                        cliff_amount: Uint128::new(1250u128),
+                    },
+                vesting_denom: cw20::Denom::Native("token".to_string()),
+                vested_amount: Uint128::new(1250u128),
+                claimed_amount: Uint128::zero(),
+                claimable_amount: Uint128::new(1250u128),
+                cliff_claimable: Uint128::new(1250u128),
+                linear_claimable: Uint128::zero(),
+                label: None,
+            }],
+            decimals: None,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn query_decimals_round_trip() -> TestResult {
+    let mut deps = mock_dependencies();
+    let env = mock_env_with_time(100);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[coin(5000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: Some(6),
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+
+    let config: Config =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.decimals, Some(6));
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        env,
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.decimals, Some(6));
+    Ok(())
+}
+
+#[test]
+fn import_accounts() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(105)?;
+
+    let vesting_data = |vesting_amount: u128,
+                        cliff_amount: u128,
+                        vested_amount: u128,
+                        claimable_amount: u128|
+     -> VestingData {
+        VestingData {
+            master_address: Some("admin-sender".to_string()),
+            vesting_denom: cw20::Denom::Native("token".to_string()),
+            vesting_amount: Uint128::new(vesting_amount),
+            vesting_schedule:
+                VestingScheduleQueryOutput::LinearVestingWithCliff {
+                    start_time: Uint64::new(100),
+                    end_time: Uint64::new(110),
+                    cliff_time: Uint64::new(105),
+                    vesting_amount: Uint128::new(vesting_amount),
+                    cliff_amount: Uint128::new(cliff_amount),
+                },
+            vested_amount: Uint128::new(vested_amount),
+            claimed_amount: Uint128::new(vested_amount - claimable_amount),
+            claimable_amount: Uint128::new(claimable_amount),
+            // At the cliff, everything claimable was unlocked by the cliff.
+            cliff_claimable: Uint128::new(claimable_amount),
+            linear_claimable: Uint128::zero(),
+            label: None,
+        }
+    };
+
+    // only the admin can import
+    let msg = ExecuteMsg::ImportAccounts {
+        accounts: vec![
+            ("addr0001".to_string(), vesting_data(1000, 500, 500, 500)),
+            // 200 of the 500 vested tokens were already claimed
+            ("addr0002".to_string(), vesting_data(2000, 500, 500, 300)),
+        ],
+    };
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("manager-sender", &[]),
+        msg.clone(),
+        StdError::generic_err("Unauthorized").into(),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        msg.clone(),
+    )?;
+    assert_eq!(
+        res.attributes.last(),
+        Some(&Attribute::new("unallocated_amount", "2200"))
+    );
+
+    let res: Vec<VestingAccountResponse> = from_json(query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VestingAccounts {
+            address: vec!["addr0001".to_string(), "addr0002".to_string()],
+        },
+    )?)?;
+    assert_eq!(res[0].vestings, vec![vesting_data(1000, 500, 500, 500)]);
+    assert_eq!(res[1].vestings, vec![vesting_data(2000, 500, 500, 300)]);
+
+    // importing existing accounts fails
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        msg,
+        StdError::generic_err("User addr0001 already has a vesting account")
+            .into(),
+    );
+    Ok(())
+}
+
+#[test]
+fn query_claimable_breakdown_at_cliff() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(5000u128),
+                cliff_amount: Uint128::new(1250u128),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+
+    let breakdown =
+        |deps: Deps, block_time: u64| -> anyhow::Result<(u128, u128, u128)> {
+            let res: VestingAccountResponse = from_json(query(
+                deps,
+                mock_env_with_time(block_time),
+                QueryMsg::VestingAccount {
+                    address: "addr0001".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )?)?;
+            let data = &res.vestings[0];
+            Ok((
+                data.claimable_amount.u128(),
+                data.cliff_claimable.u128(),
+                data.linear_claimable.u128(),
+            ))
+        };
+
+    // before the cliff, nothing is claimable
+    assert_eq!(breakdown(deps.as_ref(), 104)?, (0, 0, 0));
+    // at the cliff, the whole claimable amount comes from the cliff
+    assert_eq!(breakdown(deps.as_ref(), 105)?, (1250, 1250, 0));
+    // after the cliff, linear accrual is reported separately
+    assert_eq!(breakdown(deps.as_ref(), 106)?, (2000, 1250, 750));
+
+    // claims are counted against the cliff portion first
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(105),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(breakdown(deps.as_ref(), 106)?, (750, 0, 750));
+    Ok(())
+}
+
+#[test]
+fn query_aggregate_for() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![
+                RewardUserRequest {
+                    user_address: "addr0001".to_string(),
+                    vesting_amount: Uint128::new(1000u128),
+                    cliff_amount: Uint128::new(100u128),
+                    payout_address: None,
+                    label: None,
+                },
+                RewardUserRequest {
+                    user_address: "addr0002".to_string(),
+                    vesting_amount: Uint128::new(3000u128),
+                    cliff_amount: Uint128::new(500u128),
+                    payout_address: None,
+                    label: None,
+                },
+            ],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+    // addr0001 claims its cliff
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(105),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+
+    // At 106: addr0001 vested 100 + 900/5 = 280, claimed 100.
+    //         addr0002 vested 500 + 2500/5 = 1000, claimed 0.
+    let res: AggregateResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(106),
+        QueryMsg::AggregateFor {
+            addresses: vec![
+                "addr0001".to_string(),
+                "addr0002".to_string(),
+                "addr0001".to_string(),
+                "nonexistent".to_string(),
+            ],
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        AggregateResponse {
+            total_vesting: Uint128::new(4000u128),
+            total_vested: Uint128::new(1280u128),
+            total_claimable: Uint128::new(1180u128),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn unallocated_changed_events() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let unallocated_event = |old: u128, new: u128| {
+        Event::new("unallocated_changed")
+            .add_attribute("old_unallocated_amount", old.to_string())
+            .add_attribute("new_unallocated_amount", new.to_string())
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000u128),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+    assert_eq!(res.events, vec![unallocated_event(5000, 4000)]);
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::Withdraw {
+            denom: None,
+            amount: Uint128::new(1500u128),
+        },
+    )?;
+    assert_eq!(res.events, vec![unallocated_event(4000, 2500)]);
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::DeregisterVestingAccounts {
+            addresses: vec!["addr0001".to_string()],
+            force: None,
+        },
+    )?;
+    assert_eq!(
+        res.events,
+        vec![
+            unallocated_event(2500, 2500),
+            Event::new("vesting/deregister_batch")
+                .add_attribute("count", "1")
+                .add_attribute("total_refunded", "1000")
+                .add_attribute("block_height", env.block.height.to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reward_users_result_flags_duplicate() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |user_address: &str, vesting_amount: u128| RewardUserRequest {
+        user_address: user_address.to_string(),
+        vesting_amount: Uint128::new(vesting_amount),
+        cliff_amount: Uint128::zero(),
+        payout_address: None,
+        label: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![
+                reward("addr0001", 1000),
+                reward("addr0002", 500),
+                reward("addr0001", 2000),
+            ],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(105),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+
+    let results: Vec<RewardUserResponse> = from_json(res.data.unwrap())?;
+    assert_eq!(
+        results,
+        vec![
+            RewardUserResponse {
+                user_address: "addr0001".to_string(),
+                success: true,
+                error_msg: "".to_string(),
+            },
+            RewardUserResponse {
+                user_address: "addr0002".to_string(),
+                success: true,
+                error_msg: "".to_string(),
+            },
+            RewardUserResponse {
+                user_address: "addr0001".to_string(),
+                success: false,
+                error_msg: "Failed to register vesting account: Generic error: User addr0001 already has a vesting account".to_string(),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reward_users_skipped_rewards_stay_unallocated() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward_users = |rewards: Vec<(&str, u128)>| ExecuteMsg::RewardUsers {
+        denom: None,
+        rewards: rewards
+            .into_iter()
+            .map(|(user_address, vesting_amount)| RewardUserRequest {
+                user_address: user_address.to_string(),
+                vesting_amount: Uint128::new(vesting_amount),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            })
+            .collect(),
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            cliff_time: Uint64::new(105),
+            end_time: Uint64::new(110),
+        },
+    };
+
+    // The duplicate reward of addr0001 is skipped and only 1500 is allocated.
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward_users(vec![
+            ("addr0001", 1000),
+            ("addr0002", 500),
+            ("addr0001", 2000),
+        ]),
+    )?;
+    assert_eq!(
+        res.events[0],
+        Event::new("unallocated_changed")
+            .add_attribute("old_unallocated_amount", "5000")
+            .add_attribute("new_unallocated_amount", "3500")
+    );
+
+    // The skipped amount can be rewarded again.
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        reward_users(vec![("addr0003", 3500)]),
+    )?;
+    assert_eq!(
+        res.events[0],
+        Event::new("unallocated_changed")
+            .add_attribute("old_unallocated_amount", "3500")
+            .add_attribute("new_unallocated_amount", "0")
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_denom_rewards_tracked_independently() -> TestResult {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(5000, "unibi"),
+        coin(1000, "ustable"),
+    ]);
+    let env = mock_env_with_time(100);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(
+            "admin-sender",
+            &[coin(5000, "unibi"), coin(1000, "ustable")],
+        ),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: Some(true),
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+
+    let reward_msg =
+        |amount: u128, denom: Option<&str>| ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(amount),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+            denom: denom.map(String::from),
+        };
+
+    // the denom is required and must be funded
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        reward_msg(100, None),
+        StdError::generic_err("denom is required for a multi_denom contract")
+            .into(),
+    );
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        reward_msg(100, Some("uatom")),
+        StdError::generic_err("Denom uatom is not funded").into(),
+    );
+    // ustable only has 1000 unallocated, regardless of the unibi balance
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        reward_msg(2000, Some("ustable")),
+        StdError::generic_err(
+            "Insufficient funds for all rewards. Contract has 1000 available but trying to allocate 2000",
+        )
+        .into(),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward_msg(2000, Some("unibi")),
+    )?;
+    assert_eq!(
+        res.events[0],
+        Event::new("unallocated_changed")
+            .add_attribute("old_unallocated_amount", "5000")
+            .add_attribute("new_unallocated_amount", "3000")
+            .add_attribute("denom", "unibi")
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward_msg(1000, Some("ustable")),
+    )?;
+
+    // withdrawing from one denom leaves the other untouched
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::Withdraw {
+            amount: Uint128::new(500),
+            denom: Some("ustable".to_string()),
+        },
+        StdError::generic_err("Nothing to withdraw").into(),
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::Withdraw {
+            amount: Uint128::new(500),
+            denom: Some("unibi".to_string()),
+        },
+    )?;
+    assert_eq!(
+        res.attributes.last(),
+        Some(&Attribute::new("unallocated_amount", "2500"))
+    );
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        env,
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    let denoms: Vec<cw20::Denom> = res
+        .vestings
+        .into_iter()
+        .map(|vesting| vesting.vesting_denom)
+        .collect();
+    assert_eq!(
+        denoms,
+        vec![
+            cw20::Denom::Native("unibi".to_string()),
+            cw20::Denom::Native("ustable".to_string()),
+        ]
+    );
+
+    // a claim pays out each denom separately
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(110),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(2000, "unibi")],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(1000, "ustable")],
+            }),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_denom_claim_skips_denoms_with_nothing_vested() -> TestResult {
+    let mut deps = mock_dependencies();
+    let env = mock_env_with_time(100);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(
+            "admin-sender",
+            &[coin(5000, "unibi"), coin(1000, "ustable")],
+        ),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: Some(true),
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+    for (amount, denom, start_time) in
+        [(2000, "unibi", 100), (1000, "ustable", 200)]
+    {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            ExecuteMsg::RewardUsers {
+                rewards: vec![RewardUserRequest {
+                    user_address: "addr0001".to_string(),
+                    vesting_amount: Uint128::new(amount),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                }],
+                vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                    start_time: Uint64::new(start_time),
+                    cliff_time: Uint64::new(start_time),
+                    end_time: Uint64::new(start_time + 10),
+                },
+                denom: Some(denom.to_string()),
+            },
+        )?;
+    }
+
+    // only the unibi schedule has vested, so ustable gets no empty send
+    let claim = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                 block_time: u64|
+     -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+    };
+    let res = claim(&mut deps, 110)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(2000, "unibi")],
+        })]
+    );
+
+    let res = claim(&mut deps, 205)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(500, "ustable")],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn query_claimed_at_height() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+            denom: None,
+        },
+    )?;
+
+    let env_at = |block_time: u64, height: u64| {
+        let mut env = mock_env_with_time(block_time);
+        env.block.height = height;
+        env
+    };
+    for (block_time, height) in [(102, 500), (105, 600)] {
+        execute(
+            deps.as_mut(),
+            env_at(block_time, height),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )?;
+    }
+
+    let claimed_at = |deps: Deps, height: u64| -> anyhow::Result<u128> {
+        let res: ClaimedAtHeightResponse = from_json(query(
+            deps,
+            env_at(105, 700),
+            QueryMsg::ClaimedAtHeight {
+                address: "addr0001".to_string(),
+                height,
+            },
+        )?)?;
+        Ok(res.claimed_amount.u128())
+    };
+    // snapshots hold the value at the beginning of each height
+    assert_eq!(claimed_at(deps.as_ref(), 500)?, 0);
+    assert_eq!(claimed_at(deps.as_ref(), 501)?, 200);
+    assert_eq!(claimed_at(deps.as_ref(), 600)?, 200);
+    assert_eq!(claimed_at(deps.as_ref(), 601)?, 500);
+    Ok(())
+}
+
+#[test]
+fn clawback_unvested_amount() -> TestResult {
+    let (mut deps, _env) = setup_with_block_time(100)?;
+    let reward = |schedule: VestingSchedule, cliff_amount: u128| {
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(4000),
+                cliff_amount: Uint128::new(cliff_amount),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: schedule,
+        }
+    };
+    let clawback = |reduce_by: u128| ExecuteMsg::Clawback {
+        user_address: "addr0001".to_string(),
+        reduce_by: Uint128::new(reduce_by),
+        schedule_index: None,
+    };
+    let vesting = |deps: Deps, block_time: u64| {
+        let res: VestingAccountResponse = from_json(query(
+            deps,
+            mock_env_with_time(block_time),
+            QueryMsg::VestingAccount {
+                address: "addr0001".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )?)?;
+        anyhow::Ok(res.vestings[0].clone())
+    };
+
+    // 1000 at the cliff, then 10 per second until 500
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(100),
+        mock_info("admin-sender", &[]),
+        reward(
+            VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(200),
+                end_time: Uint64::new(500),
+            },
+            1000,
+        ),
+    )?;
+
+    // only the admin can claw back
+    let err = execute(
+        deps.as_mut(),
+        mock_env_with_time(300),
+        mock_info("manager-sender", &[]),
+        clawback(1500),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Unauthorized").into());
+
+    // 2000 is vested at 300, so 1500 of the other 2000 can be clawed back
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(300),
+        mock_info("admin-sender", &[]),
+        clawback(1500),
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "admin-sender".to_string(),
+            amount: vec![coin(1500, "token")],
+        })]
+    );
+    // the rate is kept and the schedule ends earlier
+    let data = vesting(deps.as_ref(), 300)?;
+    assert_eq!(
+        data.vesting_schedule,
+        VestingScheduleQueryOutput::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            end_time: Uint64::new(350),
+            cliff_time: Uint64::new(200),
+            vesting_amount: Uint128::new(2500),
+            cliff_amount: Uint128::new(1000),
+        }
+    );
+    assert_eq!(data.vested_amount, Uint128::new(2000));
+    assert_eq!(
+        vesting(deps.as_ref(), 350)?.vested_amount,
+        Uint128::new(2500)
+    );
+
+    // vested tokens are protected
+    let err = execute(
+        deps.as_mut(),
+        mock_env_with_time(300),
+        mock_info("admin-sender", &[]),
+        clawback(600),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        VestingError::ExcessiveClawback {
+            reduce_by: 600,
+            unvested_amount: 500,
+        }
+        .into()
+    );
+
+    // stepwise schedules lose their last unvested steps
+    let (mut deps, _env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(100),
+        mock_info("admin-sender", &[]),
+        reward(
+            VestingSchedule::Stepwise {
+                steps: vec![
+                    (Uint64::new(200), Uint128::new(1000)),
+                    (Uint64::new(300), Uint128::new(1000)),
+                    (Uint64::new(400), Uint128::new(2000)),
+                ],
+            },
+            0,
+        ),
+    )?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(250),
+        mock_info("admin-sender", &[]),
+        clawback(2500),
+    )?;
+    assert_eq!(
+        vesting(deps.as_ref(), 250)?.vesting_schedule,
+        VestingScheduleQueryOutput::Stepwise {
+            steps: vec![
+                (Uint64::new(200), Uint128::new(1000)),
+                (Uint64::new(300), Uint128::new(500)),
+            ],
+            vesting_amount: Uint128::new(1500),
+        }
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env_with_time(250),
+        mock_info("admin-sender", &[]),
+        clawback(501),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        VestingError::ExcessiveClawback {
+            reduce_by: 501,
+            unvested_amount: 500,
+        }
+        .into()
+    );
+    Ok(())
+}
+
+#[test]
+fn clawback_removes_fully_claimed_schedule() -> TestResult {
+    let (mut deps, _env) = setup_with_block_time(100)?;
+    let reward = |vesting_amount: u128, schedule: VestingSchedule| {
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(vesting_amount),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: schedule,
+        }
+    };
+    // schedule 0 vests 1 per second, schedule 1 only unlocks at 1000
+    for msg in [
+        reward(
+            100,
+            VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+        ),
+        reward(
+            500,
+            VestingSchedule::Stepwise {
+                steps: vec![(Uint64::new(1000), Uint128::new(500))],
+            },
+        ),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env_with_time(100),
+            mock_info("admin-sender", &[]),
+            msg,
+        )?;
+    }
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(140),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+
+    // 40 vested and claimed, so clawing back the other 60 of schedule 0
+    // leaves nothing to claim from it
+    let clawback = ExecuteMsg::Clawback {
+        user_address: "addr0001".to_string(),
+        reduce_by: Uint128::new(60),
+        schedule_index: Some(0),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(140),
+        mock_info("admin-sender", &[]),
+        clawback.clone(),
+    )?;
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(140),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings.len(), 1);
+    assert_eq!(res.vestings[0].vesting_amount, Uint128::new(500));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_with_time(140),
+        mock_info("admin-sender", &[]),
+        clawback,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "User addr0001 does not have a vesting schedule 0."
+        )
+        .into()
+    );
+    Ok(())
+}
+
+#[test]
+fn claim_for_by_relayer() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(4000),
+                cliff_amount: Uint128::new(1000),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(200),
+                end_time: Uint64::new(300),
+            },
+        },
+    )?;
+    let claim_for = ExecuteMsg::ClaimFor {
+        user_address: "addr0001".to_string(),
+    };
+
+    // Only the admin manages relayers
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager-sender", &[]),
+        ExecuteMsg::AddRelayer {
+            address: "relayer".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Unauthorized").into());
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::AddRelayer {
+            address: "relayer".to_string(),
+        },
+    )?;
+
+    // Unregistered callers are rejected, even the user
+    for caller in ["addr0001", "admin-sender"] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env_with_time(250),
+            mock_info(caller, &[]),
+            claim_for.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(format!(
+                "Sender {caller} is not a registered relayer."
+            ))
+            .into()
+        );
+    }
+
+    // The relayer claims, and the funds go to the user
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(250),
+        mock_info("relayer", &[]),
+        claim_for.clone(),
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(2500, "token")],
+        })]
+    );
+    assert!(res
+        .attributes
+        .contains(&Attribute::new("relayer", "relayer")));
+
+    // Removed relayers can no longer claim
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(250),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RemoveRelayer {
+            address: "relayer".to_string(),
+        },
+    )?;
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(300),
+        mock_info("relayer", &[]),
+        claim_for,
+    );
+    assert!(res.is_err());
+    Ok(())
+}
+
+#[test]
+fn query_next_unlock() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    // 1000 at the cliff, then 3000 over 7 seconds
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(4000),
+                cliff_amount: Uint128::new(1000),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(200),
+                end_time: Uint64::new(207),
+            },
+        },
+    )?;
+    let next_unlock = |deps: Deps, block_time: u64| {
+        let res: NextUnlockResponse = from_json(query(
+            deps,
+            mock_env_with_time(block_time),
+            QueryMsg::NextUnlock {
+                address: "addr0001".to_string(),
+            },
+        )?)?;
+        anyhow::Ok((res.seconds_until, res.amount_at_unlock.u128()))
+    };
+
+    // Before the cliff: time to the cliff and the cliff amount
+    assert_eq!(next_unlock(deps.as_ref(), 150)?, (Some(50), 1000));
+    // Mid-linear: 3000 / 7 = 428.57 per second, so the vested amount
+    // steps 1428 -> 1857 -> ... and reaches 4000 at the end
+    assert_eq!(next_unlock(deps.as_ref(), 200)?, (Some(1), 428));
+    assert_eq!(next_unlock(deps.as_ref(), 201)?, (Some(1), 429));
+    assert_eq!(next_unlock(deps.as_ref(), 206)?, (Some(1), 429));
+    assert_eq!(next_unlock(deps.as_ref(), 207)?, (None, 0));
+
+    // A slow schedule accrues one base unit every several seconds
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            denom: None,
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(3),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+        },
+    )?;
+    // whole units accrue at 104 (3 * 4 / 10 = 1.2), 107 and 110
+    assert_eq!(next_unlock(deps.as_ref(), 100)?, (Some(4), 1));
+    assert_eq!(next_unlock(deps.as_ref(), 104)?, (Some(3), 1));
+    assert_eq!(next_unlock(deps.as_ref(), 108)?, (Some(2), 1));
+    Ok(())
+}
+
+#[test]
+fn reclaim_surplus_sends_only_donations() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let contract_addr = env.contract.address.to_string();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+            denom: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(104),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+
+    // 4000 unallocated and 600 unclaimed are reserved. On top of that, 300
+    // token and 50 ustray were donated.
+    deps.querier.update_balance(
+        &contract_addr,
+        vec![coin(4900, "token"), coin(50, "ustray")],
+    );
+    let reclaim = ExecuteMsg::ReclaimSurplus {
+        to: Some("treasury".to_string()),
+    };
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("manager-sender", &[]),
+        reclaim.clone(),
+        StdError::generic_err("Unauthorized").into(),
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reclaim.clone(),
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![coin(300, "token"), coin(50, "ustray")],
+        })]
+    );
+
+    // Once the surplus is gone, reserved amounts stay put.
+    deps.querier
+        .update_balance(&contract_addr, vec![coin(4600, "token")]);
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        reclaim,
+        StdError::generic_err("Nothing to reclaim").into(),
+    );
+    Ok(())
+}
+
+#[test]
+fn withdraw_never_touches_reserved_vesting() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(4000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+
+    // 1000 is unallocated, but the balance drifted so that only 500 of it
+    // is not owed to addr0001.
+    deps.querier.update_balance(
+        env.contract.address.as_str(),
+        vec![coin(4500, "token")],
+    );
+    let withdraw = |amount: u128| ExecuteMsg::Withdraw {
+        amount: Uint128::new(amount),
+        denom: None,
+    };
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        withdraw(1000),
+        StdError::generic_err(
+            "Cannot withdraw 1000token, only 500 is not reserved for vesting accounts",
+        )
+        .into(),
+    );
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        withdraw(500),
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "admin-sender".to_string(),
+            amount: vec![coin(500, "token")],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn claim_emits_receipt_event() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+            denom: None,
+        },
+    )?;
+    let receipt = |amount: &str, cumulative_claimed: &str| {
+        Event::new("vesting/claim_receipt")
+            .add_attribute("user", "addr0001")
+            .add_attribute("amount", amount)
+            .add_attribute("denom", "token")
+            .add_attribute("cumulative_claimed", cumulative_claimed)
+            .add_attribute("schedule_index", "0")
+    };
+
+    for (block_time, want_receipt) in
+        [(104, receipt("400", "400")), (110, receipt("600", "1000"))]
+    {
+        let res = execute(
+            deps.as_mut(),
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )?;
+        assert_eq!(res.events, vec![want_receipt]);
+    }
+    Ok(())
+}
+
+#[test]
+fn vesting_data_reports_claimed_amount() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(110),
+            },
+            denom: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(104),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(106),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    let data = &res.vestings[0];
+    assert_eq!(data.vested_amount, Uint128::new(600));
+    assert_eq!(data.claimed_amount, Uint128::new(400));
+    assert_eq!(data.claimable_amount, Uint128::new(200));
+    Ok(())
+}
+
+#[test]
+fn milestone_vesting_unlocks_confirmed_tranches() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |tranches: Vec<u128>| ExecuteMsg::RewardUsers {
+        rewards: vec![RewardUserRequest {
+            user_address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(1000),
+            cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
+        }],
+        vesting_schedule: VestingSchedule::Milestone {
+            tranches: tranches.into_iter().map(Uint128::new).collect(),
+        },
+        denom: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(vec![300, 600]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        VestingError::MismatchedTranchesAmount {
+            tranches_amount: 900,
+            vesting_amount: 1000,
+        }
+        .into()
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(vec![u128::MAX, 1]),
+    )
+    .unwrap_err();
+    assert_eq!(err, VestingError::TranchesAmountOverflow.into());
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(vec![300, 700]),
+    )?;
+
+    let confirm = ExecuteMsg::ConfirmMilestone {
+        user_address: "addr0001".to_string(),
+        schedule_index: 0,
+    };
+    let vested_and_claimable = |deps: Deps| -> anyhow::Result<(u128, u128)> {
+        let res: VestingAccountResponse = from_json(query(
+            deps,
+            mock_env_with_time(1_000_000),
+            QueryMsg::VestingAccount {
+                address: "addr0001".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )?)?;
+        let data = &res.vestings[0];
+        Ok((data.vested_amount.u128(), data.claimable_amount.u128()))
+    };
+    let claim = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env_with_time(1_000_000),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+    };
+
+    // Nothing vests with time alone
+    assert_eq!(vested_and_claimable(deps.as_ref())?, (0, 0));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        confirm.clone(),
+    );
+    assert!(res.is_err());
+
+    let confirm_milestone = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("admin-sender", &[]),
+            confirm.clone(),
+        )
+    };
+    let claimed = |res: Response, amount: u128| {
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(amount, "token")],
+            })]
+        );
+    };
+
+    confirm_milestone(deps.as_mut())?;
+    assert_eq!(vested_and_claimable(deps.as_ref())?, (300, 300));
+    claimed(claim(deps.as_mut())?, 300);
+    assert_eq!(vested_and_claimable(deps.as_ref())?, (300, 0));
+
+    confirm_milestone(deps.as_mut())?;
+    assert_eq!(vested_and_claimable(deps.as_ref())?, (1000, 700));
+    let err = confirm_milestone(deps.as_mut()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("All milestones of vesting schedule 0 of addr0001"));
+    claimed(claim(deps.as_mut())?, 700);
+    Ok(())
+}
+
+#[test]
+fn claims_route_to_payout_address() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    for (start_time, payout_address) in [(100, Some("custody")), (101, None)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            ExecuteMsg::RewardUsers {
+                rewards: vec![RewardUserRequest {
+                    user_address: "addr0001".to_string(),
+                    vesting_amount: Uint128::new(1000),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: payout_address.map(String::from),
+                    label: None,
+                }],
+                vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                    start_time: Uint64::new(start_time),
+                    cliff_time: Uint64::new(start_time),
+                    end_time: Uint64::new(110),
+                },
+                denom: None,
+            },
+        )?;
+    }
+
+    // Only the grantee can trigger the claim
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(110),
+        mock_info("custody", &[]),
+        ExecuteMsg::Claim { limit: None },
+    );
+    assert!(res.is_err());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(110),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(1000, "token")],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "custody".to_string(),
+                amount: vec![coin(1000, "token")],
+            }),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reward_users_rejects_wrong_denom() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |denom: Option<&str>| ExecuteMsg::RewardUsers {
+        rewards: vec![RewardUserRequest {
+            user_address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(1000),
+            cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
+        }],
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            cliff_time: Uint64::new(100),
+            end_time: Uint64::new(110),
+        },
+        denom: denom.map(String::from),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(Some("uatom")),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Denom uatom is not funded, expected token")
+            .into()
+    );
+
+    // The funding denom may be given explicitly
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        reward(Some("token")),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn query_expiring_soon() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let linear = |end_time: u64| VestingSchedule::LinearVestingWithCliff {
+        start_time: Uint64::new(100),
+        cliff_time: Uint64::new(100),
+        end_time: Uint64::new(end_time),
+    };
+    for (user, schedule) in [
+        ("addr0001", linear(150)),
+        ("addr0002", linear(300)),
+        (
+            "addr0003",
+            VestingSchedule::Stepwise {
+                steps: vec![
+                    (Uint64::new(120), Uint128::new(50)),
+                    (Uint64::new(180), Uint128::new(50)),
+                ],
+            },
+        ),
+        ("addr0004", linear(200)),
+    ] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            ExecuteMsg::RewardUsers {
+                rewards: vec![RewardUserRequest {
+                    user_address: user.to_string(),
+                    vesting_amount: Uint128::new(100),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                }],
+                vesting_schedule: schedule,
+                denom: None,
+            },
+        )?;
+    }
+    let expiring = |start_after: Option<(String, u64)>, limit: Option<u32>| {
+        let res: Vec<ExpiringScheduleResponse> = from_json(query(
+            deps.as_ref(),
+            mock_env_with_time(100),
+            QueryMsg::ExpiringSoon {
+                within_seconds: 100,
+                start_after,
+                limit,
+            },
+        )?)?;
+        anyhow::Ok(res)
+    };
+    let expiring_schedule =
+        |user: &str, end_time: u64| ExpiringScheduleResponse {
+            user_address: user.to_string(),
+            schedule_index: 0,
+            denom: "token".to_string(),
+            end_time,
+            vesting_amount: Uint128::new(100),
+            claimed_amount: Uint128::zero(),
+        };
+
+    // addr0002 only ends at 300, after the window ending at 200
+    assert_eq!(
+        expiring(None, None)?,
+        vec![
+            expiring_schedule("addr0001", 150),
+            expiring_schedule("addr0003", 180),
+            expiring_schedule("addr0004", 200),
+        ]
+    );
+
+    let page = expiring(None, Some(2))?;
+    assert_eq!(page.len(), 2);
+    let last = page.last().unwrap();
+    let start_after = Some((last.user_address.clone(), last.schedule_index));
+    assert_eq!(
+        expiring(start_after, Some(2))?,
+        vec![expiring_schedule("addr0004", 200)]
+    );
+    Ok(())
+}
+
+#[test]
+fn query_params() -> TestResult {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin-sender", &[coin(1000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-b".to_string(), "manager-a".to_string()],
+            decimals: Some(6),
+            deregister_requires_force_before_cliff: Some(true),
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+    let params = |deps: Deps| -> StdResult<ParamsResponse> {
+        from_json(query(deps, mock_env(), QueryMsg::Params {})?)
+    };
+    let mut want = ParamsResponse {
+        admin: "admin-sender".to_string(),
+        managers: vec!["manager-a".to_string(), "manager-b".to_string()],
+        relayers: vec![],
+        funding_denoms: vec!["token".to_string()],
+        multi_denom: false,
+        decimals: Some(6),
+        deregister_requires_force_before_cliff: true,
+        claim_cooldown_blocks: None,
+        rounding: VestingRounding::Floor,
+        default_page_limit: 10,
+        max_page_limit: 30,
+    };
+    assert_eq!(params(deps.as_ref())?, want);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::AddRelayer {
+            address: "relayer".to_string(),
+        },
+    )?;
+    want.relayers = vec!["relayer".to_string()];
+    assert_eq!(params(deps.as_ref())?, want);
+    Ok(())
+}
+
+#[test]
+fn migrate_moves_single_schedule_accounts() -> TestResult {
+    let mut deps = mock_dependencies_with_balance(&[coin(5000, "token")]);
+    let env = mock_env_with_time(150);
+    WHITELIST.save(
+        deps.as_mut().storage,
+        &Whitelist {
+            members: HashSet::from(["admin-sender".to_string()]),
+            admin: "admin-sender".to_string(),
+        },
+    )?;
+    DENOM.save(deps.as_mut().storage, &"token".to_string())?;
+    UNALLOCATED_AMOUNT.save(deps.as_mut().storage, &Uint128::new(1000))?;
+    // Accounts as written by the single-schedule layout, without a config or
+    // a contract version.
+    deps.storage.set(
+        &LEGACY_VESTING_ACCOUNTS.key("addr0001"),
+        br#"{"address":"addr0001","vesting_amount":"4000","cliff_amount":"0","vesting_schedule":{"linear_vesting_with_cliff":{"start_time":"100","end_time":"200","cliff_time":"100"}},"claimed_amount":"1000","denom":null}"#,
+    );
+    deps.storage.set(
+        &LEGACY_VESTING_ACCOUNTS.key("addr0002"),
+        br#"{"address":"addr0002","vesting_amount":"500","cliff_amount":"0","vesting_schedule":{"linear_vesting_with_cliff":{"start_time":"100","end_time":"200","cliff_time":"100"}},"claimed_amount":"500","denom":null}"#,
+    );
+
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {})?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            Attribute::new("action", "migrate"),
+            Attribute::new("from_version", "unknown"),
+            Attribute::new("to_version", CONTRACT_VERSION),
+            Attribute::new("migrated_accounts", "2"),
+        ]
+    );
+    assert!(LEGACY_VESTING_ACCOUNTS
+        .range(&deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none());
+    assert_eq!(
+        cw2::get_contract_version(&deps.storage)?.version,
+        CONTRACT_VERSION
+    );
+
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings.len(), 1);
+    assert_eq!(res.vestings[0].vested_amount, Uint128::new(2000));
+    assert_eq!(res.vestings[0].claimable_amount, Uint128::new(1000));
+    // The fully claimed account is dropped.
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VestingAccount {
+            address: "addr0002".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert!(res.vestings.is_empty());
+    let params: ParamsResponse =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Params {})?)?;
+    assert!(!params.multi_denom);
+
+    // Migrating again is a no-op, and a different contract is rejected.
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {})?;
+    assert_eq!(
+        res.attributes[1],
+        Attribute::new("from_version", CONTRACT_VERSION)
+    );
+    cw2::set_contract_version(
+        deps.as_mut().storage,
+        "crates.io:other",
+        "1.0.0",
+    )?;
+    assert!(migrate(deps.as_mut(), env, MigrateMsg {}).is_err());
+    Ok(())
+}
+
+#[test]
+fn vested_amount_at_cliff_boundary() -> TestResult {
+    let account = |cliff_time: u64, end_time: u64| VestingAccount {
+        address: "addr0001".to_string(),
+        vesting_amount: Uint128::new(1100),
+        cliff_amount: Uint128::new(100),
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            cliff_time: Uint64::new(cliff_time),
+            end_time: Uint64::new(end_time),
+        },
+        claimed_amount: Uint128::zero(),
+        denom: None,
+        milestones_confirmed: 0,
+        payout_address: None,
+        label: None,
+        rounding: VestingRounding::Floor,
+    };
+    let at = |seconds: u64| Timestamp::from_seconds(seconds);
+
+    // Cliff at 200, linear accrual of 1000 over the 1000 seconds after it.
+    let linear = account(200, 1200);
+    for (block_time, vested, breakdown) in
+        [(199, 0, (0, 0)), (200, 100, (100, 0)), (201, 101, (100, 1))]
+    {
+        assert_eq!(
+            linear.vested_amount(at(block_time))?,
+            Uint128::new(vested),
+            "block_time {block_time}"
+        );
+        assert_eq!(
+            linear.claimable_breakdown(at(block_time))?,
+            (Uint128::new(breakdown.0), Uint128::new(breakdown.1)),
+            "block_time {block_time}"
+        );
+    }
+
+    // With the cliff at the end time, everything vests at the cliff.
+    let cliff_only = account(200, 200);
+    assert_eq!(cliff_only.vested_amount(at(199))?, Uint128::zero());
+    assert_eq!(cliff_only.vested_amount(at(200))?, Uint128::new(1100));
+    assert_eq!(cliff_only.vested_amount(at(201))?, Uint128::new(1100));
+    Ok(())
+}
+
+#[test]
+fn final_claim_at_end_time_sweeps_rounding_dust() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    // 1000 over 3 seconds accrues 333.33 per second.
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(103),
+            },
+            denom: None,
+        },
+    )?;
+    let claim = |deps: DepsMut, block_time: u64| -> anyhow::Result<String> {
+        let res = execute(
+            deps,
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )?;
+        let attr = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "claim_amount")
+            .expect("missing claim_amount attribute");
+        Ok(attr.value.clone())
+    };
+
+    assert_eq!(claim(deps.as_mut(), 101)?, "333");
+    assert_eq!(claim(deps.as_mut(), 102)?, "333");
+    // Interpolation alone would leave 1 unit after 3 * 333, but the claim at
+    // the end time releases the full remainder.
+    assert_eq!(claim(deps.as_mut(), 103)?, "334");
+    assert!(claim(deps.as_mut(), 104).is_err());
+    Ok(())
+}
+
+#[test]
+fn linear_remainder_accrues_from_cliff() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    // 1000 unlocks at the cliff and the remaining 2000 accrues linearly from
+    // the cliff at 200 until the end at 1200.
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(3000),
+                cliff_amount: Uint128::new(1000),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(200),
+                end_time: Uint64::new(1200),
+            },
+            denom: None,
+        },
+    )?;
+    let claim_at = |deps: DepsMut, block_time: u64| -> anyhow::Result<u128> {
+        let res = execute(
+            deps,
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )?;
+        Ok(match &res.messages[..] {
+            [] => 0,
+            [msg] => match &msg.msg {
+                cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                    amount, ..
+                }) => amount[0].amount.u128(),
+                msg => anyhow::bail!("unexpected message {msg:?}"),
+            },
+            msgs => anyhow::bail!("unexpected messages {msgs:?}"),
+        })
+    };
+
+    // Nothing accrues between the start and the cliff.
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(150),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings[0].vested_amount, Uint128::zero());
+
+    // Exactly the cliff amount at the cliff.
+    assert_eq!(claim_at(deps.as_mut(), 200)?, 1000);
+    // Halfway from the cliff to the end: the cliff amount plus half of the
+    // remainder, of which the cliff amount was already claimed.
+    assert_eq!(claim_at(deps.as_mut(), 700)?, 1000);
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(700),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings[0].vested_amount, Uint128::new(1000 + 2000 / 2));
+    assert_eq!(res.vestings[0].claimed_amount, Uint128::new(2000));
+    assert_eq!(claim_at(deps.as_mut(), 1200)?, 1000);
+    Ok(())
+}
+
+#[test]
+fn claim_cooldown_blocks_repeated_claims() -> TestResult {
+    let mut deps = mock_dependencies_with_balance(&[coin(1000, "token")]);
+    let at = |height: u64| {
+        let mut env = mock_env_with_time(height + 10);
+        env.block.height = height;
+        env
+    };
+    instantiate(
+        deps.as_mut(),
+        at(100),
+        mock_info("admin-sender", &[coin(1000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: Some(10),
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        at(100),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        at(100),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::AddRelayer {
+            address: "relayer".to_string(),
+        },
+    )?;
+
+    let claim = |deps: DepsMut, height: u64| {
+        execute(
+            deps,
+            at(height),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+    };
+    claim(deps.as_mut(), 100)?;
+    assert_eq!(
+        claim(deps.as_mut(), 109).unwrap_err(),
+        ContractError::ClaimCooldown { available_at: 110 }
+    );
+    // Relayers are held to the same cooldown.
+    let res = execute(
+        deps.as_mut(),
+        at(105),
+        mock_info("relayer", &[]),
+        ExecuteMsg::ClaimFor {
+            user_address: "addr0001".to_string(),
+        },
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::ClaimCooldown { available_at: 110 }
+    );
+    claim(deps.as_mut(), 110)?;
+    assert_eq!(
+        claim(deps.as_mut(), 119).unwrap_err(),
+        ContractError::ClaimCooldown { available_at: 120 }
+    );
+
+    let params: ParamsResponse =
+        from_json(query(deps.as_ref(), at(120), QueryMsg::Params {})?)?;
+    assert_eq!(params.claim_cooldown_blocks, Some(10));
+    Ok(())
+}
+
+#[test]
+fn solvency_check_reports_shortfall() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(3000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(150),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    let solvency = |deps: Deps| -> StdResult<Vec<SolvencyResponse>> {
+        from_json(query(deps, env.clone(), QueryMsg::SolvencyCheck {})?)
+    };
+
+    // The 1500 claimed is no longer owed.
+    deps.querier
+        .update_balance(testing::MOCK_CONTRACT_ADDR, vec![coin(3500, "token")]);
+    assert_eq!(
+        solvency(deps.as_ref())?,
+        vec![SolvencyResponse {
+            denom: "token".to_string(),
+            solvent: true,
+            obligations: Uint128::new(1500),
+            balance: Uint128::new(3500),
+            shortfall: Uint128::zero(),
+        }]
+    );
+
+    deps.querier
+        .update_balance(testing::MOCK_CONTRACT_ADDR, vec![coin(1000, "token")]);
+    assert_eq!(
+        solvency(deps.as_ref())?,
+        vec![SolvencyResponse {
+            denom: "token".to_string(),
+            solvent: false,
+            obligations: Uint128::new(1500),
+            balance: Uint128::new(1000),
+            shortfall: Uint128::new(500),
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn claim_with_limit_pages_through_schedules() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    // Schedules must differ, so each one ends a second later.
+    for end_time in 110..115 {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            ExecuteMsg::RewardUsers {
+                rewards: vec![RewardUserRequest {
+                    user_address: "addr0001".to_string(),
+                    vesting_amount: Uint128::new(100),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                }],
+                vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                    start_time: Uint64::new(100),
+                    cliff_time: Uint64::new(100),
+                    end_time: Uint64::new(end_time),
+                },
+                denom: None,
+            },
+        )?;
+    }
+
+    let env = mock_env_with_time(120);
+    let mut pages = vec![];
+    loop {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: Some(2) },
+        )?;
+        let claimed: Vec<u64> = res
+            .events
+            .iter()
+            .map(|event| {
+                let index = event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == "schedule_index")
+                    .expect("receipts have a schedule_index");
+                index.value.parse::<u64>()
+            })
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![coin(100 * claimed.len() as u128, "token")],
+            })]
+        );
+        let data: ClaimResponse =
+            from_json(res.data.expect("claims set response data"))?;
+        pages.push(claimed);
+        if !data.has_more {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    assert_eq!(schedule_len(deps.as_ref(), "addr0001")?, 0);
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: Some(0) },
+    );
+    assert!(res.is_err());
+    Ok(())
+}
+
+#[test]
+fn schedule_label_round_trips() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |label: Option<String>| ExecuteMsg::RewardUsers {
+        rewards: vec![RewardUserRequest {
+            user_address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(1000),
+            cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label,
+        }],
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            cliff_time: Uint64::new(100),
+            end_time: Uint64::new(110),
+        },
+        denom: None,
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(Some("x".repeat(129))),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::Vesting(VestingError::LabelTooLong {
+            len: 129,
+            max_len: 128,
+        })
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        reward(Some("grant-2024-0042".to_string())),
+    )?;
+    let res: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        env,
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.vestings[0].label, Some("grant-2024-0042".to_string()));
+
+    // Imported schedules keep the label of their exported data.
+    let (mut other, env) = setup_with_block_time(100)?;
+    execute(
+        other.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::ImportAccounts {
+            accounts: vec![("addr0002".to_string(), res.vestings[0].clone())],
+        },
+    )?;
+    let imported: VestingAccountResponse = from_json(query(
+        other.as_ref(),
+        env,
+        QueryMsg::VestingAccount {
+            address: "addr0002".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(imported.vestings[0].label, res.vestings[0].label);
+    Ok(())
+}
+
+#[test]
+fn claim_and_delegate_stakes_the_user_claim() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let contract_addr = env.contract.address.to_string();
+    let valoper = "nibivaloper1validator";
+    deps.querier.update_staking(
+        "token",
+        &[Validator {
+            address: valoper.to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(20),
+            max_change_rate: Decimal::percent(1),
+        }],
+        &[],
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    let env = mock_env_with_time(150);
+
+    // Validators are looked up in the staking module
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimAndDelegate {
+            validator: "nibivaloper1unknown".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownValidator {
+            validator: "nibivaloper1unknown".to_string(),
+        }
+    );
+
+    // The claim is sent to the user, who then delegates it through the
+    // contract's authz grant.
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimAndDelegate {
+            validator: valoper.to_string(),
+        },
+    )?;
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(500, "token")],
+        })
+    );
+    let CosmosMsg::Stargate { type_url, value } = &res.messages[1].msg else {
+        panic!("expected a stargate msg, got {:?}", res.messages[1]);
+    };
+    assert_eq!(type_url, authz::MSG_EXEC_TYPE_URL);
+    let exec = authz::MsgExec::decode(value.as_slice())?;
+    assert_eq!(exec.grantee, contract_addr);
+    assert_eq!(exec.msgs.len(), 1);
+    assert_eq!(exec.msgs[0].type_url, authz::MSG_DELEGATE_TYPE_URL);
+    assert_eq!(
+        authz::MsgDelegate::decode(exec.msgs[0].value.as_slice())?,
+        authz::MsgDelegate {
+            delegator_address: "addr0001".to_string(),
+            validator_address: valoper.to_string(),
+            amount: Some(authz::ProtoCoin {
+                denom: "token".to_string(),
+                amount: "500".to_string(),
+            }),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn managers_query_reports_validation_status() -> TestResult {
+    let mut deps = mock_dependencies();
+    let env = mock_env_with_time(100);
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[coin(1000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec![
+                "manager-two".to_string(),
+                "manager-sender".to_string(),
+            ],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: None,
+            initial_rewards: None,
+        },
+    )?;
+    let query_managers = |deps: Deps| -> StdResult<ManagersResponse> {
+        from_json(query(deps, env.clone(), QueryMsg::Managers {})?)
+    };
+    let status = |address: &str, valid: bool| ManagerStatus {
+        address: address.to_string(),
+        valid,
+    };
+
+    assert_eq!(
+        query_managers(deps.as_ref())?,
+        ManagersResponse {
+            admin: status("admin-sender", true),
+            managers: vec![
+                status("manager-sender", true),
+                status("manager-two", true),
+            ],
+        }
+    );
+
+    // An entry stored under older validation rules is reported as invalid.
+    let mut whitelist = WHITELIST.load(deps.as_ref().storage)?;
+    whitelist.members.insert("Stale-Manager".to_string());
+    WHITELIST.save(deps.as_mut().storage, &whitelist)?;
+    let res = query_managers(deps.as_ref())?;
+    assert_eq!(res.managers[0], status("Stale-Manager", false));
+    assert_eq!(res.managers.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn transfer_vesting_moves_schedules() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let reward = |user_address: &str| RewardUserRequest {
+        user_address: user_address.to_string(),
+        vesting_amount: Uint128::new(1000),
+        cliff_amount: Uint128::zero(),
+        payout_address: None,
+        label: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![reward("addr0001"), reward("addr0003")],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    let claim = |deps: DepsMut, user: &str, block_time: u64| {
+        execute(
+            deps,
+            mock_env_with_time(block_time),
+            mock_info(user, &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+    };
+    claim(deps.as_mut(), "addr0001", 150)?;
+
+    let transfer = |deps: DepsMut, sender: &str, to: &str| {
+        execute(
+            deps,
+            mock_env_with_time(150),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferVesting {
+                from: "addr0001".to_string(),
+                to: to.to_string(),
+            },
+        )
+    };
+    assert_eq!(
+        transfer(deps.as_mut(), "manager-sender", "addr0002").unwrap_err(),
+        StdError::generic_err("Unauthorized").into()
+    );
+    assert_eq!(
+        transfer(deps.as_mut(), "admin-sender", "addr0003").unwrap_err(),
+        StdError::generic_err("User addr0003 already has a vesting account.")
+            .into()
+    );
+    // Claims only save this with a cooldown configured, so seed it directly
+    LAST_CLAIM_HEIGHT.save(deps.as_mut().storage, "addr0001", &42)?;
+    transfer(deps.as_mut(), "admin-sender", "addr0002")?;
+
+    // The claim state moves along with the schedules.
+    let claimed = |deps: Deps, address: &str| -> anyhow::Result<u128> {
+        let res: ClaimedAtHeightResponse = from_json(query(
+            deps,
+            env.clone(),
+            QueryMsg::ClaimedAtHeight {
+                address: address.to_string(),
+                height: env.block.height + 1,
+            },
+        )?)?;
+        Ok(res.claimed_amount.u128())
+    };
+    assert_eq!(claimed(deps.as_ref(), "addr0001")?, 0);
+    assert_eq!(claimed(deps.as_ref(), "addr0002")?, 500);
+    assert_eq!(LAST_CLAIM_HEIGHT.may_load(&deps.storage, "addr0001")?, None);
+    assert_eq!(
+        LAST_CLAIM_HEIGHT.may_load(&deps.storage, "addr0002")?,
+        Some(42)
+    );
+
+    // The old address has nothing left, and the new one continues from the
+    // amount already claimed.
+    assert!(claim(deps.as_mut(), "addr0001", 200).is_err());
+    let res = claim(deps.as_mut(), "addr0002", 200)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: vec![coin(500, "token")],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn rounding_floor_vs_nearest() -> TestResult {
+    let account =
+        |vesting_amount: u128, end_time: u64, rounding| VestingAccount {
+            address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(vesting_amount),
+            cliff_amount: Uint128::zero(),
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(end_time),
+            },
+            claimed_amount: Uint128::zero(),
+            denom: None,
+            milestones_confirmed: 0,
+            payout_address: None,
+            label: None,
+            rounding,
+        };
+    let at = |seconds: u64| Timestamp::from_seconds(seconds);
+
+    // 1000 over 3 seconds: 333.33 at 101 and 666.67 at 102.
+    let floor = account(1000, 103, VestingRounding::Floor);
+    let nearest = account(1000, 103, VestingRounding::Nearest);
+    for (block_time, want_floor, want_nearest) in
+        [(101, 333, 333), (102, 666, 667), (103, 1000, 1000)]
+    {
+        assert_eq!(floor.vested_amount(at(block_time))?.u128(), want_floor);
+        assert_eq!(nearest.vested_amount(at(block_time))?.u128(), want_nearest);
+    }
+
+    // 3 over 1000 seconds: the first unit vests at 1000 / 3 seconds with
+    // floor rounding, and at half of that with nearest rounding.
+    let floor = account(3, 1100, VestingRounding::Floor);
+    let nearest = account(3, 1100, VestingRounding::Nearest);
+    assert_eq!(floor.next_unlock(at(100))?, Some((434, Uint128::one())));
+    assert_eq!(nearest.next_unlock(at(100))?, Some((267, Uint128::one())));
+    assert_eq!(nearest.vested_amount(at(266))?, Uint128::zero());
+    assert_eq!(nearest.vested_amount(at(267))?, Uint128::one());
+
+    // The contract applies the configured rounding to the schedules it
+    // registers.
+    let mut deps = mock_dependencies_with_balance(&[coin(1000, "token")]);
+    instantiate(
+        deps.as_mut(),
+        mock_env_with_time(100),
+        mock_info("admin-sender", &[coin(1000, "token")]),
+        InstantiateMsg {
+            admin: "admin-sender".to_string(),
+            managers: vec!["manager-sender".to_string()],
+            decimals: None,
+            deregister_requires_force_before_cliff: None,
+            multi_denom: None,
+            claim_cooldown_blocks: None,
+            rounding: Some(VestingRounding::Nearest),
+            initial_rewards: Some((
+                vec![RewardUserRequest {
+                    user_address: "addr0001".to_string(),
+                    vesting_amount: Uint128::new(1000),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                }],
+                VestingSchedule::LinearVestingWithCliff {
+                    start_time: Uint64::new(100),
+                    cliff_time: Uint64::new(100),
+                    end_time: Uint64::new(103),
+                },
+            )),
+        },
+    )?;
+    let params: ParamsResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(100),
+        QueryMsg::Params {},
+    )?)?;
+    assert_eq!(params.rounding, VestingRounding::Nearest);
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(102),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(667, "token")],
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn total_claimable_now_sums_all_accounts() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    for (user_address, start_time) in [("addr0001", 100), ("addr0002", 150)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin-sender", &[]),
+            ExecuteMsg::RewardUsers {
+                rewards: vec![RewardUserRequest {
+                    user_address: user_address.to_string(),
+                    vesting_amount: Uint128::new(1000),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                }],
+                vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                    start_time: Uint64::new(start_time),
+                    cliff_time: Uint64::new(start_time),
+                    end_time: Uint64::new(start_time + 100),
+                },
+                denom: None,
+            },
+        )?;
+    }
+    let total_claimable = |deps: Deps, block_time| -> StdResult<Vec<Coin>> {
+        from_json(query(
+            deps,
+            mock_env_with_time(block_time),
+            QueryMsg::TotalClaimableNow {},
+        )?)
+    };
+    assert_eq!(total_claimable(deps.as_ref(), 100)?, vec![]);
+
+    // addr0001 claims its first 500 while addr0002 is still before its cliff.
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(150),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(total_claimable(deps.as_ref(), 150)?, vec![]);
+
+    // At 175: addr0001 has 750 vested of which 500 claimed, addr0002 has 250.
+    assert_eq!(
+        total_claimable(deps.as_ref(), 175)?,
+        vec![coin(500, "token")]
+    );
+    assert_eq!(
+        total_claimable(deps.as_ref(), 250)?,
+        vec![coin(1500, "token")]
+    );
+    Ok(())
+}
+
+#[test]
+fn claim_preview_matches_claim() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::new(100),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(110),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    let preview = |deps: Deps, block_time| -> StdResult<ClaimPreviewResponse> {
+        from_json(query(
+            deps,
+            mock_env_with_time(block_time),
+            QueryMsg::ClaimPreview {
+                address: "addr0001".to_string(),
+            },
+        )?)
+    };
+
+    let before = preview(deps.as_ref(), 155)?;
+    assert!(!before.fully_claimed_after);
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_time(155),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![coin(before.claim_now.u128(), "token")],
+        })]
+    );
+    let account: VestingAccountResponse = from_json(query(
+        deps.as_ref(),
+        mock_env_with_time(155),
+        QueryMsg::VestingAccount {
+            address: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    let vesting = &account.vestings[0];
+    assert_eq!(
+        vesting.vesting_amount - vesting.claimed_amount,
+        before.remaining_after
+    );
+    assert_eq!(
+        preview(deps.as_ref(), 155)?,
+        ClaimPreviewResponse {
+            claim_now: Uint128::zero(),
+            remaining_after: before.remaining_after,
+            fully_claimed_after: false,
+        }
+    );
+
+    // Past the end, the claim sweeps the rest.
+    assert_eq!(
+        preview(deps.as_ref(), 200)?,
+        ClaimPreviewResponse {
+            claim_now: before.remaining_after,
+            remaining_after: Uint128::zero(),
+            fully_claimed_after: true,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn underfunded_schedules_split_balance_pro_rata() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: [("addr0001", 3000), ("addr0002", 1000)]
+                .map(|(user_address, amount)| RewardUserRequest {
+                    user_address: user_address.to_string(),
+                    vesting_amount: Uint128::new(amount),
+                    cliff_amount: Uint128::zero(),
+                    payout_address: None,
+                    label: None,
+                })
+                .to_vec(),
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    let underfunded =
+        |deps: Deps| -> StdResult<Vec<UnderfundedScheduleResponse>> {
+            from_json(query(
+                deps,
+                env.clone(),
+                QueryMsg::UnderfundedSchedules {},
+            )?)
+        };
+    assert_eq!(underfunded(deps.as_ref())?, vec![]);
+
+    // Half of the 4000 owed is left.
+    deps.querier
+        .update_balance(testing::MOCK_CONTRACT_ADDR, vec![coin(2000, "token")]);
+    assert_eq!(
+        underfunded(deps.as_ref())?,
+        vec![
+            UnderfundedScheduleResponse {
+                user_address: "addr0001".to_string(),
+                schedule_index: 0,
+                denom: "token".to_string(),
+                obligation: Uint128::new(3000),
+                available: Uint128::new(1500),
+                shortfall: Uint128::new(1500),
+            },
+            UnderfundedScheduleResponse {
+                user_address: "addr0002".to_string(),
+                schedule_index: 0,
+                denom: "token".to_string(),
+                obligation: Uint128::new(1000),
+                available: Uint128::new(500),
+                shortfall: Uint128::new(500),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn blackout_vesting_pauses_accrual() -> TestResult {
+    let account = VestingAccount {
+        address: "addr0001".to_string(),
+        vesting_amount: Uint128::new(1000),
+        cliff_amount: Uint128::zero(),
+        vesting_schedule: VestingSchedule::LinearWithBlackout {
+            start_time: Uint64::new(100),
+            end_time: Uint64::new(200),
+            blackout_start: Uint64::new(120),
+            blackout_end: Uint64::new(150),
+        },
+        claimed_amount: Uint128::zero(),
+        denom: None,
+        milestones_confirmed: 0,
+        payout_address: None,
+        label: None,
+        rounding: VestingRounding::Floor,
+    };
+    // The 30 second blackout moves the end from 200 to 230.
+    assert_eq!(account.vesting_schedule.end_time(), 230);
+    for (block_time, want_vested) in [
+        (99, 0),
+        (110, 100),
+        (120, 200),
+        (135, 200),
+        (150, 200),
+        (160, 300),
+        (229, 990),
+        (230, 1000),
+    ] {
+        assert_eq!(
+            account.vested_amount(Timestamp::from_seconds(block_time))?,
+            Uint128::new(want_vested),
+            "block_time {block_time}"
+        );
+    }
+    // Inside the blackout, the next unlock is right after it ends.
+    assert_eq!(
+        account.next_unlock(Timestamp::from_seconds(130))?,
+        Some((151, Uint128::new(10)))
+    );
+    Ok(())
+}
+
+#[test]
+fn blackout_vesting_validation_and_claims() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let msg = |blackout_start: u64, blackout_end: u64| ExecuteMsg::RewardUsers {
+        rewards: vec![RewardUserRequest {
+            user_address: "addr0001".to_string(),
+            vesting_amount: Uint128::new(1000),
+            cliff_amount: Uint128::zero(),
+            payout_address: None,
+            label: None,
+        }],
+        vesting_schedule: VestingSchedule::LinearWithBlackout {
+            start_time: Uint64::new(100),
+            end_time: Uint64::new(200),
+            blackout_start: Uint64::new(blackout_start),
+            blackout_end: Uint64::new(blackout_end),
+        },
+        denom: None,
+    };
+    for (blackout_start, blackout_end) in [(90, 150), (150, 210), (150, 150)] {
+        require_error(
+            &mut deps,
+            &env,
+            mock_info("admin-sender", &[]),
+            msg(blackout_start, blackout_end),
+            VestingError::InvalidBlackout {
+                blackout_start,
+                blackout_end,
+            }
+            .into(),
+        );
+    }
+    // The end extended by the blackout must fit in a timestamp
+    assert_eq!(
+        VestingSchedule::LinearWithBlackout {
+            start_time: Uint64::new(100),
+            end_time: Uint64::new(u64::MAX - 10),
+            blackout_start: Uint64::new(120),
+            blackout_end: Uint64::new(150),
+        }
+        .validate(),
+        Err(VestingError::BlackoutOverflow {
+            end_time: u64::MAX - 10,
+            blackout_start: 120,
+            blackout_end: 150,
+        })
+    );
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        msg(120, 150),
+    )?;
+
+    // A clawback cannot end the schedule before its blackout does: 700 of
+    // 1000 would move the end from 200 to 130.
+    require_error(
+        &mut deps,
+        &mock_env_with_time(110),
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::Clawback {
+            user_address: "addr0001".to_string(),
+            reduce_by: Uint128::new(700),
+            schedule_index: None,
+        },
+        VestingError::InvalidBlackout {
+            blackout_start: 120,
+            blackout_end: 150,
+        }
+        .into(),
+    );
+
+    let claim = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                 block_time| {
+        execute(
+            deps.as_mut(),
+            mock_env_with_time(block_time),
+            mock_info("addr0001", &[]),
+            ExecuteMsg::Claim { limit: None },
+        )
+    };
+    let res = claim(&mut deps, 130)?;
+    assert_eq!(res.attributes[4], Attribute::new("claim_amount", "200"));
+    // Nothing accrues during the blackout.
+    claim(&mut deps, 145).unwrap_err();
+    let res = claim(&mut deps, 230)?;
+    assert_eq!(res.attributes[4], Attribute::new("claim_amount", "800"));
+    assert_eq!(schedule_len(deps.as_ref(), "addr0001")?, 0);
+    Ok(())
+}
+
+#[test]
+fn progress_in_basis_points() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin-sender", &[]),
+        ExecuteMsg::RewardUsers {
+            rewards: vec![RewardUserRequest {
+                user_address: "addr0001".to_string(),
+                vesting_amount: Uint128::new(1000),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            }],
+            vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+                start_time: Uint64::new(100),
+                cliff_time: Uint64::new(100),
+                end_time: Uint64::new(200),
+            },
+            denom: None,
+        },
+    )?;
+    let progress =
+        |deps: Deps, block_time| -> StdResult<Vec<ScheduleProgress>> {
+            from_json(query(
+                deps,
+                mock_env_with_time(block_time),
+                QueryMsg::Progress {
+                    address: "addr0001".to_string(),
+                },
+            )?)
+        };
+
+    // A quarter vested, nothing claimed yet.
+    assert_eq!(
+        progress(deps.as_ref(), 125)?,
+        vec![ScheduleProgress {
+            schedule_index: 0,
+            vested_bps: 2500,
+            claimed_bps: 0,
+        }]
+    );
+    execute(
+        deps.as_mut(),
+        mock_env_with_time(125),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Claim { limit: None },
+    )?;
+    assert_eq!(
+        progress(deps.as_ref(), 150)?,
+        vec![ScheduleProgress {
+            schedule_index: 0,
+            vested_bps: 5000,
+            claimed_bps: 2500,
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn reward_users_batches_draw_from_the_same_unallocated() -> TestResult {
+    let (mut deps, env) = setup_with_block_time(100)?;
+    let batch = |users: &[(&str, u128)]| ExecuteMsg::RewardUsers {
+        rewards: users
+            .iter()
+            .map(|(user_address, amount)| RewardUserRequest {
+                user_address: user_address.to_string(),
+                vesting_amount: Uint128::new(*amount),
+                cliff_amount: Uint128::zero(),
+                payout_address: None,
+                label: None,
+            })
+            .collect(),
+        vesting_schedule: VestingSchedule::LinearVestingWithCliff {
+            start_time: Uint64::new(100),
+            cliff_time: Uint64::new(100),
+            end_time: Uint64::new(200),
+        },
+        denom: None,
+    };
+
+    // The first batch takes 4000 of the 5000 unallocated.
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin-sender", &[]),
+        batch(&[("addr0001", 2000), ("addr0002", 2000)]),
+    )?;
+    // A second batch that would have fit in the original 5000 is rejected.
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("manager-sender", &[]),
+        batch(&[("addr0003", 3000)]),
+        StdError::generic_err(
+            "Insufficient funds for all rewards. Contract has 1000 available but trying to allocate 3000",
+        )
+        .into(),
+    );
+    assert_eq!(schedule_len(deps.as_ref(), "addr0003")?, 0);
+
+    // The rejected batch left the remainder untouched.
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager-sender", &[]),
+        batch(&[("addr0003", 1000)]),
+    )?;
+    require_error(
+        &mut deps,
+        &env,
+        mock_info("admin-sender", &[]),
+        batch(&[("addr0004", 1)]),
+        StdError::generic_err(
+            "Insufficient funds for all rewards. Contract has 0 available but trying to allocate 1",
+        )
+        .into(),
+    );
     Ok(())
 }
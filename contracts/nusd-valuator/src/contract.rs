@@ -1,14 +1,21 @@
-use cosmwasm_std::{entry_point, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    entry_point, DepsMut, Env, MessageInfo, Response, StdResult, Storage,
+};
 
 use cw2::set_contract_version;
 
 use crate::{
     error::ContractError,
     events::{
-        denom_set_json, event_add_denom, event_change_denom, event_remove_denom,
+        denom_set_json, event_add_denom, event_change_denom,
+        event_ratio_changed, event_remove_denom, event_replace_denom,
+        event_set_fees, event_set_ratio_source, event_set_rounding_mode,
     },
     msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg},
-    state::ACCEPTED_DENOMS,
+    state::{
+        push_ratio_change, Fees, RatioChange, RatioSource, ACCEPTED_DENOMS,
+        FEES, MAX_FEE_BPS, RATIO_SOURCES, ROUNDING_MODE,
+    },
 };
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
@@ -35,14 +42,27 @@ pub fn execute(
             denom_set.remove(&from);
             denom_set.insert(to.clone());
             ACCEPTED_DENOMS.save(deps.storage, &denom_set)?;
+            // "to" needs its own ratio source before it can be valued.
+            let removed_source =
+                remove_ratio_source(deps.storage, &from, env.block.height)?;
 
             let event = event_change_denom(
                 from.as_str(),
                 to.as_str(),
                 denom_set_json(denom_set)?.as_str(),
             );
+            let changed_event = match removed_source {
+                Some(_) => Some(event_ratio_changed(
+                    &from,
+                    serde_json::to_string(&removed_source)?.as_str(),
+                    serde_json::to_string(&None::<RatioSource>)?.as_str(),
+                )),
+                None => None,
+            };
 
-            Ok(Response::default().add_event(event))
+            Ok(Response::default()
+                .add_event(event)
+                .add_events(changed_event))
         }
         ExecuteMsg::AddDenom { denom } => {
             nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
@@ -73,11 +93,137 @@ pub fn execute(
                 });
             }
             ACCEPTED_DENOMS.save(deps.storage, &denom_set)?;
+            let removed_source =
+                remove_ratio_source(deps.storage, &denom, env.block.height)?;
 
+            let removed_json = serde_json::to_string(&removed_source)?;
             let event = event_remove_denom(
                 denom.as_str(),
                 denom_set_json(denom_set)?.as_str(),
+                removed_json.as_str(),
+            );
+            let changed_event = match removed_source {
+                Some(_) => Some(event_ratio_changed(
+                    &denom,
+                    removed_json.as_str(),
+                    serde_json::to_string(&None::<RatioSource>)?.as_str(),
+                )),
+                None => None,
+            };
+            Ok(Response::default()
+                .add_event(event)
+                .add_events(changed_event))
+        }
+
+        ExecuteMsg::ReplaceDenom { from, to, ratio } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            let mut denom_set = ACCEPTED_DENOMS.load(deps.storage)?;
+            if !denom_set.contains(&from) {
+                return Err(ContractError::RemoveNonexistentDenom {
+                    denom: from,
+                    denom_set,
+                });
+            }
+            if denom_set.contains(&to) {
+                return Err(ContractError::AddExistentDenom {
+                    denom: to,
+                    denom_set,
+                });
+            }
+
+            denom_set.remove(&from);
+            denom_set.insert(to.clone());
+            ACCEPTED_DENOMS.save(deps.storage, &denom_set)?;
+            RATIO_SOURCES.remove(deps.storage, &from);
+            RATIO_SOURCES.save(deps.storage, &to, &ratio)?;
+            push_ratio_change(
+                deps.storage,
+                &to,
+                RatioChange {
+                    old_ratio: None,
+                    new_ratio: Some(ratio.clone()),
+                    block_height: env.block.height,
+                },
+            )?;
+
+            let ratio_json = serde_json::to_string(&ratio)?;
+            let event = event_replace_denom(
+                &from,
+                &to,
+                &ratio_json,
+                denom_set_json(denom_set)?.as_str(),
             );
+            let changed_event = event_ratio_changed(
+                &to,
+                serde_json::to_string(&None::<RatioSource>)?.as_str(),
+                &ratio_json,
+            );
+            Ok(Response::default()
+                .add_event(event)
+                .add_event(changed_event))
+        }
+
+        ExecuteMsg::SetRoundingMode { mode } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            ROUNDING_MODE.save(deps.storage, &mode)?;
+
+            let event =
+                event_set_rounding_mode(serde_json::to_string(&mode)?.as_str());
+            Ok(Response::default().add_event(event))
+        }
+
+        ExecuteMsg::SetRatioSource { denom, source } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            let denom_set = ACCEPTED_DENOMS.load(deps.storage)?;
+            if !denom_set.contains(&denom) {
+                return Err(ContractError::UnacceptedDenom { denom, denom_set });
+            }
+            let old_source = RATIO_SOURCES.may_load(deps.storage, &denom)?;
+            RATIO_SOURCES.save(deps.storage, &denom, &source)?;
+            push_ratio_change(
+                deps.storage,
+                &denom,
+                RatioChange {
+                    old_ratio: old_source.clone(),
+                    new_ratio: Some(source.clone()),
+                    block_height: env.block.height,
+                },
+            )?;
+
+            let source_json = serde_json::to_string(&source)?;
+            let event = event_set_ratio_source(&denom, source_json.as_str());
+            let changed_event = event_ratio_changed(
+                &denom,
+                serde_json::to_string(&old_source)?.as_str(),
+                source_json.as_str(),
+            );
+            Ok(Response::default()
+                .add_event(event)
+                .add_event(changed_event))
+        }
+
+        ExecuteMsg::SetFees {
+            mint_fee_bps,
+            redeem_fee_bps,
+        } => {
+            nibiru_ownable::assert_owner(deps.storage, info.sender.as_str())?;
+            for fee_bps in [mint_fee_bps, redeem_fee_bps] {
+                if fee_bps > MAX_FEE_BPS {
+                    return Err(ContractError::InvalidFee {
+                        fee_bps,
+                        max_fee_bps: MAX_FEE_BPS,
+                    });
+                }
+            }
+            FEES.save(
+                deps.storage,
+                &Fees {
+                    mint_fee_bps,
+                    redeem_fee_bps,
+                },
+            )?;
+
+            let event = event_set_fees(mint_fee_bps, redeem_fee_bps);
             Ok(Response::default().add_event(event))
         }
 
@@ -87,6 +233,29 @@ pub fn execute(
     }
 }
 
+/// Removes the ratio source of "denom", recording the removal in its ratio
+/// history. Returns the removed source, if any.
+fn remove_ratio_source(
+    storage: &mut dyn Storage,
+    denom: &str,
+    block_height: u64,
+) -> StdResult<Option<RatioSource>> {
+    let old_source = RATIO_SOURCES.may_load(storage, denom)?;
+    if old_source.is_some() {
+        RATIO_SOURCES.remove(storage, denom);
+        push_ratio_change(
+            storage,
+            denom,
+            RatioChange {
+                old_ratio: old_source.clone(),
+                new_ratio: None,
+                block_height,
+            },
+        )?;
+    }
+    Ok(old_source)
+}
+
 fn execute_update_ownership(
     deps: DepsMut,
     env: Env,
@@ -144,18 +313,32 @@ pub fn instantiate(
     )?;
     nibiru_ownable::initialize_owner(deps.storage, Some(&msg.owner))?;
     ACCEPTED_DENOMS.save(deps.storage, &msg.accepted_denoms)?;
+    ROUNDING_MODE.save(deps.storage, &msg.rounding_mode.unwrap_or_default())?;
     Ok(Response::default())
 }
 
 #[cfg(test)]
 pub mod tests {
-    use cosmwasm_std::Response;
+    use std::collections::BTreeSet;
+
+    use cosmwasm_std::{
+        testing::mock_env, Coin, Decimal, Response, StdResult, Uint128,
+    };
+    use nibiru_std::{
+        bindings::query::PATH_EXCHANGE_RATE, proto::nibiru::oracle,
+        tutil::encode_stargate,
+    };
 
     use crate::{
         contract::execute,
         error::ContractError,
-        msgs::{ExecuteMsg, QueryMsg},
+        msgs::{
+            BestMintDenomResponse, ExecuteMsg, MintableResponse, QueryMsg,
+            RedeemableResponse,
+        },
         queries::query,
+        rounding::RoundingMode,
+        state::{Fees, RatioChange, RatioSource},
         testing::{self, TestResult, TEST_DENOM},
     };
 
@@ -237,7 +420,7 @@ pub mod tests {
         // Ensure the proper event is emitted
         let event = &res.events[0];
         assert_eq!(event.ty, "nusd_valuator/remove_denom");
-        assert_eq!(event.attributes.len(), 2);
+        assert_eq!(event.attributes.len(), 3);
 
         // Query the registered denoms (should be empty now)
         let want_denom_set: Vec<String> = vec![];
@@ -299,9 +482,683 @@ pub mod tests {
         Ok(())
     }
 
-    // TODO: test change denom
+    #[test]
+    fn remove_denom_clears_ratio_source() -> TestResult {
+        let (mut deps, env, info) =
+            testing::setup_contract(vec![TEST_DENOM.to_string()])?;
+        let source = RatioSource::Fixed(Decimal::percent(150));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: TEST_DENOM.to_string(),
+                source: source.clone(),
+            },
+        )?;
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::RemoveDenom {
+                denom: TEST_DENOM.to_string(),
+            },
+        )?;
+        let removed = res.events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "removed_ratio_source")
+            .expect("missing removed_ratio_source attribute");
+        assert_eq!(removed.value, serde_json::to_string(&Some(&source))?);
+
+        // The removal is recorded like any other ratio change
+        let changed = &res.events[1];
+        assert_eq!(changed.ty, "nusd_valuator/ratio_changed");
+        assert_eq!(changed.attributes[2].value, "null");
+        let history: Vec<RatioChange> = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RatioHistory {
+                denom: TEST_DENOM.to_string(),
+                limit: Some(1),
+            },
+        )?)?;
+        assert_eq!(
+            history,
+            vec![RatioChange {
+                old_ratio: Some(source),
+                new_ratio: None,
+                block_height: env.block.height,
+            }]
+        );
+
+        let query_res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RatioSource {
+                denom: TEST_DENOM.to_string(),
+            },
+        )?;
+        let source: Option<RatioSource> = serde_json::from_slice(&query_res)?;
+        assert_eq!(source, None);
+        Ok(())
+    }
+
+    #[test]
+    fn replace_denom() -> TestResult {
+        let (mut deps, env, info) = testing::setup_contract(vec![
+            "uusdc".to_string(),
+            TEST_DENOM.to_string(),
+        ])?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: "uusdc".to_string(),
+                source: RatioSource::Fixed(Decimal::one()),
+            },
+        )?;
+        let replace = |to: &str| ExecuteMsg::ReplaceDenom {
+            from: "uusdc".to_string(),
+            to: to.to_string(),
+            ratio: RatioSource::Fixed(Decimal::percent(50)),
+        };
+        let accepted_denoms = |deps: cosmwasm_std::Deps| -> StdResult<_> {
+            let res = query(deps, mock_env(), QueryMsg::AcceptedDenoms {})?;
+            cosmwasm_std::from_json::<BTreeSet<String>>(&res)
+        };
+        let ratio_source = |deps: cosmwasm_std::Deps, denom: &str| {
+            let msg = QueryMsg::RatioSource {
+                denom: denom.to_string(),
+            };
+            query(deps, mock_env(), msg).and_then(|res| {
+                cosmwasm_std::from_json::<Option<RatioSource>>(&res)
+            })
+        };
+
+        // Replacing with an accepted denom fails and changes nothing.
+        let denoms_before = accepted_denoms(deps.as_ref())?;
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            replace(TEST_DENOM),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AddExistentDenom {
+                denom: TEST_DENOM.to_string(),
+                denom_set: denoms_before.clone(),
+            }
+        );
+        assert_eq!(accepted_denoms(deps.as_ref())?, denoms_before);
+        assert_eq!(
+            ratio_source(deps.as_ref(), "uusdc")?,
+            Some(RatioSource::Fixed(Decimal::one()))
+        );
+
+        let res = execute(deps.as_mut(), env, info, replace("uusdt"))?;
+        assert_eq!(res.events[0].ty, "nusd_valuator/replace_denom");
+        assert_eq!(
+            accepted_denoms(deps.as_ref())?,
+            BTreeSet::from([TEST_DENOM.to_string(), "uusdt".to_string()])
+        );
+        assert_eq!(ratio_source(deps.as_ref(), "uusdc")?, None);
+        assert_eq!(
+            ratio_source(deps.as_ref(), "uusdt")?,
+            Some(RatioSource::Fixed(Decimal::percent(50)))
+        );
+        let mintable = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Mintable {
+                from_coins: BTreeSet::from(["100uusdt".to_string()]),
+            },
+        )?;
+        let mintable: MintableResponse = cosmwasm_std::from_json(&mintable)?;
+        assert_eq!(mintable.mintable, Uint128::new(50));
+        Ok(())
+    }
+
+    #[test]
+    fn set_rounding_mode() -> TestResult {
+        let (mut deps, env, info) = testing::setup_contract(vec![])?;
+        let query_mode =
+            |deps: cosmwasm_std::Deps| -> anyhow::Result<RoundingMode> {
+                let query_res =
+                    query(deps, mock_env(), QueryMsg::RoundingMode {})?;
+                Ok(serde_json::from_slice(&query_res)?)
+            };
+        assert_eq!(query_mode(deps.as_ref())?, RoundingMode::FloorFloor);
+
+        // Contracts migrated from before rounding modes existed fall back to
+        // the default.
+        crate::state::ROUNDING_MODE.remove(deps.as_mut().storage);
+        assert_eq!(query_mode(deps.as_ref())?, RoundingMode::FloorFloor);
+        let mintable: MintableResponse = serde_json::from_slice(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Mintable {
+                from_coins: BTreeSet::new(),
+            },
+        )?)?;
+        assert_eq!(mintable.mintable, Uint128::zero());
+
+        // Only the owner can set the rounding mode
+        let msg = ExecuteMsg::SetRoundingMode {
+            mode: RoundingMode::HalfEvenHalfEven,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info_for_sender("not-owner"),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, nibiru_ownable::OwnershipError::NotOwner.into());
+
+        let res = execute(deps.as_mut(), env, info, msg)?;
+        assert_eq!(res.events[0].ty, "nusd_valuator/set_rounding_mode");
+        assert_eq!(query_mode(deps.as_ref())?, RoundingMode::HalfEvenHalfEven);
+        Ok(())
+    }
+
+    #[test]
+    fn set_ratio_source() -> TestResult {
+        let (mut deps, env, info) =
+            testing::setup_contract(vec![TEST_DENOM.to_string()])?;
+        let source = RatioSource::Fixed(Decimal::percent(150));
+
+        // The denom must be accepted
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: "unaccepted".to_string(),
+                source: source.clone(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedDenom { .. }));
+
+        // Only the owner can set a ratio source
+        let msg = ExecuteMsg::SetRatioSource {
+            denom: TEST_DENOM.to_string(),
+            source: source.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info_for_sender("not-owner"),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, nibiru_ownable::OwnershipError::NotOwner.into());
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        assert_eq!(res.events[0].ty, "nusd_valuator/set_ratio_source");
+        let query_msg = QueryMsg::RatioSource {
+            denom: TEST_DENOM.to_string(),
+        };
+        let got: Option<RatioSource> = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            query_msg.clone(),
+        )?)?;
+        assert_eq!(got, Some(source));
+
+        // 10 testdenom at 1.5 μNUSD each, and back
+        let mintable: MintableResponse = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Mintable {
+                from_coins: [format!("10{TEST_DENOM}")].into(),
+            },
+        )?)?;
+        assert_eq!(mintable.mintable, Uint128::new(15));
+        let redeemable: RedeemableResponse = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Redeemable {
+                redeem_amount: Uint128::new(15),
+                to_denom: TEST_DENOM.to_string(),
+            },
+        )?)?;
+        assert_eq!(redeemable.redeemable, Uint128::new(10));
+
+        // Removing the denom drops its ratio source
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::RemoveDenom {
+                denom: TEST_DENOM.to_string(),
+            },
+        )?;
+        let got: Option<RatioSource> =
+            serde_json::from_slice(&query(deps.as_ref(), env, query_msg)?)?;
+        assert_eq!(got, None);
+        Ok(())
+    }
+
+    #[test]
+    fn ratio_history() -> TestResult {
+        let (mut deps, mut env, info) =
+            testing::setup_contract(vec![TEST_DENOM.to_string()])?;
+        let first = RatioSource::Fixed(Decimal::percent(150));
+        let second = RatioSource::Fixed(Decimal::percent(120));
+
+        for (height, source) in [(10, &first), (20, &second)] {
+            env.block.height = height;
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::SetRatioSource {
+                    denom: TEST_DENOM.to_string(),
+                    source: source.clone(),
+                },
+            )?;
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: TEST_DENOM.to_string(),
+                source: first.clone(),
+            },
+        )?;
+        let changed = &res.events[1];
+        assert_eq!(changed.ty, "nusd_valuator/ratio_changed");
+        assert_eq!(
+            changed.attributes,
+            vec![
+                cosmwasm_std::Attribute::new("denom", TEST_DENOM),
+                cosmwasm_std::Attribute::new(
+                    "old_ratio",
+                    serde_json::to_string(&Some(&second))?
+                ),
+                cosmwasm_std::Attribute::new(
+                    "new_ratio",
+                    serde_json::to_string(&first)?
+                ),
+            ]
+        );
+
+        let history = |limit: Option<u32>| -> anyhow::Result<Vec<RatioChange>> {
+            Ok(serde_json::from_slice(&query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::RatioHistory {
+                    denom: TEST_DENOM.to_string(),
+                    limit,
+                },
+            )?)?)
+        };
+        let got = history(Some(2))?;
+        assert_eq!(
+            got,
+            vec![
+                RatioChange {
+                    old_ratio: Some(second.clone()),
+                    new_ratio: Some(first.clone()),
+                    block_height: 20,
+                },
+                RatioChange {
+                    old_ratio: Some(first.clone()),
+                    new_ratio: Some(second.clone()),
+                    block_height: 20,
+                },
+            ]
+        );
+        let got = history(None)?;
+        assert_eq!(got.len(), 3);
+        assert_eq!(
+            got[2],
+            RatioChange {
+                old_ratio: None,
+                new_ratio: Some(first),
+                block_height: 10,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn oracle_ratio_source() -> TestResult {
+        let oracle_denom = "uusdc";
+        let resp = oracle::QueryExchangeRateResponse {
+            // 0.998 as a LegacyDec
+            exchange_rate: "998000000000000000".to_string(),
+        };
+        let (mut deps, env, info) = testing::setup_contract_with_stargate(
+            vec![oracle_denom.to_string(), TEST_DENOM.to_string()],
+            [(PATH_EXCHANGE_RATE.to_string(), encode_stargate(&resp))],
+        )?;
+
+        // Denoms without a ratio source can't be valued
+        let query_mintable = |deps: cosmwasm_std::Deps, coins: &[&str]| {
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::Mintable {
+                    from_coins: coins.iter().map(|c| c.to_string()).collect(),
+                },
+            )
+            .and_then(|bin| cosmwasm_std::from_json::<MintableResponse>(&bin))
+            .map(|resp| resp.mintable)
+        };
+        assert!(query_mintable(deps.as_ref(), &["1000uusdc"]).is_err());
+
+        for (denom, source) in [
+            (
+                oracle_denom,
+                RatioSource::Oracle {
+                    pair: "uusdc:unusd".to_string(),
+                },
+            ),
+            (TEST_DENOM, RatioSource::Fixed(Decimal::one())),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::SetRatioSource {
+                    denom: denom.to_string(),
+                    source,
+                },
+            )?;
+        }
+
+        // 1000 uusdc * 0.998 + 500 testdenom * 1
+        assert_eq!(
+            query_mintable(deps.as_ref(), &["1000uusdc", "500testdenom"])?,
+            Uint128::new(1498)
+        );
+        // Unaccepted denoms are rejected
+        assert!(query_mintable(deps.as_ref(), &["1000unibi"]).is_err());
+
+        // 998 μNUSD / 0.998 = 1000 uusdc
+        let choices: Vec<Coin> = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RedeemableChoices {
+                redeem_amount: Uint128::new(998),
+            },
+        )?)?;
+        assert_eq!(
+            choices,
+            vec![
+                Coin::new(998u128, TEST_DENOM),
+                Coin::new(1000u128, oracle_denom)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn best_mint_denom() -> TestResult {
+        let (mut deps, env, info) = testing::setup_contract(vec![
+            "uusdc".to_string(),
+            TEST_DENOM.to_string(),
+        ])?;
+        for (denom, ratio) in [
+            ("uusdc", Decimal::percent(50)),
+            (TEST_DENOM, Decimal::percent(150)),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::SetRatioSource {
+                    denom: denom.to_string(),
+                    source: RatioSource::Fixed(ratio),
+                },
+            )?;
+        }
+        let query_best = |coins: Vec<Coin>| {
+            query(deps.as_ref(), mock_env(), QueryMsg::BestMintDenom { coins })
+                .and_then(|bin| {
+                    cosmwasm_std::from_json::<BestMintDenomResponse>(&bin)
+                })
+        };
+
+        // 100 uusdc mints 50 μNUSD, but 40 testdenom mints 60.
+        let best = query_best(vec![
+            Coin::new(100u128, "uusdc"),
+            Coin::new(40u128, TEST_DENOM),
+        ])?;
+        assert_eq!(
+            best,
+            BestMintDenomResponse {
+                denom: TEST_DENOM.to_string(),
+                mintable: Uint128::new(60),
+            }
+        );
+
+        let best = query_best(vec![
+            Coin::new(200u128, "uusdc"),
+            Coin::new(40u128, TEST_DENOM),
+        ])?;
+        assert_eq!(best.denom, "uusdc");
+        assert_eq!(best.mintable, Uint128::new(100));
+
+        assert!(query_best(vec![]).is_err());
+        assert!(query_best(vec![Coin::new(1u128, "unaccepted")]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn mintable_capacity() -> TestResult {
+        let (mut deps, env, info) = testing::setup_contract(vec![
+            "uusdc".to_string(),
+            TEST_DENOM.to_string(),
+        ])?;
+        for (denom, ratio) in [
+            ("uusdc", Decimal::percent(50)),
+            (TEST_DENOM, Decimal::percent(150)),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::SetRatioSource {
+                    denom: denom.to_string(),
+                    source: RatioSource::Fixed(ratio),
+                },
+            )?;
+        }
+        let query_capacity = |reserves: Vec<Coin>| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::MintableCapacity { reserves },
+            )
+            .and_then(|bin| cosmwasm_std::from_json::<MintableResponse>(&bin))
+        };
+
+        // 1000 uusdc backs 500 μNUSD and 300 testdenom backs 450.
+        let capacity = query_capacity(vec![
+            Coin::new(1000u128, "uusdc"),
+            Coin::new(300u128, TEST_DENOM),
+        ])?;
+        assert_eq!(
+            capacity,
+            MintableResponse {
+                mintable: Uint128::new(950),
+                fee: Uint128::zero(),
+                fee_bps: 0,
+            }
+        );
+
+        assert_eq!(query_capacity(vec![])?.mintable, Uint128::zero());
+        assert!(query_capacity(vec![
+            Coin::new(1000u128, "uusdc"),
+            Coin::new(1u128, "unaccepted"),
+        ])
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn set_fees() -> TestResult {
+        let (mut deps, env, info) =
+            testing::setup_contract(vec![TEST_DENOM.to_string()])?;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: TEST_DENOM.to_string(),
+                source: RatioSource::Fixed(Decimal::one()),
+            },
+        )?;
+        let set_fees =
+            |mint_fee_bps: u16, redeem_fee_bps: u16| ExecuteMsg::SetFees {
+                mint_fee_bps,
+                redeem_fee_bps,
+            };
+
+        // Only the owner can set fees, and fees can't exceed 100%
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            testing::mock_info_for_sender("not-owner"),
+            set_fees(30, 30),
+        )
+        .unwrap_err();
+        assert_eq!(err, nibiru_ownable::OwnershipError::NotOwner.into());
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_fees(30, 10_001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidFee {
+                fee_bps: 10_001,
+                max_fee_bps: 10_000
+            }
+        );
+
+        let res = execute(deps.as_mut(), env.clone(), info, set_fees(30, 30))?;
+        assert_eq!(res.events[0].ty, "nusd_valuator/set_fees");
+        let fees: Fees = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Fees {},
+        )?)?;
+        assert_eq!(
+            fees,
+            Fees {
+                mint_fee_bps: 30,
+                redeem_fee_bps: 30
+            }
+        );
+
+        // 30 bps of 10_000 is 30 in both directions
+        let mintable: MintableResponse = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Mintable {
+                from_coins: [format!("10000{TEST_DENOM}")].into(),
+            },
+        )?)?;
+        assert_eq!(
+            mintable,
+            MintableResponse {
+                mintable: Uint128::new(9970),
+                fee: Uint128::new(30),
+                fee_bps: 30,
+            }
+        );
+        let redeemable: RedeemableResponse = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Redeemable {
+                redeem_amount: Uint128::new(10_000),
+                to_denom: TEST_DENOM.to_string(),
+            },
+        )?)?;
+        assert_eq!(
+            redeemable,
+            RedeemableResponse {
+                redeemable: Uint128::new(9970),
+                fee: Uint128::new(30),
+                fee_bps: 30,
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn change_denom() -> TestResult {
+        let (mut deps, env, info) =
+            testing::setup_contract(vec![TEST_DENOM.to_string()])?;
+        let source = RatioSource::Fixed(Decimal::percent(150));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetRatioSource {
+                denom: TEST_DENOM.to_string(),
+                source: source.clone(),
+            },
+        )?;
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::ChangeDenom {
+                from: TEST_DENOM.to_string(),
+                to: "newdenom".to_string(),
+            },
+        )?;
+        assert_eq!(res.events[0].ty, "nusd_valuator/change_denom");
+        let changed = &res.events[1];
+        assert_eq!(changed.ty, "nusd_valuator/ratio_changed");
+        assert_eq!(
+            changed.attributes,
+            vec![
+                cosmwasm_std::Attribute::new("denom", TEST_DENOM),
+                cosmwasm_std::Attribute::new(
+                    "old_ratio",
+                    serde_json::to_string(&Some(&source))?
+                ),
+                cosmwasm_std::Attribute::new("new_ratio", "null"),
+            ]
+        );
+
+        let denoms: Vec<String> = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AcceptedDenoms {},
+        )?)?;
+        assert_eq!(denoms, vec!["newdenom".to_string()]);
+        let history: Vec<RatioChange> = serde_json::from_slice(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RatioHistory {
+                denom: TEST_DENOM.to_string(),
+                limit: Some(1),
+            },
+        )?)?;
+        assert_eq!(
+            history,
+            vec![RatioChange {
+                old_ratio: Some(source),
+                new_ratio: None,
+                block_height: env.block.height,
+            }]
+        );
         Ok(())
     }
 
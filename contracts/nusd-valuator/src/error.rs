@@ -28,6 +28,15 @@ pub enum ContractError {
         denom_set: BTreeSet<String>,
     },
 
+    #[error("denom {denom} is not in the accepted set {denom_set:?}")]
+    UnacceptedDenom {
+        denom: String,
+        denom_set: BTreeSet<String>,
+    },
+
+    #[error("fee of {fee_bps} bps is above the maximum of {max_fee_bps} bps")]
+    InvalidFee { fee_bps: u16, max_fee_bps: u16 },
+
     #[error("no need to add denom {denom} to set {denom_set:?}")]
     AddExistentDenom {
         denom: String,
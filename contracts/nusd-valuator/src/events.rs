@@ -8,10 +8,28 @@ pub fn event_add_denom(denom: &str, denom_set_json: &str) -> Event {
         .add_attribute("new_denom_set", denom_set_json)
 }
 
-pub fn event_remove_denom(denom: &str, denom_set_json: &str) -> Event {
+pub fn event_remove_denom(
+    denom: &str,
+    denom_set_json: &str,
+    removed_ratio_json: &str,
+) -> Event {
     Event::new("nusd_valuator/remove_denom")
         .add_attribute("denom", denom)
         .add_attribute("new_denom_set", denom_set_json)
+        .add_attribute("removed_ratio_source", removed_ratio_json)
+}
+
+pub fn event_replace_denom(
+    from_denom: &str,
+    to_denom: &str,
+    ratio_json: &str,
+    denom_set_json: &str,
+) -> Event {
+    Event::new("nusd_valuator/replace_denom")
+        .add_attribute("from_denom", from_denom)
+        .add_attribute("to_denom", to_denom)
+        .add_attribute("new_ratio_source", ratio_json)
+        .add_attribute("new_denom_set", denom_set_json)
 }
 
 pub fn event_change_denom(
@@ -25,6 +43,34 @@ pub fn event_change_denom(
         .add_attribute("new_denom_set", denom_set_json)
 }
 
+pub fn event_set_rounding_mode(mode_json: &str) -> Event {
+    Event::new("nusd_valuator/set_rounding_mode")
+        .add_attribute("new_rounding_mode", mode_json)
+}
+
+pub fn event_set_ratio_source(denom: &str, source_json: &str) -> Event {
+    Event::new("nusd_valuator/set_ratio_source")
+        .add_attribute("denom", denom)
+        .add_attribute("new_ratio_source", source_json)
+}
+
+pub fn event_ratio_changed(
+    denom: &str,
+    old_ratio_json: &str,
+    new_ratio_json: &str,
+) -> Event {
+    Event::new("nusd_valuator/ratio_changed")
+        .add_attribute("denom", denom)
+        .add_attribute("old_ratio", old_ratio_json)
+        .add_attribute("new_ratio", new_ratio_json)
+}
+
+pub fn event_set_fees(mint_fee_bps: u16, redeem_fee_bps: u16) -> Event {
+    Event::new("nusd_valuator/set_fees")
+        .add_attribute("mint_fee_bps", mint_fee_bps.to_string())
+        .add_attribute("redeem_fee_bps", redeem_fee_bps.to_string())
+}
+
 pub fn denom_set_json(
     denom_set: BTreeSet<String>,
 ) -> serde_json::Result<String> {
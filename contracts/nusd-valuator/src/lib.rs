@@ -1,5 +1,6 @@
 pub mod msgs;
 pub mod queries;
+pub mod rounding;
 
 #[cfg(not(feature = "library"))]
 // When imported with the "library" feature, contract.rs will not be compiled.
@@ -3,18 +3,25 @@ use std::collections::BTreeSet;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std as cw;
 
+use crate::{
+    rounding::RoundingMode,
+    state::{Fees, RatioChange, RatioSource},
+};
+
 #[nibiru_ownable::ownable_query]
 #[cw_serde]
 #[derive(cosmwasm_schema::QueryResponses)]
 pub enum QueryMsg {
     /// Mintable: Returns the amount of μNUSD that can be minted in exchange
-    /// for the given set of "from_coins".
-    #[returns(cw::Uint128)]
+    /// for the given set of "from_coins" after the mint fee, rounded with the
+    /// mint half of the "ROUNDING_MODE".
+    #[returns(MintableResponse)]
     Mintable { from_coins: BTreeSet<String> },
 
     /// Redeemable: Returns the amount of "to_denom"  redeemable
-    /// for the given "redeem_amount" of μNUSD.
-    #[returns(cw::Uint128)]
+    /// for the given "redeem_amount" of μNUSD after the redeem fee, rounded
+    /// with the redeem half of the "ROUNDING_MODE".
+    #[returns(RedeemableResponse)]
     Redeemable {
         redeem_amount: cw::Uint128,
         to_denom: String,
@@ -28,21 +35,107 @@ pub enum QueryMsg {
     /// when redeeming the given "redeem_amount" of μNUSD.
     #[returns(BTreeSet<cw::Coin>)]
     RedeemableChoices { redeem_amount: cw::Uint128 },
+
+    /// Returns the current mint and redeem fees.
+    #[returns(Fees)]
+    Fees {},
+
+    /// Returns the current rounding mode for minting and redeeming.
+    #[returns(RoundingMode)]
+    RoundingMode {},
+
+    /// Returns the ratio source of the given accepted denom, if one is set.
+    #[returns(Option<RatioSource>)]
+    RatioSource { denom: String },
+
+    /// RatioHistory: Returns up to "limit" of the latest ratio source changes
+    /// of "denom", newest first. "limit" defaults to and is capped at the
+    /// number of changes kept per denom.
+    #[returns(Vec<RatioChange>)]
+    RatioHistory { denom: String, limit: Option<u32> },
+
+    /// BestMintDenom: Returns the coin in "coins" that mints the most μNUSD
+    /// on its own under the current ratios. Errors if "coins" is empty.
+    #[returns(BestMintDenomResponse)]
+    BestMintDenom { coins: Vec<cw::Coin> },
+
+    /// MintableCapacity: Returns the μNUSD that minting all of "reserves"
+    /// would yield under the current ratios, after the mint fee. Errors if
+    /// any coin is not an accepted denom.
+    #[returns(MintableResponse)]
+    MintableCapacity { reserves: Vec<cw::Coin> },
+}
+
+#[cw_serde]
+pub struct MintableResponse {
+    /// μNUSD minted after the fee.
+    pub mintable: cw::Uint128,
+    /// μNUSD kept as the mint fee.
+    pub fee: cw::Uint128,
+    pub fee_bps: u16,
+}
+
+#[cw_serde]
+pub struct RedeemableResponse {
+    /// Amount of "to_denom" redeemed after the fee.
+    pub redeemable: cw::Uint128,
+    /// Amount of "to_denom" kept as the redeem fee.
+    pub fee: cw::Uint128,
+    pub fee_bps: u16,
+}
+
+#[cw_serde]
+pub struct BestMintDenomResponse {
+    pub denom: String,
+    /// Amount of μNUSD mintable from the full amount of "denom" in the basket,
+    /// after the mint fee.
+    pub mintable: cw::Uint128,
 }
 
 #[nibiru_ownable::ownable_execute]
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Change one denom in the "ACCEPTED_DENOMS" set to another one in-place.
+    /// The ratio source of "from" is removed, and the removal is recorded in
+    /// its ratio history.
     ChangeDenom { from: String, to: String },
 
     /// Add a denom to the set of "ACCEPTED_DENOMS", emitting the new denom set
     /// with with the "nusd_valuator/add_denom" event
     AddDenom { denom: String },
 
-    /// Remove a denom from the set of "ACCEPTED_DENOMS", emitting the new
-    /// denom set with the "nusd_valuator/remove_denom" event
+    /// Remove a denom from the set of "ACCEPTED_DENOMS" together with its
+    /// ratio source, emitting the new denom set and the removed ratio source
+    /// with the "nusd_valuator/remove_denom" event. A removed ratio source is
+    /// also recorded in the ratio history.
     RemoveDenom { denom: String },
+
+    /// Atomically remove "from" with its ratio source and add "to" with the
+    /// ratio source "ratio", emitting the "nusd_valuator/replace_denom" and
+    /// "nusd_valuator/ratio_changed" events. Fails without changes if "from"
+    /// is not accepted or "to" already is.
+    ReplaceDenom {
+        from: String,
+        to: String,
+        ratio: RatioSource,
+    },
+
+    /// Set the "ROUNDING_MODE" used by the Mintable and Redeemable queries,
+    /// emitting the "nusd_valuator/set_rounding_mode" event
+    SetRoundingMode { mode: RoundingMode },
+
+    /// Set the "RatioSource" used to value an accepted denom in μNUSD,
+    /// emitting the "nusd_valuator/set_ratio_source" and
+    /// "nusd_valuator/ratio_changed" events. The change is recorded in the
+    /// "RATIO_HISTORY" of the denom.
+    SetRatioSource { denom: String, source: RatioSource },
+
+    /// Set the "FEES" applied by the Mintable and Redeemable queries, emitting
+    /// the "nusd_valuator/set_fees" event
+    SetFees {
+        mint_fee_bps: u16,
+        redeem_fee_bps: u16,
+    },
 }
 
 // TODO: MigrateMsg
@@ -54,4 +147,6 @@ pub struct InstantiateMsg {
     /// The owner is the only one that can use ExecuteMsg.
     pub owner: String,
     pub accepted_denoms: BTreeSet<String>,
+    /// Defaults to `RoundingMode::FloorFloor`.
+    pub rounding_mode: Option<RoundingMode>,
 }
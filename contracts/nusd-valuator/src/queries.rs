@@ -1,10 +1,17 @@
 use cosmwasm_std::{
-    to_json_binary, Binary, Coin, Deps, Env, StdResult, Uint128,
+    to_json_binary, Binary, Coin, Decimal, Deps, Env, StdError, StdResult,
+    Uint128,
 };
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, str::FromStr};
 
-use crate::msgs::QueryMsg;
-use crate::state::ACCEPTED_DENOMS;
+use crate::msgs::{
+    BestMintDenomResponse, MintableResponse, QueryMsg, RedeemableResponse,
+};
+use crate::rounding::RoundingMode;
+use crate::state::{
+    apply_fee, Fees, RatioChange, ACCEPTED_DENOMS, FEES, MAX_RATIO_HISTORY,
+    RATIO_HISTORY, RATIO_SOURCES, ROUNDING_MODE,
+};
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -26,31 +33,162 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::RedeemableChoices { redeem_amount } => {
             to_json_binary(&query_redeemable_choices(deps, redeem_amount)?)
         }
+        QueryMsg::Fees {} => to_json_binary(&query_fees(deps)?),
+        QueryMsg::RoundingMode {} => to_json_binary(&query_rounding_mode(deps)?),
+        QueryMsg::RatioSource { denom } => {
+            to_json_binary(&RATIO_SOURCES.may_load(deps.storage, &denom)?)
+        }
+        QueryMsg::RatioHistory { denom, limit } => {
+            to_json_binary(&query_ratio_history(deps, &denom, limit)?)
+        }
+        QueryMsg::BestMintDenom { coins } => {
+            to_json_binary(&query_best_mint_denom(deps, coins)?)
+        }
+        QueryMsg::MintableCapacity { reserves } => {
+            to_json_binary(&query_mintable_capacity(deps, &reserves)?)
+        }
         QueryMsg::Ownership {} => {
             to_json_binary(&nibiru_ownable::get_ownership(deps.storage)?)
         }
     }
 }
 
+pub fn query_fees(deps: Deps) -> StdResult<Fees> {
+    Ok(FEES.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Contracts migrated from before rounding modes existed have no
+/// "ROUNDING_MODE" saved and use the default.
+pub fn query_rounding_mode(deps: Deps) -> StdResult<RoundingMode> {
+    Ok(ROUNDING_MODE.may_load(deps.storage)?.unwrap_or_default())
+}
+
 pub fn query_accepted_denoms(deps: Deps) -> StdResult<BTreeSet<String>> {
     ACCEPTED_DENOMS.load(deps.storage)
 }
 
-// TODO: query_mintable
+/// Returns the latest ratio source changes of "denom", newest first.
+pub fn query_ratio_history(
+    deps: Deps,
+    denom: &str,
+    limit: Option<u32>,
+) -> StdResult<Vec<RatioChange>> {
+    let limit = limit
+        .map_or(MAX_RATIO_HISTORY, |limit| limit as usize)
+        .min(MAX_RATIO_HISTORY);
+    let history = RATIO_HISTORY.may_load(deps.storage, denom)?;
+    Ok(history
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .take(limit)
+        .collect())
+}
+
+/// Returns the amount of μNUSD per base unit of "denom", read from the
+/// denom's "RatioSource".
+pub fn query_ratio(deps: Deps, denom: &str) -> StdResult<Decimal> {
+    let denom_set = ACCEPTED_DENOMS.load(deps.storage)?;
+    if !denom_set.contains(denom) {
+        return Err(StdError::generic_err(format!(
+            "denom {denom} is not in the accepted set {denom_set:?}"
+        )));
+    }
+    let source = RATIO_SOURCES
+        .may_load(deps.storage, denom)?
+        .ok_or_else(|| StdError::not_found(format!("ratio source {denom}")))?;
+    source.ratio(&deps.querier)
+}
+
+/// Returns the unrounded μNUSD value of "coin".
+fn coin_value(deps: Deps, coin: &Coin) -> StdResult<Decimal> {
+    let ratio = query_ratio(deps, &coin.denom)?;
+    Decimal::from_ratio(coin.amount, 1u128)
+        .checked_mul(ratio)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+/// Rounds the "gross" μNUSD value of a mint after taking the mint fee.
+fn mintable_after_fee(
+    deps: Deps,
+    gross: Decimal,
+) -> StdResult<MintableResponse> {
+    let fee_bps = query_fees(deps)?.mint_fee_bps;
+    let (net, _) = apply_fee(gross, fee_bps);
+    let rounding = query_rounding_mode(deps)?;
+    let mintable = rounding.round_mint(net);
+    Ok(MintableResponse {
+        mintable,
+        fee: rounding.round_mint(gross).saturating_sub(mintable),
+        fee_bps,
+    })
+}
+
 pub fn query_mintable(
-    _deps: Deps,
-    _from_coins: BTreeSet<String>,
-) -> StdResult<Uint128> {
-    todo!()
+    deps: Deps,
+    from_coins: BTreeSet<String>,
+) -> StdResult<MintableResponse> {
+    let mut mintable = Decimal::zero();
+    for coin_str in from_coins {
+        let coin = Coin::from_str(&coin_str)?;
+        mintable = mintable.checked_add(coin_value(deps, &coin)?)?;
+    }
+    mintable_after_fee(deps, mintable)
+}
+
+/// Returns the μNUSD mintable from the whole "reserves" snapshot. Unlike
+/// "Mintable", repeated denoms are each counted.
+pub fn query_mintable_capacity(
+    deps: Deps,
+    reserves: &[Coin],
+) -> StdResult<MintableResponse> {
+    let mut capacity = Decimal::zero();
+    for coin in reserves {
+        capacity = capacity.checked_add(coin_value(deps, coin)?)?;
+    }
+    mintable_after_fee(deps, capacity)
+}
+
+/// Returns the coin of the basket with the highest μNUSD value. On a tie, the
+/// coin that comes first in "coins" wins.
+pub fn query_best_mint_denom(
+    deps: Deps,
+    coins: Vec<Coin>,
+) -> StdResult<BestMintDenomResponse> {
+    let mut best: Option<(&Coin, Decimal)> = None;
+    for coin in &coins {
+        let value = coin_value(deps, coin)?;
+        if best.is_none_or(|(_, best_value)| value > best_value) {
+            best = Some((coin, value));
+        }
+    }
+    let (coin, value) = best.ok_or_else(|| {
+        StdError::generic_err("coins must contain at least one coin")
+    })?;
+    Ok(BestMintDenomResponse {
+        denom: coin.denom.clone(),
+        mintable: mintable_after_fee(deps, value)?.mintable,
+    })
 }
 
-// TODO: query_redeemable
 pub fn query_redeemable(
-    _deps: Deps,
-    _redeem_amount: Uint128,
-    _to_denom: &str,
-) -> StdResult<Uint128> {
-    todo!()
+    deps: Deps,
+    redeem_amount: Uint128,
+    to_denom: &str,
+) -> StdResult<RedeemableResponse> {
+    let ratio = query_ratio(deps, to_denom)?;
+    let gross = Decimal::from_ratio(redeem_amount, 1u128)
+        .checked_div(ratio)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let fee_bps = query_fees(deps)?.redeem_fee_bps;
+    let (net, _) = apply_fee(gross, fee_bps);
+    let rounding = query_rounding_mode(deps)?;
+    let redeemable = rounding.round_redeem(net);
+    Ok(RedeemableResponse {
+        redeemable,
+        fee: rounding.round_redeem(gross).saturating_sub(redeemable),
+        fee_bps,
+    })
 }
 
 pub fn query_redeemable_choices(
@@ -63,7 +201,7 @@ pub fn query_redeemable_choices(
         .map(|denom| {
             Ok(Coin {
                 denom: denom.clone(),
-                amount: query_redeemable(deps, redeem_amount, denom)?,
+                amount: query_redeemable(deps, redeem_amount, denom)?.redeemable,
             })
         })
         .collect();
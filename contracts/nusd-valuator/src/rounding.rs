@@ -0,0 +1,114 @@
+//! rounding.rs: Rounding behavior for converting between μNUSD and
+//! collateral amounts.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Uint128};
+
+/// RoundingMode: How fractional amounts are rounded to whole base units. The
+/// first half of the name applies to `Mintable` (μNUSD received for
+/// collateral) and the second half to `Redeemable` (collateral received for
+/// μNUSD).
+///
+/// - "Floor": Round toward zero, e.g. 2.5 -> 2. This always favors the
+///   protocol.
+/// - "Ceil": Round away from zero, e.g. 2.5 -> 3.
+/// - "HalfEven": Banker's rounding. Round to the nearest integer, and break
+///   ties toward the even one, e.g. 2.5 -> 2, 3.5 -> 4, 2.6 -> 3.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum RoundingMode {
+    #[default]
+    FloorFloor,
+    FloorCeil,
+    CeilFloor,
+    CeilCeil,
+    HalfEvenHalfEven,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Rounding {
+    Floor,
+    Ceil,
+    HalfEven,
+}
+
+impl RoundingMode {
+    fn split(&self) -> (Rounding, Rounding) {
+        match self {
+            RoundingMode::FloorFloor => (Rounding::Floor, Rounding::Floor),
+            RoundingMode::FloorCeil => (Rounding::Floor, Rounding::Ceil),
+            RoundingMode::CeilFloor => (Rounding::Ceil, Rounding::Floor),
+            RoundingMode::CeilCeil => (Rounding::Ceil, Rounding::Ceil),
+            RoundingMode::HalfEvenHalfEven => {
+                (Rounding::HalfEven, Rounding::HalfEven)
+            }
+        }
+    }
+
+    /// Rounds an amount of μNUSD to mint.
+    pub fn round_mint(&self, amount: Decimal) -> Uint128 {
+        round(amount, self.split().0)
+    }
+
+    /// Rounds an amount of collateral to redeem.
+    pub fn round_redeem(&self, amount: Decimal) -> Uint128 {
+        round(amount, self.split().1)
+    }
+}
+
+fn round(amount: Decimal, rounding: Rounding) -> Uint128 {
+    let floor = amount.to_uint_floor();
+    match rounding {
+        Rounding::Floor => floor,
+        Rounding::Ceil => amount.to_uint_ceil(),
+        Rounding::HalfEven => {
+            let frac = amount - amount.floor();
+            let half = Decimal::percent(50);
+            let is_odd = floor.u128() % 2 == 1;
+            if frac > half || (frac == half && is_odd) {
+                floor + Uint128::one()
+            } else {
+                floor
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cosmwasm_std::{Decimal, Uint128};
+
+    use super::RoundingMode;
+
+    #[test]
+    fn round_half_boundary() {
+        let test_cases: Vec<(RoundingMode, &str, u128, u128)> = vec![
+            // (mode, amount, want_mint, want_redeem)
+            (RoundingMode::FloorFloor, "2.5", 2, 2),
+            (RoundingMode::FloorCeil, "2.5", 2, 3),
+            (RoundingMode::CeilFloor, "2.5", 3, 2),
+            (RoundingMode::CeilCeil, "2.5", 3, 3),
+            (RoundingMode::HalfEvenHalfEven, "2.5", 2, 2),
+            (RoundingMode::HalfEvenHalfEven, "3.5", 4, 4),
+            (RoundingMode::HalfEvenHalfEven, "2.500000000000000001", 3, 3),
+            (RoundingMode::HalfEvenHalfEven, "2.499999999999999999", 2, 2),
+            // Whole amounts are never rounded.
+            (RoundingMode::CeilCeil, "2", 2, 2),
+        ];
+        for (mode, amount, want_mint, want_redeem) in test_cases {
+            let amount = Decimal::from_str(amount).unwrap();
+            assert_eq!(
+                mode.round_mint(amount),
+                Uint128::new(want_mint),
+                "mode: {mode:?}, amount: {amount}"
+            );
+            assert_eq!(
+                mode.round_redeem(amount),
+                Uint128::new(want_redeem),
+                "mode: {mode:?}, amount: {amount}"
+            );
+        }
+    }
+}
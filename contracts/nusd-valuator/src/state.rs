@@ -1,6 +1,109 @@
-use cw_storage_plus::Item;
-use std::collections::BTreeSet;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, QuerierWrapper, StdError, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::rounding::RoundingMode;
 
 /// ACCEPTED_DENOMS: Defines the set of denominations that can be converted to
 /// and from NUSD.
 pub const ACCEPTED_DENOMS: Item<BTreeSet<String>> = Item::new("accepted_denoms");
+
+/// ROUNDING_MODE: How `Mintable` and `Redeemable` amounts are rounded. Set by
+/// the owner.
+pub const ROUNDING_MODE: Item<RoundingMode> = Item::new("rounding_mode");
+
+/// FEES: Fees taken by `Mintable` and `Redeemable`, in basis points. Set by
+/// the owner. Contracts without stored fees charge none.
+pub const FEES: Item<Fees> = Item::new("fees");
+
+/// RATIO_SOURCES: Maps each accepted denom to the source of its μNUSD
+/// exchange rate. Set by the owner.
+pub const RATIO_SOURCES: Map<&str, RatioSource> = Map::new("ratio_sources");
+
+/// RATIO_HISTORY: Most recent ratio source changes of each denom, oldest
+/// first, holding at most `MAX_RATIO_HISTORY` entries.
+pub const RATIO_HISTORY: Map<&str, VecDeque<RatioChange>> =
+    Map::new("ratio_history");
+
+/// Largest number of `RatioChange` entries kept per denom.
+pub const MAX_RATIO_HISTORY: usize = 20;
+
+/// RatioChange: A change of the ratio source of a denom, replacing
+/// "old_ratio" (`None` if the denom had no ratio source) with "new_ratio" at
+/// "block_height". "new_ratio" is `None` when the ratio source was removed by
+/// `ChangeDenom` or `RemoveDenom`.
+#[cw_serde]
+pub struct RatioChange {
+    pub old_ratio: Option<RatioSource>,
+    pub new_ratio: Option<RatioSource>,
+    pub block_height: u64,
+}
+
+/// Appends "change" to the history of "denom", dropping the oldest entry once
+/// the history is full.
+pub fn push_ratio_change(
+    storage: &mut dyn Storage,
+    denom: &str,
+    change: RatioChange,
+) -> StdResult<()> {
+    let mut history =
+        RATIO_HISTORY.may_load(storage, denom)?.unwrap_or_default();
+    if history.len() == MAX_RATIO_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(change);
+    RATIO_HISTORY.save(storage, denom, &history)
+}
+
+/// Fees: Fees in basis points (1 bps = 0.01%), each at most 10_000.
+///
+/// - "mint_fee_bps": Share of the μNUSD output of a mint that is kept as a fee.
+/// - "redeem_fee_bps": Share of the collateral output of a redeem that is kept
+///   as a fee.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub struct Fees {
+    pub mint_fee_bps: u16,
+    pub redeem_fee_bps: u16,
+}
+
+/// Largest allowed fee in basis points, a fee of 100%.
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// Splits the unrounded "gross" output of a mint or redeem into the amount
+/// after the fee and the fee itself.
+pub fn apply_fee(gross: Decimal, fee_bps: u16) -> (Decimal, Decimal) {
+    let fee = gross * Decimal::from_ratio(fee_bps, MAX_FEE_BPS);
+    (gross - fee, fee)
+}
+
+/// RatioSource: Where the amount of μNUSD per base unit of a collateral denom
+/// comes from.
+///
+/// - "Fixed": A constant ratio set by the owner.
+/// - "Oracle": The Nibiru oracle exchange rate of "pair", e.g. "uusdc:unusd",
+///   queried on every `Mintable` or `Redeemable` request.
+#[cw_serde]
+pub enum RatioSource {
+    Fixed(Decimal),
+    Oracle { pair: String },
+}
+
+impl RatioSource {
+    /// Returns the current amount of μNUSD per base unit of collateral.
+    pub fn ratio(&self, querier: &QuerierWrapper) -> StdResult<Decimal> {
+        let ratio = match self {
+            RatioSource::Fixed(ratio) => *ratio,
+            RatioSource::Oracle { pair } => {
+                nibiru_std::bindings::query::query_exchange_rate(querier, pair)?
+            }
+        };
+        if ratio.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "ratio from source {self:?} must be positive"
+            )));
+        }
+        Ok(ratio)
+    }
+}
@@ -5,8 +5,9 @@ use cosmwasm_std::{
         mock_dependencies, mock_env, mock_info, MockApi, MockQuerier,
         MockStorage,
     },
-    Env, MessageInfo, OwnedDeps,
+    Binary, Env, MessageInfo, OwnedDeps, Querier,
 };
+use nibiru_std::tutil::{mock_dependencies_with_stargate, MockStargateQuerier};
 
 use crate::{contract::instantiate, msgs::InstantiateMsg};
 
@@ -22,13 +23,33 @@ pub fn setup_contract(
     Env,
     MessageInfo,
 )> {
-    let mut deps = mock_dependencies();
+    init_contract(mock_dependencies(), accepted_denoms)
+}
+
+/// Like [`setup_contract`], but the querier answers the given Stargate
+/// `(path, response)` pairs, e.g. oracle exchange rates.
+pub fn setup_contract_with_stargate(
+    accepted_denoms: Vec<String>,
+    responses: impl IntoIterator<Item = (String, Binary)>,
+) -> anyhow::Result<(
+    OwnedDeps<MockStorage, MockApi, MockStargateQuerier>,
+    Env,
+    MessageInfo,
+)> {
+    init_contract(mock_dependencies_with_stargate(responses), accepted_denoms)
+}
+
+fn init_contract<Q: Querier>(
+    mut deps: OwnedDeps<MockStorage, MockApi, Q>,
+    accepted_denoms: Vec<String>,
+) -> anyhow::Result<(OwnedDeps<MockStorage, MockApi, Q>, Env, MessageInfo)> {
     let env = mock_env();
     let info = mock_info(TEST_OWNER, &[]);
 
     let msg = InstantiateMsg {
         owner: info.sender.to_string(),
         accepted_denoms: accepted_denoms.into_iter().collect(),
+        rounding_mode: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(0, res.messages.len());
@@ -0,0 +1,139 @@
+//! bindings/gov.rs: Builder for `cosmos.gov.v1.MsgSubmitProposal`, which lets
+//! a contract propose governance actions executed by the gov module if the
+//! proposal passes.
+
+use cosmwasm_std::{Coin, CosmosMsg};
+
+use crate::proto::{
+    cosmos::gov::v1::MsgSubmitProposal, NibiruProstMsg, NibiruStargateMsg,
+};
+
+/// SubmitProposal: Builds a `MsgSubmitProposal` from the given proposer,
+/// title, and summary, plus any number of messages and deposit coins.
+///
+/// ```
+/// use cosmwasm_std::coin;
+/// use nibiru_std::{bindings::gov::SubmitProposal, proto::cosmos};
+///
+/// let send = cosmos::bank::v1beta1::MsgSend {
+///     from_address: "nibi1gov".to_string(),
+///     to_address: "nibi1to".to_string(),
+///     amount: vec![coin(5, "unibi").into()],
+/// };
+/// let msg = SubmitProposal::new("nibi1contract", "Title", "Summary")
+///     .message(&send)
+///     .deposit(coin(10, "unibi"))
+///     .into_cosmos_msg();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubmitProposal {
+    msg: MsgSubmitProposal,
+}
+
+impl SubmitProposal {
+    pub fn new(
+        proposer: impl Into<String>,
+        title: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> Self {
+        Self {
+            msg: MsgSubmitProposal {
+                proposer: proposer.into(),
+                title: title.into(),
+                summary: summary.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Appends `msg`, embedded as a `google.protobuf.Any`, to the messages
+    /// the proposal executes.
+    pub fn message<M>(mut self, msg: &M) -> Self
+    where
+        M: prost::Message + prost::Name,
+    {
+        self.msg.messages.push(prost_types::Any {
+            type_url: msg.type_url(),
+            value: msg.to_bytes(),
+        });
+        self
+    }
+
+    /// Appends an already encoded message to the messages the proposal
+    /// executes.
+    pub fn any_message(mut self, any: prost_types::Any) -> Self {
+        self.msg.messages.push(any);
+        self
+    }
+
+    /// Adds `coin` to the initial deposit paid by the proposer.
+    pub fn deposit(mut self, coin: Coin) -> Self {
+        self.msg.initial_deposit.push(coin.into());
+        self
+    }
+
+    /// Adds every coin of `coins` to the initial deposit.
+    pub fn deposits(self, coins: impl IntoIterator<Item = Coin>) -> Self {
+        coins.into_iter().fold(self, Self::deposit)
+    }
+
+    pub fn metadata(mut self, metadata: impl Into<String>) -> Self {
+        self.msg.metadata = metadata.into();
+        self
+    }
+
+    pub fn build(self) -> MsgSubmitProposal {
+        self.msg
+    }
+
+    /// Returns the proposal as a `CosmosMsg::Stargate` a contract can dispatch.
+    pub fn into_cosmos_msg(self) -> CosmosMsg {
+        self.msg.into_stargate_msg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{coin, CosmosMsg};
+    use prost::Message;
+
+    use super::*;
+    use crate::{errors::TestResult, proto::cosmos::bank};
+
+    #[test]
+    fn submit_proposal_embeds_messages() -> TestResult {
+        let send = bank::v1beta1::MsgSend {
+            from_address: "nibi1gov".to_string(),
+            to_address: "nibi1to".to_string(),
+            amount: vec![coin(5, "unibi").into()],
+        };
+        let cosmos_msg =
+            SubmitProposal::new("nibi1contract", "Title", "Summary")
+                .message(&send)
+                .deposits([coin(10, "unibi"), coin(1, "uusdc")])
+                .metadata("ipfs://metadata")
+                .into_cosmos_msg();
+
+        #[allow(deprecated)]
+        let CosmosMsg::Stargate { type_url, value } = cosmos_msg
+        else {
+            panic!("expected a Stargate msg, got {cosmos_msg:?}");
+        };
+        assert_eq!(type_url, "/cosmos.gov.v1.MsgSubmitProposal");
+
+        let proposal = MsgSubmitProposal::decode(value.as_slice())?;
+        assert_eq!(proposal.proposer, "nibi1contract");
+        assert_eq!(proposal.title, "Title");
+        assert_eq!(proposal.metadata, "ipfs://metadata");
+        assert_eq!(proposal.messages.len(), 1);
+        let any = &proposal.messages[0];
+        assert_eq!(any.type_url, "/cosmos.bank.v1beta1.MsgSend");
+        assert_eq!(any.value, send.to_bytes());
+        assert_eq!(bank::v1beta1::MsgSend::from_any(any)?, send);
+        assert_eq!(
+            proposal.initial_deposit,
+            vec![coin(10, "unibi").into(), coin(1, "uusdc").into()]
+        );
+        Ok(())
+    }
+}
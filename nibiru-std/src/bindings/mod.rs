@@ -6,4 +6,6 @@
 //! allow the Go-based Cosmos SDK to interact with and execute smart contracts,
 //! which are written in Rust and compiled to WebAssembly (Wasm).
 
+pub mod gov;
 pub mod msg;
+pub mod query;
@@ -1,5 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{Binary, Coin, CosmosMsg, Decimal, Uint128};
+
+use crate::proto::{
+    cosmos::base::v1beta1::Coin as ProtoCoin, nibiru::tokenfactory,
+    NibiruProstMsg, NibiruStargateMsg,
+};
 
 #[cw_serde]
 pub enum NibiruMsg {
@@ -34,11 +39,212 @@ pub enum NibiruMsg {
         donation: Coin,
     },
 
+    /// ModuleExecute: Escape hatch that executes any module message, given
+    /// as its protobuf type URL and encoded bytes. This lets contracts
+    /// dispatch new module messages before a dedicated binding exists. Build
+    /// it from a prost message with `NibiruMsg::module_execute`. The chain
+    /// does not handle it as a custom message, so dispatch it with
+    /// `NibiruMsg::into_cosmos_msg`.
+    ModuleExecute {
+        type_url: String,
+        value: Binary,
+    },
+
+    /// TokenMint: Mints `amount` of a token factory denom to `mint_to`, or
+    /// to the sender when unset. `denom` is either a full
+    /// "tf/{creator}/{subdenom}" denom or a subdenom created by the sender.
+    /// Build the `MsgMint` to dispatch with `NibiruMsg::tokenfactory_msg`.
+    TokenMint {
+        denom: String,
+        amount: Uint128,
+        mint_to: Option<String>,
+    },
+
+    /// TokenBurn: Burns `amount` of a token factory denom from the sender.
+    /// `denom` is handled as in `TokenMint`.
+    TokenBurn {
+        denom: String,
+        amount: Uint128,
+    },
+
     NoOp {},
 }
 
+impl NibiruMsg {
+    /// Returns a `NibiruMsg::ModuleExecute` for the given protobuf message.
+    pub fn module_execute<M>(msg: &M) -> NibiruMsg
+    where
+        M: prost::Message + prost::Name,
+    {
+        NibiruMsg::ModuleExecute {
+            type_url: msg.type_url(),
+            value: msg.to_binary(),
+        }
+    }
+
+    /// Returns a `ModuleExecute` as the `CosmosMsg::Stargate` that executes
+    /// it, or `None` for any other message.
+    pub fn into_cosmos_msg(self) -> Option<CosmosMsg> {
+        match self {
+            #[allow(deprecated)]
+            NibiruMsg::ModuleExecute { type_url, value } => {
+                Some(CosmosMsg::Stargate { type_url, value })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the token factory `MsgMint` or `MsgBurn` signed by `sender` as
+    /// a `CosmosMsg::Stargate`, or `None` if this is not a `TokenMint` or
+    /// `TokenBurn`.
+    pub fn tokenfactory_msg(&self, sender: &str) -> Option<CosmosMsg> {
+        match self {
+            NibiruMsg::TokenMint {
+                denom,
+                amount,
+                mint_to,
+            } => Some(
+                tokenfactory::MsgMint {
+                    sender: sender.to_string(),
+                    coin: Some(tf_coin(sender, denom, amount)),
+                    mint_to: mint_to.clone().unwrap_or_else(|| sender.into()),
+                }
+                .into_stargate_msg(),
+            ),
+            NibiruMsg::TokenBurn { denom, amount } => Some(
+                tokenfactory::MsgBurn {
+                    sender: sender.to_string(),
+                    coin: Some(tf_coin(sender, denom, amount)),
+                    burn_from: sender.to_string(),
+                }
+                .into_stargate_msg(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the full token factory denom for `denom`: unchanged if it is
+/// already of the form "tf/{creator}/{subdenom}", otherwise treated as a
+/// subdenom of `creator`.
+pub fn tf_denom(creator: &str, denom: &str) -> String {
+    if denom.starts_with("tf/") {
+        denom.to_string()
+    } else {
+        format!("tf/{creator}/{denom}")
+    }
+}
+
+fn tf_coin(creator: &str, denom: &str, amount: &Uint128) -> ProtoCoin {
+    ProtoCoin {
+        denom: tf_denom(creator, denom),
+        amount: amount.to_string(),
+    }
+}
+
 #[cw_serde]
 pub struct LiquidationArgs {
     pub pair: String,
     pub trader: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+    use crate::{
+        errors::TestResult,
+        proto::cosmos::{bank, base},
+    };
+
+    #[test]
+    fn module_execute_from_proto() {
+        let msg = bank::v1beta1::MsgSend {
+            from_address: "nibi1from".to_string(),
+            to_address: "nibi1to".to_string(),
+            amount: vec![base::v1beta1::Coin {
+                denom: "unibi".to_string(),
+                amount: "42".to_string(),
+            }],
+        };
+        let NibiruMsg::ModuleExecute { type_url, value } =
+            NibiruMsg::module_execute(&msg)
+        else {
+            panic!("expected NibiruMsg::ModuleExecute");
+        };
+        assert_eq!(type_url, "/cosmos.bank.v1beta1.MsgSend");
+        assert_eq!(
+            bank::v1beta1::MsgSend::decode(value.as_slice()).unwrap(),
+            msg
+        );
+
+        let cosmos_msg = NibiruMsg::module_execute(&msg).into_cosmos_msg();
+        assert_eq!(cosmos_msg, Some(msg.into_stargate_msg()));
+        assert_eq!(NibiruMsg::NoOp {}.into_cosmos_msg(), None);
+    }
+
+    #[test]
+    fn tokenfactory_mint_and_burn() -> TestResult {
+        let sender = "nibi1contract";
+        let mint = NibiruMsg::TokenMint {
+            denom: "unusd".to_string(),
+            amount: Uint128::new(42),
+            mint_to: Some("nibi1to".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&mint)?,
+            serde_json::json!({ "token_mint": {
+                "denom": "unusd",
+                "amount": "42",
+                "mint_to": "nibi1to",
+            }})
+        );
+
+        #[allow(deprecated)]
+        let Some(CosmosMsg::Stargate { type_url, value }) =
+            mint.tokenfactory_msg(sender)
+        else {
+            panic!("expected a Stargate msg");
+        };
+        assert_eq!(type_url, "/nibiru.tokenfactory.v1.MsgMint");
+        assert_eq!(
+            tokenfactory::MsgMint::decode(value.as_slice())?,
+            tokenfactory::MsgMint {
+                sender: sender.to_string(),
+                coin: Some(base::v1beta1::Coin {
+                    denom: "tf/nibi1contract/unusd".to_string(),
+                    amount: "42".to_string(),
+                }),
+                mint_to: "nibi1to".to_string(),
+            }
+        );
+
+        // Full denoms are kept as is.
+        let burn = NibiruMsg::TokenBurn {
+            denom: "tf/nibi1creator/unusd".to_string(),
+            amount: Uint128::new(7),
+        };
+        #[allow(deprecated)]
+        let Some(CosmosMsg::Stargate { type_url, value }) =
+            burn.tokenfactory_msg(sender)
+        else {
+            panic!("expected a Stargate msg");
+        };
+        assert_eq!(type_url, "/nibiru.tokenfactory.v1.MsgBurn");
+        assert_eq!(
+            tokenfactory::MsgBurn::decode(value.as_slice())?,
+            tokenfactory::MsgBurn {
+                sender: sender.to_string(),
+                coin: Some(base::v1beta1::Coin {
+                    denom: "tf/nibi1creator/unusd".to_string(),
+                    amount: "7".to_string(),
+                }),
+                burn_from: sender.to_string(),
+            }
+        );
+
+        assert_eq!(NibiruMsg::NoOp {}.tokenfactory_msg(sender), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! bindings/query.rs: Helpers for querying Nibiru modules from smart contracts
+//! with `QueryRequest::Stargate`.
+
+use cosmwasm_std::{
+    to_json_vec, Binary, ContractResult, CustomQuery, Decimal, QuerierWrapper,
+    QueryRequest, StdError, SystemResult,
+};
+
+use crate::{
+    errors::NibiruResult,
+    proto::{
+        cosmos::bank::v1beta1 as bank,
+        nibiru::{epochs, oracle},
+        parse_sdk_dec, NibiruProstMsg,
+    },
+};
+
+/// Stargate path of the epochs module `Query/EpochInfos` RPC.
+pub const PATH_EPOCH_INFOS: &str = "/nibiru.epochs.v1.Query/EpochInfos";
+/// Stargate path of the epochs module `Query/CurrentEpoch` RPC.
+pub const PATH_CURRENT_EPOCH: &str = "/nibiru.epochs.v1.Query/CurrentEpoch";
+/// Stargate path of the oracle module `Query/ExchangeRate` RPC.
+pub const PATH_EXCHANGE_RATE: &str = "/nibiru.oracle.v1.Query/ExchangeRate";
+/// Stargate path of the bank module `Query/DenomMetadata` RPC.
+pub const PATH_DENOM_METADATA: &str = "/cosmos.bank.v1beta1.Query/DenomMetadata";
+
+/// Sends `data` to the Stargate query `path` and decodes the protobuf
+/// response as `T`.
+pub fn query_stargate<T, C>(
+    querier: &QuerierWrapper<C>,
+    path: impl Into<String>,
+    data: Binary,
+) -> NibiruResult<T>
+where
+    T: prost::Message + Default,
+    C: CustomQuery,
+{
+    #[allow(deprecated)]
+    let request: QueryRequest<C> = QueryRequest::Stargate {
+        path: path.into(),
+        data,
+    };
+    let bin = match querier.raw_query(&to_json_vec(&request)?) {
+        SystemResult::Err(err) => {
+            return Err(StdError::generic_err(format!(
+                "Querier system error: {err}"
+            ))
+            .into())
+        }
+        SystemResult::Ok(ContractResult::Err(err)) => {
+            return Err(StdError::generic_err(format!(
+                "Querier contract error: {err}"
+            ))
+            .into())
+        }
+        SystemResult::Ok(ContractResult::Ok(bin)) => bin,
+    };
+    T::from_stargate_response(&bin)
+}
+
+/// Returns the `EpochInfo` of the epoch with the given identifier, e.g.
+/// "15 min" or "30 min".
+pub fn query_epoch_info<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    identifier: &str,
+) -> NibiruResult<epochs::EpochInfo> {
+    let resp: epochs::QueryEpochInfosResponse = query_stargate(
+        querier,
+        PATH_EPOCH_INFOS,
+        epochs::QueryEpochInfosRequest {}.to_binary(),
+    )?;
+    resp.epochs
+        .into_iter()
+        .find(|epoch| epoch.identifier == identifier)
+        .ok_or_else(|| StdError::not_found(format!("epoch {identifier}")).into())
+}
+
+/// Returns the current epoch number of the epoch with the given identifier.
+pub fn query_current_epoch<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    identifier: &str,
+) -> NibiruResult<u64> {
+    let resp: epochs::QueryCurrentEpochResponse = query_stargate(
+        querier,
+        PATH_CURRENT_EPOCH,
+        epochs::QueryCurrentEpochRequest {
+            identifier: identifier.to_string(),
+        }
+        .to_binary(),
+    )?;
+    Ok(resp.current_epoch)
+}
+
+/// Returns the oracle exchange rate of `pair`, e.g. "ueth:unusd", as the price
+/// of one unit of the base asset in units of the quote asset.
+pub fn query_exchange_rate<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    pair: &str,
+) -> NibiruResult<Decimal> {
+    let resp: oracle::QueryExchangeRateResponse = query_stargate(
+        querier,
+        PATH_EXCHANGE_RATE,
+        oracle::QueryExchangeRateRequest {
+            pair: pair.to_string(),
+        }
+        .to_binary(),
+    )?;
+    // The rate is a cosmossdk.io/math.LegacyDec in its protobuf form, and
+    // negative rates are rejected.
+    parse_sdk_dec(&resp.exchange_rate)
+}
+
+/// Returns the bank `Metadata` of `denom`, which lists its denom units with
+/// their exponents along with the base and display denoms.
+pub fn query_denom_metadata<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    denom: &str,
+) -> NibiruResult<bank::Metadata> {
+    let resp: bank::QueryDenomMetadataResponse = query_stargate(
+        querier,
+        PATH_DENOM_METADATA,
+        bank::QueryDenomMetadataRequest {
+            denom: denom.to_string(),
+        }
+        .to_binary(),
+    )?;
+    resp.metadata.ok_or_else(|| {
+        StdError::not_found(format!("denom metadata {denom}")).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{Empty, QuerierWrapper};
+
+    use super::*;
+    use crate::{
+        proto::NibiruStargateQuery,
+        tutil::{encode_stargate, mock_dependencies_with_stargate, TestResult},
+    };
+
+    fn epoch(identifier: &str, current_epoch: u64) -> epochs::EpochInfo {
+        epochs::EpochInfo {
+            identifier: identifier.to_string(),
+            start_time: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 0,
+            }),
+            duration: Some(prost_types::Duration {
+                seconds: 900,
+                nanos: 0,
+            }),
+            current_epoch,
+            current_epoch_start_time: None,
+            epoch_counting_started: true,
+            current_epoch_start_height: 420,
+        }
+    }
+
+    #[test]
+    fn paths_match_request_types() {
+        assert_eq!(epochs::QueryEpochInfosRequest {}.path(), PATH_EPOCH_INFOS);
+        assert_eq!(
+            epochs::QueryCurrentEpochRequest::default().path(),
+            PATH_CURRENT_EPOCH
+        );
+        assert_eq!(
+            oracle::QueryExchangeRateRequest::default().path(),
+            PATH_EXCHANGE_RATE
+        );
+        assert_eq!(
+            bank::QueryDenomMetadataRequest::default().path(),
+            PATH_DENOM_METADATA
+        );
+    }
+
+    #[test]
+    fn epoch_queries_decode() -> TestResult {
+        let infos = epochs::QueryEpochInfosResponse {
+            epochs: vec![epoch("15 min", 7), epoch("30 min", 3)],
+        };
+        let current = epochs::QueryCurrentEpochResponse { current_epoch: 7 };
+        let deps = mock_dependencies_with_stargate([
+            (PATH_EPOCH_INFOS.to_string(), encode_stargate(&infos)),
+            (PATH_CURRENT_EPOCH.to_string(), encode_stargate(&current)),
+        ]);
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+
+        let info = query_epoch_info(&querier, "30 min")?;
+        assert_eq!(info, epoch("30 min", 3));
+        assert_eq!(info.duration.map(|d| d.seconds), Some(900));
+        assert!(query_epoch_info(&querier, "1 hour").is_err());
+
+        assert_eq!(query_current_epoch(&querier, "15 min")?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn exchange_rate_decodes() -> TestResult {
+        let resp = oracle::QueryExchangeRateResponse {
+            exchange_rate: "1500000000000000000".to_string(),
+        };
+        let deps = mock_dependencies_with_stargate([(
+            PATH_EXCHANGE_RATE.to_string(),
+            encode_stargate(&resp),
+        )]);
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        assert_eq!(
+            query_exchange_rate(&querier, "ueth:unusd")?,
+            Decimal::from_ratio(3u128, 2u128)
+        );
+
+        let resp = oracle::QueryExchangeRateResponse {
+            exchange_rate: "-1500000000000000000".to_string(),
+        };
+        let deps = mock_dependencies_with_stargate([(
+            PATH_EXCHANGE_RATE.to_string(),
+            encode_stargate(&resp),
+        )]);
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        assert!(query_exchange_rate(&querier, "ueth:unusd").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn denom_metadata_decodes() -> TestResult {
+        let metadata = bank::Metadata {
+            description: "USD Coin".to_string(),
+            denom_units: vec![
+                bank::DenomUnit {
+                    denom: "uusdc".to_string(),
+                    exponent: 0,
+                    aliases: vec!["microusdc".to_string()],
+                },
+                bank::DenomUnit {
+                    denom: "usdc".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ],
+            base: "uusdc".to_string(),
+            display: "usdc".to_string(),
+            name: "USD Coin".to_string(),
+            symbol: "USDC".to_string(),
+            uri: String::new(),
+            uri_hash: String::new(),
+        };
+        let resp = bank::QueryDenomMetadataResponse {
+            metadata: Some(metadata.clone()),
+        };
+        let deps = mock_dependencies_with_stargate([(
+            PATH_DENOM_METADATA.to_string(),
+            encode_stargate(&resp),
+        )]);
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+
+        let got = query_denom_metadata(&querier, "uusdc")?;
+        assert_eq!(got, metadata);
+        let display_exponent = got
+            .denom_units
+            .iter()
+            .find(|unit| unit.denom == got.display)
+            .map(|unit| unit.exponent);
+        assert_eq!(display_exponent, Some(6));
+
+        // A response without metadata is a not found error.
+        let deps = mock_dependencies_with_stargate([(
+            PATH_DENOM_METADATA.to_string(),
+            encode_stargate(&bank::QueryDenomMetadataResponse {
+                metadata: None,
+            }),
+        )]);
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        assert!(query_denom_metadata(&querier, "uusdc").is_err());
+        Ok(())
+    }
+}
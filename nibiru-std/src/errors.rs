@@ -21,10 +21,46 @@ pub enum NibiruError {
     #[error("prost::Name::type_url {} does not correspond to a CosmosMsg::Stargate type_url.", type_url)]
     ProstNameisNotMsg { type_url: String },
 
+    #[error("failed to decode {} from protobuf bytes: {}", type_name, err)]
+    ProstDecode { type_name: String, err: String },
+
+    #[error("stargate query {} to {} failed: {}", index, path, err)]
+    StargateQuery {
+        index: usize,
+        path: String,
+        err: String,
+    },
+
+    #[error("chain version {} is incompatible with the compiled-in nibiru version {}", chain_version, expected)]
+    IncompatibleChainVersion {
+        chain_version: String,
+        expected: String,
+    },
+
+    #[error("Sender {} is unauthorized to {}.", sender, action)]
+    Unauthorized { sender: String, action: String },
+
+    #[error("inflation distribution must sum to 1, got {}", sum)]
+    InvalidInflationDistribution { sum: String },
+
     #[error("{0}")]
     MathError(#[from] MathError),
 }
 
+impl NibiruError {
+    /// Builds a `NibiruError::Unauthorized`. `action` completes the message
+    /// "Sender {sender} is unauthorized to {action}.", e.g. "reward users".
+    pub fn unauthorized(
+        sender: impl Into<String>,
+        action: impl Into<String>,
+    ) -> Self {
+        NibiruError::Unauthorized {
+            sender: sender.into(),
+            action: action.into(),
+        }
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum MathError {
     #[error("division by zero not well defined")]
@@ -37,6 +73,9 @@ pub enum MathError {
     SdkDecError(String),
 }
 
+/// Lets `nibiru-std` helpers be used with `?` in `StdResult` functions, such as
+/// contract entry points. A wrapped `StdError` is returned as is, and every
+/// other variant becomes a `StdError::GenericErr` with the variant's message.
 impl From<NibiruError> for cw::StdError {
     fn from(err: NibiruError) -> cw::StdError {
         match err {
@@ -45,3 +84,65 @@ impl From<NibiruError> for cw::StdError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nibiru_error_into_std_error() {
+        fn assert_std_error<E: std::error::Error>(_: &E) {}
+
+        let err = NibiruError::IncompatibleChainVersion {
+            chain_version: "v1.0.0".to_string(),
+            expected: "v2.0.0".to_string(),
+        };
+        assert_std_error(&err);
+        let msg = err.to_string();
+        assert_eq!(cw::StdError::from(err), cw::StdError::generic_err(msg));
+
+        let err = NibiruError::MathError(MathError::DivisionByZero);
+        let std_err: cw::StdError = err.into();
+        assert_eq!(
+            std_err.to_string(),
+            "Generic error: division by zero not well defined"
+        );
+
+        // Wrapped StdErrors are not double wrapped.
+        let std_err: cw::StdError =
+            NibiruError::CwStd(cw::StdError::not_found("epoch")).into();
+        assert_eq!(std_err, cw::StdError::not_found("epoch"));
+
+        let use_with_question_mark = || -> cw::StdResult<()> {
+            Err(NibiruError::NoTypeUrl {
+                type_name: "Foo".to_string(),
+            })?
+        };
+        assert!(use_with_question_mark()
+            .unwrap_err()
+            .to_string()
+            .contains("no prost::Name implementation for type Foo"));
+    }
+
+    #[test]
+    fn unauthorized_display() {
+        let err = NibiruError::unauthorized("nibi1sender", "reward users");
+        assert_eq!(
+            err,
+            NibiruError::Unauthorized {
+                sender: "nibi1sender".to_string(),
+                action: "reward users".to_string(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Sender nibi1sender is unauthorized to reward users."
+        );
+        assert_eq!(
+            cw::StdError::from(err),
+            cw::StdError::generic_err(
+                "Sender nibi1sender is unauthorized to reward users."
+            )
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! proto/dec.rs: Parsing of `cosmossdk.io/math.LegacyDec` strings, the
+//! representation of `sdk.Dec` fields in protobuf messages.
+
+use std::str::FromStr;
+
+use cosmwasm_std as cw;
+
+use crate::{
+    errors::{MathError, NibiruError},
+    math::DecimalExt,
+};
+
+/// Parses an `sdk.Dec` string into a `cosmwasm_std::Decimal`. Accepts both
+/// encodings of `cosmossdk.io/math.LegacyDec`:
+/// - the protobuf form, the value times 10^18 without a decimal point, e.g.
+///   "272770000000000000" for 0.27277.
+/// - the `String()` form used in JSON, with a decimal point and at most 18
+///   fractional digits, e.g. "0.272770000000000000".
+///
+/// Errors on negative values, which `Decimal` cannot hold, on more than 18
+/// fractional digits, and on anything that is not a decimal number.
+pub fn parse_sdk_dec(s: &str) -> Result<cw::Decimal, NibiruError> {
+    let invalid = |reason: &str| -> NibiruError {
+        MathError::SdkDecError(format!("{reason}: \"{s}\"")).into()
+    };
+    if s.starts_with('-') {
+        return Err(invalid("negative sdk.Dec is not a cosmwasm_std::Decimal"));
+    }
+    let is_digits = |part: &str| {
+        !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit())
+    };
+    let Some((int_part, frac_part)) = s.split_once('.') else {
+        if !is_digits(s) {
+            return Err(invalid("expected a decimal string"));
+        }
+        let atomics = cw::Uint128::from_str(s)
+            .map_err(|_| invalid("value out of range"))?;
+        let precision = DecimalExt::precision_digits() as u32;
+        return cw::Decimal::from_atomics(atomics, precision)
+            .map_err(|_| invalid("value out of range"));
+    };
+    if !is_digits(int_part) || !is_digits(frac_part) {
+        return Err(invalid("expected a decimal string"));
+    }
+    if frac_part.len() > DecimalExt::precision_digits() {
+        return Err(invalid(&format!(
+            "value exceeds max precision digits ({})",
+            DecimalExt::precision_digits()
+        )));
+    }
+    cw::Decimal::from_str(s).map_err(|_| invalid("value out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::TestResult;
+
+    #[test]
+    fn parse_sdk_dec_valid() -> TestResult {
+        let want = cw::Decimal::from_str("0.27277")?;
+        for s in ["272770000000000000", "0.272770000000000000", "0.27277"] {
+            assert_eq!(parse_sdk_dec(s)?, want, "input {s}");
+        }
+        assert_eq!(parse_sdk_dec("1000000000000000000")?, cw::Decimal::one());
+        assert_eq!(parse_sdk_dec("12.5")?, cw::Decimal::from_str("12.5")?);
+        assert_eq!(parse_sdk_dec("000000000000000000")?, cw::Decimal::zero());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_sdk_dec_over_precision() {
+        let err = parse_sdk_dec("0.1234567890123456789").unwrap_err();
+        assert!(
+            err.to_string().contains("max precision digits (18)"),
+            "got {err}"
+        );
+    }
+
+    #[test]
+    fn parse_sdk_dec_malformed() {
+        for s in ["", "abc", "1.2.3", ".5", "5.", "1e18", " 1", "-0.5", "-5"] {
+            assert!(parse_sdk_dec(s).is_err(), "input {s:?}");
+        }
+    }
+}
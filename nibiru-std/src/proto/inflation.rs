@@ -0,0 +1,234 @@
+//! proto/inflation.rs: Constructors for the `nibiru.inflation.v1` genesis
+//! state and module params, which encode decimals as
+//! `cosmossdk.io/math.LegacyDec` protobuf strings.
+
+use std::str::FromStr;
+
+use cosmwasm_std as cw;
+
+use crate::{
+    errors::{NibiruError, NibiruResult},
+    math::SdkDec,
+    proto::nibiru::inflation::{GenesisState, InflationDistribution, Params},
+};
+
+/// Default polynomial factors of the inflation module, from highest to lowest
+/// degree.
+pub const DEFAULT_POLYNOMIAL_FACTORS: [&str; 6] = [
+    "-0.000147085524",
+    "0.074291982762",
+    "-18.867415611180",
+    "3128.641926954698",
+    "-334834.740631598223",
+    "17827464.906540066004",
+];
+
+impl InflationDistribution {
+    /// Returns the distribution with the given proportions, which must sum
+    /// to one.
+    pub fn new(
+        staking_rewards: cw::Decimal,
+        community_pool: cw::Decimal,
+        strategic_reserves: cw::Decimal,
+    ) -> NibiruResult<Self> {
+        let sum = staking_rewards
+            .checked_add(community_pool)
+            .and_then(|sum| sum.checked_add(strategic_reserves))
+            .map_err(cw::StdError::from)?;
+        if sum != cw::Decimal::one() {
+            return Err(NibiruError::InvalidInflationDistribution {
+                sum: sum.to_string(),
+            });
+        }
+        Ok(Self {
+            staking_rewards: SdkDec::from_cw_dec(staking_rewards)?.pb_repr(),
+            community_pool: SdkDec::from_cw_dec(community_pool)?.pb_repr(),
+            strategic_reserves: SdkDec::from_cw_dec(strategic_reserves)?
+                .pb_repr(),
+        })
+    }
+
+    /// Default distribution of the inflation module.
+    pub fn default_distribution() -> Self {
+        Self::new(
+            cw::Decimal::from_ratio(27277u128, 100_000u128),
+            cw::Decimal::from_ratio(35742u128, 100_000u128),
+            cw::Decimal::from_ratio(36981u128, 100_000u128),
+        )
+        .expect("default inflation distribution sums to one")
+    }
+}
+
+impl Params {
+    /// Default params of the inflation module.
+    pub fn default_params() -> Self {
+        Self::builder()
+            .build()
+            .expect("default inflation params are valid")
+    }
+
+    /// Returns a `ParamsBuilder` starting from the default params.
+    pub fn builder() -> ParamsBuilder {
+        ParamsBuilder::default()
+    }
+}
+
+impl GenesisState {
+    /// Genesis state with the given params that starts at the first period.
+    pub fn new(params: Params) -> Self {
+        Self {
+            params: Some(params),
+            period: 0,
+            skipped_epochs: 0,
+        }
+    }
+}
+
+/// ParamsBuilder: Builds inflation `Params` from human readable decimals.
+#[derive(Clone, Debug)]
+pub struct ParamsBuilder {
+    inflation_enabled: bool,
+    polynomial_factors: Vec<String>,
+    inflation_distribution: InflationDistribution,
+    epochs_per_period: u64,
+    periods_per_year: u64,
+    max_period: u64,
+}
+
+impl Default for ParamsBuilder {
+    fn default() -> Self {
+        Self {
+            inflation_enabled: true,
+            polynomial_factors: DEFAULT_POLYNOMIAL_FACTORS
+                .iter()
+                .map(|factor| factor.to_string())
+                .collect(),
+            inflation_distribution: InflationDistribution::default_distribution(
+            ),
+            epochs_per_period: 30,
+            periods_per_year: 12,
+            max_period: 8 * 12,
+        }
+    }
+}
+
+impl ParamsBuilder {
+    pub fn inflation_enabled(mut self, inflation_enabled: bool) -> Self {
+        self.inflation_enabled = inflation_enabled;
+        self
+    }
+
+    /// Sets the polynomial factors from decimal strings such as "-18.86",
+    /// ordered from highest to lowest degree.
+    pub fn polynomial_factors(mut self, factors: &[&str]) -> Self {
+        self.polynomial_factors =
+            factors.iter().map(|factor| factor.to_string()).collect();
+        self
+    }
+
+    pub fn inflation_distribution(
+        mut self,
+        inflation_distribution: InflationDistribution,
+    ) -> Self {
+        self.inflation_distribution = inflation_distribution;
+        self
+    }
+
+    pub fn epochs_per_period(mut self, epochs_per_period: u64) -> Self {
+        self.epochs_per_period = epochs_per_period;
+        self
+    }
+
+    pub fn periods_per_year(mut self, periods_per_year: u64) -> Self {
+        self.periods_per_year = periods_per_year;
+        self
+    }
+
+    pub fn max_period(mut self, max_period: u64) -> Self {
+        self.max_period = max_period;
+        self
+    }
+
+    /// Returns the `Params`, failing if a polynomial factor is not a valid
+    /// decimal.
+    pub fn build(self) -> NibiruResult<Params> {
+        let polynomial_factors = self
+            .polynomial_factors
+            .iter()
+            .map(|factor| Ok(SdkDec::from_str(factor)?.pb_repr()))
+            .collect::<NibiruResult<Vec<String>>>()?;
+        Ok(Params {
+            inflation_enabled: self.inflation_enabled,
+            polynomial_factors,
+            inflation_distribution: Some(self.inflation_distribution),
+            epochs_per_period: self.epochs_per_period,
+            periods_per_year: self.periods_per_year,
+            max_period: self.max_period,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{errors::TestResult, math::DecimalExt};
+
+    #[test]
+    fn default_distribution_sums_to_one() -> TestResult {
+        let params = Params::default_params();
+        let dist = params.inflation_distribution.unwrap();
+        let sum = [
+            &dist.staking_rewards,
+            &dist.community_pool,
+            &dist.strategic_reserves,
+        ]
+        .into_iter()
+        .map(|pb_repr| DecimalExt::from_sdk_dec(pb_repr))
+        .try_fold(DecimalExt::zero(), |sum, dec| dec.map(|dec| sum.add(dec)))?;
+        assert_eq!(sum, DecimalExt::from_str("1")?);
+        assert_eq!(
+            DecimalExt::from_sdk_dec(&dist.staking_rewards)?,
+            DecimalExt::from_str("0.27277")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn params_builder() -> TestResult {
+        let err = InflationDistribution::new(
+            cw::Decimal::percent(50),
+            cw::Decimal::percent(50),
+            cw::Decimal::percent(1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            NibiruError::InvalidInflationDistribution {
+                sum: "1.01".to_string()
+            }
+        );
+
+        let params = Params::builder()
+            .inflation_enabled(false)
+            .polynomial_factors(&["-1.5", "2"])
+            .max_period(12)
+            .build()?;
+        assert!(!params.inflation_enabled);
+        assert_eq!(
+            params.polynomial_factors,
+            vec!["-1500000000000000000", "2000000000000000000"]
+        );
+        assert_eq!(params.max_period, 12);
+        assert_eq!(params.epochs_per_period, 30);
+
+        assert!(Params::builder()
+            .polynomial_factors(&["1.2.3"])
+            .build()
+            .is_err());
+
+        let genesis = GenesisState::new(params.clone());
+        assert_eq!(genesis.params, Some(params));
+        assert_eq!(genesis.period, 0);
+        Ok(())
+    }
+}
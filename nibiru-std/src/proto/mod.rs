@@ -1,10 +1,16 @@
 //! proto/mod.rs: Protobuf types defined in NibiruChain/nibiru/proto.
 
+mod dec;
+mod inflation;
 mod traits;
 mod type_url_cosmos;
 mod type_url_nibiru;
+mod version;
 
+pub use dec::*;
+pub use inflation::*;
 pub use traits::*;
+pub use version::*;
 
 pub mod cosmos {
     /// Authentication of accounts and transactions.
@@ -30,6 +30,18 @@ pub trait NibiruProstMsg: prost::Message {
     {
         any.to_msg()
     }
+
+    /// Parse into this protobuf type from the raw bytes of a
+    /// `QueryRequest::Stargate` response.
+    fn from_stargate_response(bin: &Binary) -> NibiruResult<Self>
+    where
+        Self: Default + Sized,
+    {
+        Self::decode(bin.as_slice()).map_err(|err| NibiruError::ProstDecode {
+            type_name: std::any::type_name::<Self>().to_string(),
+            err: err.to_string(),
+        })
+    }
 }
 
 impl<M> NibiruProstMsg for M
@@ -0,0 +1,56 @@
+//! proto/version.rs: Guards for the chain version the protobuf types in
+//! [`crate::proto`] were generated against.
+
+use crate::{
+    errors::{NibiruError, NibiruResult},
+    VERSION_NIBIRU,
+};
+
+/// Checks that `chain_version` matches [`VERSION_NIBIRU`], the Nibiru commit
+/// the protobuf types were compiled from. Abbreviated commit hashes (at least
+/// 7 characters) are accepted, as are surrounding whitespace and case
+/// differences.
+///
+/// Contracts can call this before sending Stargate messages or queries that
+/// depend on the shape of a proto type.
+pub fn assert_compatible(chain_version: &str) -> NibiruResult<()> {
+    let chain_version = chain_version.trim().to_lowercase();
+    let compatible = chain_version.len() >= 7
+        && VERSION_NIBIRU.starts_with(chain_version.as_str());
+    if !compatible {
+        return Err(NibiruError::IncompatibleChainVersion {
+            chain_version,
+            expected: VERSION_NIBIRU.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compatible_versions() {
+        assert_eq!(assert_compatible(VERSION_NIBIRU), Ok(()));
+        assert_eq!(assert_compatible(&VERSION_NIBIRU[..7]), Ok(()));
+        assert_eq!(
+            assert_compatible(&format!(" {} ", VERSION_NIBIRU.to_uppercase())),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn incompatible_versions() {
+        for chain_version in ["", "9e97", "v1.0.0", "0000000"] {
+            assert_eq!(
+                assert_compatible(chain_version),
+                Err(NibiruError::IncompatibleChainVersion {
+                    chain_version: chain_version.to_string(),
+                    expected: VERSION_NIBIRU.to_string(),
+                }),
+                "chain_version: {chain_version:?}"
+            );
+        }
+    }
+}
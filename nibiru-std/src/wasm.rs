@@ -1,6 +1,16 @@
 // crate::wasm.rs
 
-use cosmwasm_std::{QueryRequest, StdResult, WasmQuery};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use cosmwasm_std::{
+    Addr, Api, Attribute, Binary, Coin, CustomQuery, Event, QuerierWrapper,
+    QueryRequest, Response, StdResult, Uint128, WasmQuery,
+};
+
+use crate::{
+    bindings::query::query_stargate,
+    errors::{NibiruError, NibiruResult},
+};
 
 /// Generic helper for constructing WasmQuery::Smart query requests.
 pub fn wasm_query_smart<CosmosMsg>(
@@ -24,15 +34,141 @@ pub fn wasm_query_raw<CosmosMsg>(
     }))
 }
 
+/// Sends each `(path, data)` Stargate query in order and decodes every response
+/// as `T`. Stops at the first failure, returning a
+/// `NibiruError::StargateQuery` with the index and path of the failed query.
+pub fn query_many<T, C>(
+    querier: &QuerierWrapper<C>,
+    requests: Vec<(String, Binary)>,
+) -> NibiruResult<Vec<T>>
+where
+    T: prost::Message + Default,
+    C: CustomQuery,
+{
+    requests
+        .into_iter()
+        .enumerate()
+        .map(|(index, (path, data))| {
+            query_stargate(querier, path.clone(), data).map_err(|err| {
+                NibiruError::StargateQuery {
+                    index,
+                    path,
+                    err: err.to_string(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Builds the response of an execute message in a uniform shape: an `action`
+/// attribute followed by `attrs`, and the event describing the action.
+pub fn action_response(
+    action: &str,
+    attrs: Vec<Attribute>,
+    event: Event,
+) -> Response {
+    Response::new()
+        .add_attribute("action", action)
+        .add_attributes(attrs)
+        .add_event(event)
+}
+
+/// A set of addresses stored as strings, such as a set of operators or
+/// whitelist members.
+pub trait AddrSet {
+    /// Adds the address, returning whether it was newly inserted.
+    fn insert_addr(&mut self, addr: String) -> bool;
+    /// Removes the address, returning whether it was present.
+    fn remove_addr(&mut self, addr: &str) -> bool;
+}
+
+impl AddrSet for BTreeSet<String> {
+    fn insert_addr(&mut self, addr: String) -> bool {
+        self.insert(addr)
+    }
+
+    fn remove_addr(&mut self, addr: &str) -> bool {
+        self.remove(addr)
+    }
+}
+
+impl AddrSet for HashSet<String> {
+    fn insert_addr(&mut self, addr: String) -> bool {
+        self.insert(addr)
+    }
+
+    fn remove_addr(&mut self, addr: &str) -> bool {
+        self.remove(addr)
+    }
+}
+
+/// Validates `addr` and adds it to the set. Returns `Ok(false)` if the
+/// address was already a member, and errors if it is not a valid address.
+pub fn insert_validated(
+    api: &dyn Api,
+    set: &mut impl AddrSet,
+    addr: &str,
+) -> NibiruResult<bool> {
+    let addr = api.addr_validate(addr)?;
+    Ok(set.insert_addr(addr.into_string()))
+}
+
+/// Removes `addr` from the set, returning whether it was a member.
+pub fn remove_addr(set: &mut impl AddrSet, addr: &str) -> bool {
+    set.remove_addr(addr)
+}
+
+/// Errors with `NibiruError::Unauthorized` unless `sender` is in `allowed`.
+/// `action` completes the message "Sender {sender} is unauthorized to
+/// {action}.", e.g. "reward users".
+pub fn assert_authorized(
+    sender: &Addr,
+    allowed: &BTreeSet<String>,
+    action: &str,
+) -> NibiruResult<()> {
+    if allowed.contains(sender.as_str()) {
+        return Ok(());
+    }
+    Err(NibiruError::unauthorized(sender, action))
+}
+
+/// Returns the coins of `coins` whose denom is in `denoms`, in their original
+/// order.
+pub fn filter_coins(coins: &[Coin], denoms: &BTreeSet<String>) -> Vec<Coin> {
+    coins
+        .iter()
+        .filter(|coin| denoms.contains(&coin.denom))
+        .cloned()
+        .collect()
+}
+
+/// Merges the coins of `coins` that share a denom, ordered by denom. Denoms
+/// that sum to zero are left out. Fails if a sum overflows `Uint128`.
+pub fn sum_coins(coins: &[Coin]) -> StdResult<Vec<Coin>> {
+    let mut sums: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for coin in coins {
+        let sum = sums.entry(coin.denom.as_str()).or_default();
+        *sum = sum.checked_add(coin.amount)?;
+    }
+    Ok(sums
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| Coin::new(amount, denom))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use prost::Message;
 
     use super::*;
-    use crate::proto::{
-        cosmos::{bank, base::v1beta1::Coin},
-        nibiru::{self, perp},
-        NibiruProstMsg,
+    use crate::{
+        proto::{
+            cosmos::{bank, base::v1beta1::Coin},
+            nibiru::{self, oracle, perp},
+            NibiruProstMsg,
+        },
+        tutil::{encode_stargate, mock_dependencies_with_stargate},
     };
 
     #[test]
@@ -56,6 +192,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_action_response() {
+        let event = Event::new("broker_bank/toggle_halt")
+            .add_attribute("new_is_halted", "true");
+        let res = action_response(
+            "toggle_halt",
+            vec![Attribute::new("caller", "owner")],
+            event.clone(),
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                Attribute::new("action", "toggle_halt"),
+                Attribute::new("caller", "owner"),
+            ]
+        );
+        assert_eq!(res.events, vec![event]);
+        assert!(res.messages.is_empty());
+    }
+
     #[test]
     fn proto_msgs_encode() {
         let coin = Coin {
@@ -118,4 +274,136 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_query_many() -> anyhow::Result<()> {
+        let rate = |exchange_rate: &str| oracle::QueryExchangeRateResponse {
+            exchange_rate: exchange_rate.to_string(),
+        };
+        let deps = mock_dependencies_with_stargate([
+            ("/test.Query/RateA".to_string(), encode_stargate(&rate("1"))),
+            ("/test.Query/RateB".to_string(), encode_stargate(&rate("2"))),
+        ]);
+        let querier = QuerierWrapper::<cosmwasm_std::Empty>::new(&deps.querier);
+        let request = |path: &str| (path.to_string(), Binary::default());
+
+        let resps: Vec<oracle::QueryExchangeRateResponse> = query_many(
+            &querier,
+            vec![request("/test.Query/RateB"), request("/test.Query/RateA")],
+        )?;
+        assert_eq!(resps, vec![rate("2"), rate("1")]);
+
+        // The error points at the failed request
+        let err = query_many::<oracle::QueryExchangeRateResponse, _>(
+            &querier,
+            vec![request("/test.Query/RateA"), request("/test.Query/Missing")],
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            NibiruError::StargateQuery { index: 1, ref path, .. }
+                if path == "/test.Query/Missing"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_authorized() {
+        let allowed: BTreeSet<String> =
+            ["admin", "manager"].map(String::from).into();
+        for sender in ["admin", "manager"] {
+            assert!(assert_authorized(
+                &Addr::unchecked(sender),
+                &allowed,
+                "reward users"
+            )
+            .is_ok());
+        }
+
+        let err = assert_authorized(
+            &Addr::unchecked("stranger"),
+            &allowed,
+            "reward users",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Sender stranger is unauthorized to reward users."
+        );
+        assert!(assert_authorized(
+            &Addr::unchecked("admin"),
+            &BTreeSet::new(),
+            "reward users"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_filter_and_sum_coins() -> anyhow::Result<()> {
+        use cosmwasm_std::coin;
+
+        let coins = vec![
+            coin(5, "uusdc"),
+            coin(10, "unibi"),
+            coin(0, "ustake"),
+            coin(7, "uusdc"),
+            coin(1, "dust"),
+        ];
+        let denoms: BTreeSet<String> =
+            ["unibi", "uusdc"].map(String::from).into();
+        assert_eq!(
+            filter_coins(&coins, &denoms),
+            vec![coin(5, "uusdc"), coin(10, "unibi"), coin(7, "uusdc")]
+        );
+        assert!(filter_coins(&coins, &BTreeSet::new()).is_empty());
+
+        assert_eq!(
+            sum_coins(&coins)?,
+            vec![coin(1, "dust"), coin(10, "unibi"), coin(12, "uusdc")]
+        );
+        assert_eq!(
+            sum_coins(&filter_coins(&coins, &denoms))?,
+            vec![coin(10, "unibi"), coin(12, "uusdc")]
+        );
+        assert!(sum_coins(&[])?.is_empty());
+
+        let overflow = [coin(u128::MAX, "unibi"), coin(1, "unibi")];
+        assert!(sum_coins(&overflow).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_addr_set_mutations() -> anyhow::Result<()> {
+        let api = cosmwasm_std::testing::MockApi::default();
+        let alice = api.addr_make("alice").into_string();
+        let bob = api.addr_make("bob").into_string();
+
+        let mut btree_set: BTreeSet<String> = BTreeSet::new();
+        let mut hash_set: HashSet<String> = HashSet::new();
+
+        // insert-new
+        assert!(insert_validated(&api, &mut btree_set, &alice)?);
+        assert!(insert_validated(&api, &mut hash_set, &alice)?);
+
+        // insert-duplicate
+        assert!(!insert_validated(&api, &mut btree_set, &alice)?);
+        assert!(!insert_validated(&api, &mut hash_set, &alice)?);
+        assert_eq!(btree_set.len(), 1);
+        assert_eq!(hash_set.len(), 1);
+
+        // insert-invalid
+        assert!(insert_validated(&api, &mut btree_set, "not-an-addr").is_err());
+        assert_eq!(btree_set.len(), 1);
+
+        // remove-absent
+        assert!(!remove_addr(&mut btree_set, &bob));
+        assert!(!remove_addr(&mut hash_set, &bob));
+
+        // remove-present
+        assert!(remove_addr(&mut btree_set, &alice));
+        assert!(remove_addr(&mut hash_set, &alice));
+        assert!(btree_set.is_empty());
+        assert!(hash_set.is_empty());
+        Ok(())
+    }
 }
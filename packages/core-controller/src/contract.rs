@@ -1,6 +1,12 @@
+use std::collections::BTreeSet;
+
 use cosmwasm_std::{
-    attr, entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult,
+    attr, entry_point, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult,
+};
+use nibiru_std::{
+    errors::NibiruError,
+    wasm::{assert_authorized, insert_validated, remove_addr},
 };
 
 use crate::{
@@ -23,23 +29,21 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
-fn check_admin(can: CanExecute) -> Result<(), cosmwasm_std::StdError> {
-    match can.is_admin {
-        true => Ok(()),
-        false => Err(cosmwasm_std::StdError::generic_err(format!(
-            "unauthorized : sender {} is not an admin",
-            can.sender,
-        ))),
-    }
+fn check_admin(can: &CanExecute, action: &str) -> StdResult<()> {
+    let admins = BTreeSet::from([can.whitelist.admin.clone()]);
+    assert_authorized(&can.sender, &admins, action).map_err(into_std_err)
 }
 
-fn check_member(can: CanExecute) -> Result<(), cosmwasm_std::StdError> {
-    match can.is_member {
-        true => Ok(()),
-        false => Err(cosmwasm_std::StdError::generic_err(format!(
-            "unauthorized : sender {} is not a whitelist member",
-            can.sender,
-        ))),
+fn check_member(can: &CanExecute, action: &str) -> StdResult<()> {
+    let members: BTreeSet<String> =
+        can.whitelist.members.iter().cloned().collect();
+    assert_authorized(&can.sender, &members, action).map_err(into_std_err)
+}
+
+fn into_std_err(err: NibiruError) -> StdError {
+    match err {
+        NibiruError::CwStd(err) => err,
+        err => StdError::generic_err(err.to_string()),
     }
 }
 
@@ -51,14 +55,13 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> StdResult<Response> {
     let deps_for_check = &deps;
-    let check: CanExecute =
-        can_execute(deps_for_check.as_ref(), info.sender.as_ref())?;
+    let check: CanExecute = can_execute(deps_for_check.as_ref(), &info.sender)?;
     let mut whitelist = check.whitelist.clone();
 
     match msg {
         #[allow(unused_variables, deprecated, unreachable_code)]
         ExecuteMsg::WithdrawPerpFund { amount, to } => {
-            check_member(check)?;
+            check_member(&check, "withdraw the perp fund")?;
             todo!();
             let _cw_msg = ExecuteMsg::WithdrawPerpFund { amount, to };
             // let res = Response::new()
@@ -69,7 +72,7 @@ pub fn execute(
 
         #[allow(unused_variables, deprecated, unreachable_code)]
         ExecuteMsg::SetMarketEnabled { pair, enabled } => {
-            check_member(check)?;
+            check_member(&check, "enable markets")?;
             todo!();
             let _cw_msg = ExecuteMsg::SetMarketEnabled { pair, enabled };
             // let res = Response::new()
@@ -85,7 +88,7 @@ pub fn execute(
             sqrt_depth,
             market_params,
         } => {
-            check_member(check)?;
+            check_member(&check, "create markets")?;
             todo!();
             let _cw_msg = ExecuteMsg::CreateMarket {
                 pair,
@@ -112,7 +115,7 @@ pub fn execute(
             min_voters,
             validator_fee_ratio,
         } => {
-            check_member(check)?;
+            check_member(&check, "edit oracle params")?;
             todo!();
             let _cw_msg = ExecuteMsg::EditOracleParams {
                 vote_period,
@@ -133,32 +136,35 @@ pub fn execute(
         }
 
         ExecuteMsg::AddMember { address } => {
-            check_admin(check)?;
-            let addr = address.as_str();
-            whitelist.members.insert(addr.to_string());
+            check_admin(&check, "add members")?;
+            let changed =
+                insert_validated(deps.api, &mut whitelist.members, &address)
+                    .map_err(into_std_err)?;
             WHITELIST.save(deps.storage, &whitelist)?;
 
             let res = Response::new().add_attributes(vec![
                 attr("action", "add_member"),
                 attr("address", address),
+                attr("changed", changed.to_string()),
             ]);
             Ok(res)
         }
 
         ExecuteMsg::RemoveMember { address } => {
-            check_admin(check)?;
-            whitelist.members.remove(address.as_str());
+            check_admin(&check, "remove members")?;
+            let changed = remove_addr(&mut whitelist.members, &address);
             WHITELIST.save(deps.storage, &whitelist)?;
 
             let res = Response::new().add_attributes(vec![
                 attr("action", "remove_member"),
                 attr("address", address),
+                attr("changed", changed.to_string()),
             ]);
             Ok(res)
         }
 
         ExecuteMsg::ChangeAdmin { address } => {
-            check_admin(check)?;
+            check_admin(&check, "change the admin")?;
             let new_admin = address.as_str();
             whitelist.admin = new_admin.to_string();
             whitelist.members.insert(new_admin.to_string());
@@ -174,18 +180,14 @@ pub fn execute(
 }
 
 struct CanExecute {
-    is_admin: bool,
-    is_member: bool,
-    sender: String,
+    sender: Addr,
     whitelist: Whitelist,
 }
 
-fn can_execute(deps: Deps, sender: &str) -> StdResult<CanExecute> {
-    let whitelist = WHITELIST.load(deps.storage).unwrap();
+fn can_execute(deps: Deps, sender: &Addr) -> StdResult<CanExecute> {
+    let whitelist = WHITELIST.load(deps.storage)?;
     Ok(CanExecute {
-        is_admin: whitelist.is_admin(sender),
-        is_member: whitelist.is_member(sender),
-        sender: sender.into(),
+        sender: sender.clone(),
         whitelist,
     })
 }
@@ -286,7 +288,10 @@ mod tests {
             unauthorized_info,
             execute_msg,
         );
-        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Generic error: Sender unauthorized is unauthorized to add members."
+        );
     }
 
     #[test]
@@ -302,7 +307,8 @@ mod tests {
         instantiate(deps.as_mut(), testing::mock_env(), init_info, init_msg)
             .unwrap();
 
-        let new_member = "new_member";
+        let new_member = testing::MockApi::default().addr_make("new_member");
+        let new_member = new_member.as_str();
         let whitelist = WHITELIST.load(&deps.storage).unwrap();
         let has: bool = whitelist.is_admin(new_member);
         assert!(!has);
@@ -322,7 +328,7 @@ mod tests {
             );
             assert_eq!(
                 resp.attributes.len(),
-                2,
+                3,
                 "resp.attributes: {:#?}",
                 resp.attributes
             );
@@ -350,6 +356,20 @@ mod tests {
         let response: IsMemberResponse =
             cosmwasm_std::from_json(binary).unwrap();
         assert!(response.is_member);
+
+        // Adding a member twice changes nothing.
+        let execute_msg = ExecuteMsg::AddMember {
+            address: new_member.to_string(),
+        };
+        let execute_info = testing::mock_info(admin.as_str(), &[]);
+        let result = execute(
+            deps.as_mut(),
+            testing::mock_env(),
+            execute_info,
+            execute_msg,
+        )
+        .unwrap();
+        assert_eq!(result.attributes[2], attr("changed", "false"));
     }
 
     #[test]
@@ -393,7 +413,7 @@ mod tests {
             );
             assert_eq!(
                 resp.attributes.len(),
-                2,
+                3,
                 "resp.attributes: {:#?}",
                 resp.attributes
             );